@@ -0,0 +1,63 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use levelspecter::{LevelSpec, LevelSpecRef, Interner, parse_many};
+use std::str::FromStr;
+
+// one input per shape the grammar accepts, most common first
+const SHAPES: [(&str, &str); 13] = [
+    ("shot", "DEV01.RD.0001"),
+    ("show", "DEV01"),
+    ("sequence", "DEV01.RD"),
+    ("assetdev_shot", "DEV01.ASSETDEV.FOO1A"),
+    ("wildcard_shot", "DEV01.%.%"),
+    ("rel_shot", "..0001"),
+    ("rel_seq_shot", ".RD.0001"),
+    ("rel_seq_rel", ".RD."),
+    ("rel_seq", ".RD"),
+    ("show_rel_shot", "DEV01.."),
+    ("show_seq_rel", "DEV01.RD."),
+    ("show_rel_seq", "DEV01."),
+    ("rel_only", "..."),
+];
+
+fn parse(c: &mut Criterion) {
+    let mut group = c.benchmark_group("from_str");
+    for (name, input) in SHAPES.iter() {
+        group.bench_function(*name, |b| b.iter(|| LevelSpec::from_str(black_box(input))));
+    }
+    group.bench_function("invalid", |b| b.iter(|| LevelSpec::from_str(black_box("DEV01.RD.00_1"))));
+    group.finish();
+}
+
+fn parse_ref(c: &mut Criterion) {
+    let mut group = c.benchmark_group("parse_ref");
+    for (name, input) in SHAPES.iter() {
+        group.bench_function(*name, |b| b.iter(|| LevelSpecRef::parse(black_box(input))));
+    }
+    group.finish();
+}
+
+// a manifest of shots across a few sequences, with the odd bad line
+fn manifest() -> Vec<String> {
+    (0..10_000)
+        .map(|idx| if idx % 100 == 99 {
+            format!("DEV01.SEQ{}.00_{}", idx % 7, idx)
+        } else {
+            format!("DEV01.SEQ{}A.{:04}", idx % 7, idx)
+        })
+        .collect()
+}
+
+fn bulk(c: &mut Criterion) {
+    let manifest = manifest();
+    let lines = manifest.iter().map(String::as_str).collect::<Vec<_>>();
+    let mut group = c.benchmark_group("bulk");
+    group.bench_function("naive_loop", |b| b.iter(|| {
+        black_box(&lines).iter().map(|line| LevelSpec::from_str(line)).collect::<Vec<_>>()
+    }));
+    group.bench_function("parse_many", |b| b.iter(|| parse_many(black_box(&lines))));
+    group.bench_function("interner_parse_many", |b| b.iter(|| Interner::new().parse_many(black_box(&lines))));
+    group.finish();
+}
+
+criterion_group!(benches, parse, parse_ref, bulk);
+criterion_main!(benches);
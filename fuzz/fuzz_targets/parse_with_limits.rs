@@ -0,0 +1,24 @@
+#![no_main]
+use levelspecter::{is_valid, LevelSpec, ParseLimits};
+use libfuzzer_sys::fuzz_target;
+
+// parse_with_limits must not panic on any input, must respect its limits,
+// and must agree with the unlimited parser on input within them
+fuzz_target!(|data: &[u8]| {
+    let input = match std::str::from_utf8(data) {
+        Ok(input) => input,
+        Err(_) => return,
+    };
+    let limits = ParseLimits::default();
+    match LevelSpec::parse_with_limits(input, &limits) {
+        Ok(levelspec) => {
+            assert!(input.len() <= limits.max_len);
+            assert!(levelspec.levels() <= limits.max_levels);
+            assert!(levelspec.to_string_checked().is_ok());
+        }
+        Err(error) => {
+            assert!(input.len() > limits.max_len || !is_valid(input));
+            let _ = error.to_string();
+        }
+    }
+});
@@ -0,0 +1,60 @@
+/*
+ * C interface to levelspecter, available when the crate is built with the
+ * "ffi" feature:
+ *
+ *     make ffi
+ *
+ * which runs `cargo rustc --release --lib --features ffi` with the cdylib
+ * and staticlib crate types, and linked against target/release/liblevelspecter.{a,so}.
+ *
+ * LevelSpecs are opaque and released with levelspec_free. Strings returned
+ * by the library are released with levelspec_string_free.
+ */
+#ifndef LEVELSPECTER_H
+#define LEVELSPECTER_H
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+typedef struct LevelSpec LevelSpec;
+
+typedef enum LevelSpecStatus {
+    LEVELSPEC_OK = 0,
+    LEVELSPEC_NULL_POINTER = 1,
+    LEVELSPEC_INVALID_UTF8 = 2,
+    LEVELSPEC_PARSE_ERROR = 3,
+    LEVELSPEC_ERROR = 4,
+} LevelSpecStatus;
+
+/* Parse input, storing a new LevelSpec in out, or null on failure */
+LevelSpecStatus levelspec_parse(const char *input, LevelSpec **out);
+
+/* Release a LevelSpec. Null is ignored. */
+void levelspec_free(LevelSpec *levelspec);
+
+/* The LevelSpec as a string, eg DEV01.RD.0001 */
+char *levelspec_to_string(const LevelSpec *levelspec);
+
+/* The levels of the LevelSpec, or null if missing */
+char *levelspec_show(const LevelSpec *levelspec);
+char *levelspec_sequence(const LevelSpec *levelspec);
+char *levelspec_shot(const LevelSpec *levelspec);
+
+/* The number of levels, from 1 to 3, or 0 if levelspec is null */
+int levelspec_depth(const LevelSpec *levelspec);
+
+/* 1 if the LevelSpec has neither wildcards nor relative levels */
+int levelspec_is_concrete(const LevelSpec *levelspec);
+
+/* The most recent failure on the calling thread, or null */
+char *levelspec_last_error(void);
+
+/* Release a string returned by the library. Null is ignored. */
+void levelspec_string_free(char *value);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif
@@ -0,0 +1,145 @@
+use crate::{LevelSpec, LevelType};
+use std::collections::HashMap;
+
+/// Maps legacy show and sequence names onto their canonical names, eg
+/// sequence `RND` to `RD`. An alias written in another case, eg `rnd`,
+/// resolves as well, and adding an alias replaces any which differs from it
+/// only in case. Applied by `LevelSpec::resolve_aliases`, by
+/// `ParseOptions` while parsing, and by `NormalizeRules` while normalizing.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, AliasTable};
+///
+/// let aliases = AliasTable::default()
+///     .with_show_alias("DEVTEST", "DEV01")
+///     .with_sequence_alias("RND", "RD");
+/// let ls = LevelSpec::new("DEVTEST.RND.0001").unwrap();
+/// assert_eq!(ls.resolve_aliases(&aliases), LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct AliasTable {
+    /// Map of show alias to canonical show
+    pub shows: HashMap<String, String>,
+    /// Map of sequence alias to canonical sequence
+    pub sequences: HashMap<String, String>,
+}
+
+impl AliasTable {
+    /// Add a show alias and return self
+    pub fn with_show_alias<I>(mut self, alias: I, canonical: I) -> Self
+    where
+        I: Into<String>
+    {
+        insert(&mut self.shows, alias.into(), canonical.into());
+        self
+    }
+
+    /// Add a sequence alias and return self
+    pub fn with_sequence_alias<I>(mut self, alias: I, canonical: I) -> Self
+    where
+        I: Into<String>
+    {
+        insert(&mut self.sequences, alias.into(), canonical.into());
+        self
+    }
+
+    /// Returns true if the table holds no aliases
+    pub fn is_empty(&self) -> bool {
+        self.shows.is_empty() && self.sequences.is_empty()
+    }
+
+    /// Retrieve the canonical name of a show alias, if `show` is one
+    pub fn show(&self, show: &str) -> Option<&str> {
+        lookup(&self.shows, show).map(String::as_str)
+    }
+
+    /// Retrieve the canonical name of a sequence alias, if `sequence` is one
+    pub fn sequence(&self, sequence: &str) -> Option<&str> {
+        lookup(&self.sequences, sequence).map(String::as_str)
+    }
+
+    /// Replace the show and sequence of the supplied LevelSpec with their
+    /// canonical names. Wildcards and relative levels are left alone.
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to resolve
+    ///
+    /// # Returns
+    /// The LevelSpec with its aliases resolved
+    pub fn resolve(&self, levelspec: &LevelSpec) -> LevelSpec {
+        let mut return_value = levelspec.clone();
+        if let Some(canonical) = term(&return_value.show).and_then(|show| self.show(show)) {
+            return_value.show = LevelType::from(canonical);
+        }
+        let sequence = return_value.sequence.as_ref().and_then(term).and_then(|sequence| self.sequence(sequence));
+        if let Some(canonical) = sequence {
+            return_value.sequence = Some(LevelType::from(canonical));
+        }
+        return_value
+    }
+}
+
+// retrieve the name of a term level
+fn term(level: &LevelType) -> Option<&str> {
+    match level {
+        LevelType::Term(term) => Some(term),
+        _ => None,
+    }
+}
+
+/// Insert the key, replacing any key which differs from it only in ASCII
+/// case, so that case insensitive lookups have a single candidate
+pub(crate) fn insert<V>(map: &mut HashMap<String, V>, key: String, value: V) {
+    map.retain(|existing, _| *existing == key || !existing.eq_ignore_ascii_case(&key));
+    map.insert(key, value);
+}
+
+/// Find the value of the key, preferring an exact match, and otherwise
+/// ignoring ASCII case. Should the public map hold several keys differing
+/// only in case, the least of them is chosen, so the result does not depend
+/// on the map's iteration order.
+pub(crate) fn lookup<'a, V>(map: &'a HashMap<String, V>, key: &str) -> Option<&'a V> {
+    if let Some(value) = map.get(key) {
+        return Some(value);
+    }
+    map.iter()
+        .filter(|(existing, _)| existing.eq_ignore_ascii_case(key))
+        .min_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, value)| value)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn resolves_shows_and_sequences() {
+        let aliases = AliasTable::default().with_show_alias("DEVTEST", "DEV01").with_sequence_alias("RND", "RD");
+        let ls = LevelSpec::from_shot("devtest", "rnd", "0001");
+        assert_eq!(aliases.resolve(&ls), LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert_eq!(aliases.show("DEV01"), None);
+    }
+
+    #[test]
+    fn aliases_differing_in_case_replace_each_other() {
+        let aliases = AliasTable::default().with_sequence_alias("RND", "RD").with_sequence_alias("rnd", "RX");
+        assert_eq!(aliases.sequences.len(), 1);
+        assert_eq!(aliases.sequence("Rnd"), Some("RX"));
+        let mut aliases = AliasTable::default();
+        aliases.sequences.insert("RND".to_string(), "RD".to_string());
+        aliases.sequences.insert("rnd".to_string(), "RX".to_string());
+        assert_eq!(aliases.sequence("Rnd"), Some("RD"));
+        assert_eq!(aliases.sequence("rnd"), Some("RX"));
+    }
+
+    #[test]
+    fn leaves_wildcards_and_relative_levels_alone() {
+        let aliases = AliasTable::default().with_sequence_alias("%", "RD");
+        let ls = LevelSpec::from_str(".%.0001").unwrap();
+        assert_eq!(aliases.resolve(&ls), ls);
+    }
+}
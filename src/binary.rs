@@ -0,0 +1,329 @@
+//! A compact, versioned binary layout for LevelSpecs, so that large indexes
+//! may be snapshotted to disk and loaded, or memory mapped, without parsing
+//! text. All integers are little endian.
+//!
+//! Encoded data starts with a header: `BINARY_MAGIC`, the `BINARY_VERSION`
+//! it was written with, the oldest version able to read it, a kind byte (1
+//! for a LevelSpec, 2 for a snapshot), and a `u16` length followed by that
+//! many bytes of header extensions. A release reads any data whose oldest
+//! readable version is no newer than its own `BINARY_VERSION`, skipping
+//! extensions it does not understand, and rejects the rest, so a release may
+//! add to the layout without breaking older readers, or lock them out when
+//! it must. Version 1 data, which has no header beyond its version byte, is
+//! still read.
+//!
+//! The header is followed by the body. A record is the depth as a `u8`,
+//! followed by each level as a `u16` byte length and its UTF-8 name.
+//! Relative levels have a length of zero, and levels longer than `u16::MAX`
+//! bytes cannot be encoded. `LevelSpec::to_bytes` writes a
+//! single record, and a snapshot is the record count as a `u32` followed by
+//! the records.
+use crate::{LevelSpec, LevelType, LevelSpecterError as LSE};
+use std::convert::TryFrom;
+use std::io::Write;
+use std::str::FromStr;
+
+/// Version of the binary layout. Bumped whenever the layout changes.
+pub const BINARY_VERSION: u8 = 2;
+
+/// Leading bytes of data written by this module
+pub const BINARY_MAGIC: [u8; 4] = *b"LSPC";
+
+/// Leading bytes of a snapshot written by `write_snapshot`. The same as
+/// `BINARY_MAGIC`, which also leads encoded LevelSpecs.
+pub const SNAPSHOT_MAGIC: [u8; 4] = BINARY_MAGIC;
+
+// The first version, whose LevelSpecs are a version byte and a record, and
+// whose snapshots are the magic, the version byte, and the body
+const LEGACY_VERSION: u8 = 1;
+
+// The oldest version able to read the data this release writes
+const MIN_READER_VERSION: u8 = 2;
+
+// Kinds of encoded data, so that one is not mistaken for another
+const KIND_LEVELSPEC: u8 = 1;
+const KIND_SNAPSHOT: u8 = 2;
+
+// Write the header of the supplied kind of data onto the end of `buf`
+fn encode_header(kind: u8, buf: &mut Vec<u8>) {
+    buf.extend_from_slice(&BINARY_MAGIC);
+    buf.extend_from_slice(&[BINARY_VERSION, MIN_READER_VERSION, kind]);
+    buf.extend_from_slice(&0u16.to_le_bytes());
+}
+
+/// Write a LevelSpec's record, without a header, onto the end of `buf`.
+/// Levels longer than `u16::MAX` bytes are a SerializeError.
+pub(crate) fn encode_record(levelspec: &LevelSpec, buf: &mut Vec<u8>) -> Result<(), LSE> {
+    let levels = levelspec.to_vec_str();
+    buf.push(levels.len() as u8);
+    for level in levels {
+        let name = level.to_str();
+        let len = u16::try_from(name.len()).map_err(|_| LSE::SerializeError(format!(
+            "Unable to encode a level of {} bytes; levels are limited to {} bytes", name.len(), u16::MAX
+        )))?;
+        buf.extend_from_slice(&len.to_le_bytes());
+        buf.extend_from_slice(name.as_bytes());
+    }
+    Ok(())
+}
+
+/// Encode a LevelSpec as a header followed by its record
+pub(crate) fn encode(levelspec: &LevelSpec) -> Result<Vec<u8>, LSE> {
+    let mut buf = Vec::new();
+    encode_header(KIND_LEVELSPEC, &mut buf);
+    encode_record(levelspec, &mut buf)?;
+    Ok(buf)
+}
+
+/// Decode the output of `encode`, or of a version 1 `encode`. Trailing
+/// bytes are an error.
+pub(crate) fn decode(bytes: &[u8]) -> Result<LevelSpec, LSE> {
+    let mut reader = Reader::new(bytes);
+    if bytes.first() == Some(&LEGACY_VERSION) {
+        reader.u8()?;
+    } else {
+        reader.header(KIND_LEVELSPEC)?;
+    }
+    let levelspec = reader.record()?;
+    reader.finish()?;
+    Ok(levelspec)
+}
+
+/// Write a snapshot of the LevelSpecs onto the end of `buf`, returning the
+/// number written. On error, `buf` holds a partial snapshot.
+pub(crate) fn encode_snapshot<'a, I>(levelspecs: I, buf: &mut Vec<u8>) -> Result<u32, LSE>
+where
+    I: IntoIterator<Item = &'a LevelSpec>
+{
+    encode_header(KIND_SNAPSHOT, buf);
+    let count_at = buf.len();
+    buf.extend_from_slice(&0u32.to_le_bytes());
+    let mut count = 0u32;
+    for levelspec in levelspecs {
+        encode_record(levelspec, buf)?;
+        count += 1;
+    }
+    buf[count_at..count_at + 4].copy_from_slice(&count.to_le_bytes());
+    Ok(count)
+}
+
+/// Write a snapshot of the supplied LevelSpecs, readable by `read_snapshot`.
+///
+/// # Parameters
+///
+/// * `levelspecs` - The LevelSpecs to write
+/// * `writer` - The destination, eg a BufWriter wrapping a File
+///
+/// # Returns
+/// The number of LevelSpecs written, an IoError, or a SerializeError if a
+/// level is too long to encode
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, binary};
+///
+/// let specs = vec![LevelSpec::from_shot("DEV01", "RD", "0001"), LevelSpec::from_show("DEV02")];
+/// let mut snapshot = Vec::new();
+/// binary::write_snapshot(&specs, &mut snapshot).unwrap();
+/// assert_eq!(binary::read_snapshot(&snapshot).unwrap(), specs);
+/// ```
+pub fn write_snapshot<'a, I, W>(levelspecs: I, mut writer: W) -> Result<usize, LSE>
+where
+    I: IntoIterator<Item = &'a LevelSpec>,
+    W: Write
+{
+    let mut snapshot = Vec::new();
+    let count = encode_snapshot(levelspecs, &mut snapshot)?;
+    writer.write_all(&snapshot)
+        .and_then(|_| writer.flush())
+        .map_err(|e| LSE::IoError(format!("Unable to write snapshot: {}", e)))?;
+    Ok(count as usize)
+}
+
+/// Read a snapshot written by `write_snapshot`. The bytes may come straight
+/// from a memory mapped file. Each level is validated as per
+/// `LevelType::from_str`.
+///
+/// # Parameters
+///
+/// * `bytes` - The snapshot
+///
+/// # Returns
+/// The LevelSpecs in the order written, or a SerializeError if the snapshot
+/// is truncated, requires a newer release to read, or holds invalid levels
+pub fn read_snapshot(bytes: &[u8]) -> Result<Vec<LevelSpec>, LSE> {
+    let mut reader = Reader::new(bytes);
+    reader.header(KIND_SNAPSHOT)?;
+    let count = u32::from_le_bytes([reader.u8()?, reader.u8()?, reader.u8()?, reader.u8()?]);
+    let levelspecs = (0..count).map(|_| reader.record()).collect::<Result<Vec<_>, _>>()?;
+    reader.finish()?;
+    Ok(levelspecs)
+}
+
+// Cursor over encoded bytes
+struct Reader<'a> {
+    bytes: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, pos: 0 }
+    }
+
+    fn take(&mut self, len: usize) -> Result<&'a [u8], LSE> {
+        let end = self.pos + len;
+        let taken = self.bytes.get(self.pos..end)
+            .ok_or_else(|| LSE::SerializeError(format!("Unexpected end of data at byte {}", self.bytes.len())))?;
+        self.pos = end;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, LSE> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, LSE> {
+        Ok(u16::from_le_bytes([self.u8()?, self.u8()?]))
+    }
+
+    // read the header of the expected kind of data, skipping any extensions
+    fn header(&mut self, kind: u8) -> Result<(), LSE> {
+        if self.take(BINARY_MAGIC.len())? != BINARY_MAGIC {
+            return Err(LSE::SerializeError("Not levelspecter binary data".to_string()));
+        }
+        let version = self.u8()?;
+        if version == LEGACY_VERSION && kind == KIND_SNAPSHOT {
+            return Ok(());
+        }
+        if version <= LEGACY_VERSION {
+            return Err(LSE::SerializeError(format!("Unsupported binary version {}", version)));
+        }
+        let min_version = self.u8()?;
+        if min_version > BINARY_VERSION {
+            return Err(LSE::SerializeError(format!(
+                "Data written with binary version {} requires version {} to read; this release reads version {}",
+                version, min_version, BINARY_VERSION
+            )));
+        }
+        match self.u8()? {
+            found if found == kind => (),
+            found => return Err(LSE::SerializeError(format!(
+                "Expected {}, found {}", kind_name(kind), kind_name(found)
+            ))),
+        }
+        let extensions = self.u16()? as usize;
+        self.take(extensions)?;
+        Ok(())
+    }
+
+    fn level(&mut self) -> Result<LevelType, LSE> {
+        let len = self.u16()? as usize;
+        let name = std::str::from_utf8(self.take(len)?)
+            .map_err(|e| LSE::SerializeError(format!("Invalid level name: {}", e)))?;
+        LevelType::from_str(name).map_err(|e| LSE::SerializeError(e.to_string()))
+    }
+
+    fn record(&mut self) -> Result<LevelSpec, LSE> {
+        let depth = self.u8()?;
+        if !(1..=3).contains(&depth) {
+            return Err(LSE::SerializeError(format!("Invalid levelspec depth {}", depth)));
+        }
+        let show = self.level()?;
+        let sequence = if depth > 1 { Some(self.level()?) } else { None };
+        let shot = if depth > 2 { Some(self.level()?) } else { None };
+        Ok(LevelSpec { show, sequence, shot })
+    }
+
+    fn finish(&self) -> Result<(), LSE> {
+        if self.pos == self.bytes.len() {
+            Ok(())
+        } else {
+            Err(LSE::SerializeError(format!("Unexpected trailing data at byte {}", self.pos)))
+        }
+    }
+}
+
+fn kind_name(kind: u8) -> String {
+    match kind {
+        KIND_LEVELSPEC => "an encoded levelspec".to_string(),
+        KIND_SNAPSHOT => "a snapshot".to_string(),
+        kind => format!("unknown data kind {}", kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // the header this release writes for the supplied kind
+    fn header(kind: u8) -> Vec<u8> {
+        let mut bytes = b"LSPC".to_vec();
+        bytes.extend_from_slice(&[2, 2, kind, 0, 0]);
+        bytes
+    }
+
+    #[test]
+    fn layout_is_stable() {
+        let ls = LevelSpec::from_str(".RD.%").unwrap();
+        let mut expect = header(1);
+        expect.extend_from_slice(&[3, 0, 0, 2, 0, b'R', b'D', 1, 0, b'%']);
+        assert_eq!(encode(&ls), Ok(expect));
+        assert_eq!(decode(&encode(&ls).unwrap()), Ok(ls));
+    }
+
+    #[test]
+    fn reads_version_one() {
+        let ls = LevelSpec::from_str(".RD.%").unwrap();
+        assert_eq!(decode(&[1, 3, 0, 0, 2, 0, b'R', b'D', 1, 0, b'%']), Ok(ls.clone()));
+        let snapshot = [&b"LSPC"[..], &[1, 1, 0, 0, 0, 3, 0, 0, 2, 0, b'R', b'D', 1, 0, b'%']].concat();
+        assert_eq!(read_snapshot(&snapshot), Ok(vec![ls]));
+    }
+
+    #[test]
+    fn reads_newer_versions_which_permit_it() {
+        // version 3 data, readable by version 2, with a header extension
+        let mut bytes = b"LSPC".to_vec();
+        bytes.extend_from_slice(&[3, 2, 1, 2, 0, 0xAB, 0xCD, 1, 2, 0, b'D', b'1']);
+        assert_eq!(decode(&bytes), Ok(LevelSpec::from_show("D1")));
+        bytes[5] = 3;
+        assert_eq!(
+            decode(&bytes),
+            Err(LSE::SerializeError("Data written with binary version 3 requires version 3 to read; this release reads version 2".to_string()))
+        );
+    }
+
+    #[test]
+    fn rejects_other_kinds_and_versions() {
+        let specs = vec![LevelSpec::from_show("DEV01")];
+        let mut snapshot = Vec::new();
+        encode_snapshot(&specs, &mut snapshot).unwrap();
+        assert!(decode(&snapshot).is_err());
+        assert!(read_snapshot(&encode(&specs[0]).unwrap()).is_err());
+        let mut bytes = encode(&specs[0]).unwrap();
+        bytes[4] = 0;
+        assert!(decode(&bytes).is_err());
+    }
+
+    #[test]
+    fn rejects_truncated_and_invalid_data() {
+        let bytes = encode(&LevelSpec::from_sequence("DEV01", "RD")).unwrap();
+        assert!(decode(&bytes[..bytes.len() - 1]).is_err());
+        assert!(decode(&[1, 1, 3, 0, b'A', b'-', b'B']).is_err());
+        assert!(decode(&[1, 4]).is_err());
+        assert!(read_snapshot(b"NOPE").is_err());
+    }
+
+    #[test]
+    fn rejects_levels_too_long_to_encode() {
+        let longest = LevelSpec::from_shot("DEV01", "RD", &"1".repeat(u16::MAX as usize));
+        assert_eq!(decode(&encode(&longest).unwrap()), Ok(longest));
+        let ls = LevelSpec::from_shot("DEV01", "RD", &"1".repeat(u16::MAX as usize + 1));
+        assert_eq!(
+            encode(&ls),
+            Err(LSE::SerializeError("Unable to encode a level of 65536 bytes; levels are limited to 65535 bytes".to_string()))
+        );
+        assert!(write_snapshot(&[ls], Vec::new()).is_err());
+    }
+}
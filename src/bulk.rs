@@ -0,0 +1,160 @@
+use crate::{LevelSpec, LevelSpecRef, LevelSpecterError as LSE};
+use failure::Fail;
+#[cfg(any(feature = "rayon", feature = "async"))]
+use std::str::FromStr;
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+#[cfg(feature = "async")]
+use futures::stream::{Stream, StreamExt};
+
+/// An error parsing one line of a bulk input, recording the 1-based line
+/// number along with the underlying error
+#[derive(Debug, Fail, PartialEq, Eq, Clone)]
+#[fail(display = "line {}: {}", line, error)]
+pub struct LineError {
+    pub line: usize,
+    pub error: LSE,
+}
+
+// parse a single line, attaching its position to any error
+#[cfg(any(feature = "rayon", feature = "async"))]
+fn parse_line(idx: usize, line: &str) -> Result<LevelSpec, LineError> {
+    LevelSpec::from_str(line).map_err(|error| LineError { line: idx + 1, error })
+}
+
+/// Parse many LevelSpecs on the current thread. Equivalent to calling
+/// `LevelSpec::from_str` on each line, but sized for manifests of millions
+/// of lines: the results are allocated once up front, each line is parsed
+/// through the borrowed parser, and rejected lines allocate nothing beyond
+/// their LineError. Use `Interner::parse_many` to also share repeated
+/// shows and sequences between the results.
+/// 
+/// # Parameters
+/// 
+/// * `lines` - The strs to parse, one LevelSpec each
+/// 
+/// # Returns
+/// A Vec of results in input order. Errors carry the 1-based line number of
+/// the input which failed to parse.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, parse_many};
+/// 
+/// let results = parse_many(&["DEV01.RD.0001", "DEV01..RD", "DEV01.RD"]);
+/// assert_eq!(results[0], Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+/// assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+/// assert!(results[2].is_ok());
+/// ```
+pub fn parse_many(lines: &[&str]) -> Vec<Result<LevelSpec, LineError>> {
+    let mut results = Vec::with_capacity(lines.len());
+    results.extend(lines.iter().enumerate().map(|(idx, line)| {
+        LevelSpecRef::parse(line)
+            .map(|levelspec| levelspec.to_levelspec())
+            .map_err(|error| LineError { line: idx + 1, error })
+    }));
+    results
+}
+
+/// Parse many LevelSpecs in parallel. Available with the `rayon` feature.
+/// 
+/// # Parameters
+/// 
+/// * `lines` - The strs to parse, one LevelSpec each
+/// 
+/// # Returns
+/// A Vec of results in input order. Errors carry the 1-based line number of
+/// the input which failed to parse.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, parse_many_parallel};
+/// 
+/// let results = parse_many_parallel(&["DEV01.RD.0001", "DEV01..RD", "DEV01.RD"]);
+/// assert_eq!(results[0], Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+/// assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+/// assert!(results[2].is_ok());
+/// ```
+#[cfg(feature = "rayon")]
+pub fn parse_many_parallel(lines: &[&str]) -> Vec<Result<LevelSpec, LineError>> {
+    lines.par_iter()
+        .enumerate()
+        .map(|(idx, line)| parse_line(idx, line))
+        .collect()
+}
+
+/// Adapt a stream of lines, such as those read from a socket, into a stream
+/// of parsed LevelSpecs. Available with the `async` feature.
+/// 
+/// # Parameters
+/// 
+/// * `lines` - A stream of Strings, one LevelSpec each
+/// 
+/// # Returns
+/// A stream of results in input order. Errors carry the 1-based line number
+/// of the input which failed to parse, and do not end the stream.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, parse_stream};
+/// use futures::{executor::block_on, stream::{self, StreamExt}};
+/// 
+/// let lines = stream::iter(vec!["DEV01.RD.0001".to_string(), "DEV01..RD".to_string()]);
+/// let results = block_on(parse_stream(lines).collect::<Vec<_>>());
+/// assert_eq!(results[0], Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+/// assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+/// ```
+#[cfg(feature = "async")]
+pub fn parse_stream<S>(lines: S) -> impl Stream<Item = Result<LevelSpec, LineError>>
+where
+    S: Stream<Item = String>
+{
+    lines.enumerate().map(|(idx, line)| parse_line(idx, &line))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_many_matches_from_str() {
+        let lines = ["DEV01.RD.0001", "bad_line", "", ".RD.", "DEV01.ASSETDEV.FOO1A"];
+        let results = parse_many(&lines);
+        assert_eq!(results.len(), lines.len());
+        for (idx, (line, result)) in lines.iter().zip(results).enumerate() {
+            let expect = line.parse::<LevelSpec>().map_err(|error| LineError { line: idx + 1, error });
+            assert_eq!(result, expect);
+        }
+    }
+
+    #[test]
+    fn line_error_displays_position() {
+        let error = LineError { line: 5, error: LSE::NoLevels };
+        assert_eq!(error.to_string(), "line 5: No levels supplied");
+    }
+
+    #[cfg(feature = "async")]
+    #[test]
+    fn stream_continues_past_errors() {
+        use futures::{executor::block_on, stream};
+        let lines = stream::iter(vec!["bad_line".to_string(), "DEV01".to_string(), "".to_string(), "DEV01.RD".to_string()]);
+        let results = block_on(parse_stream(lines).collect::<Vec<_>>());
+        let lines_ok = results.iter().map(Result::is_ok).collect::<Vec<_>>();
+        assert_eq!(lines_ok, vec![false, true, false, true]);
+        assert_eq!(results[2].as_ref().unwrap_err().line, 3);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn preserves_input_order() {
+        let lines = (0..1000).map(|shot| format!("DEV01.RD.{:04}", shot)).collect::<Vec<_>>();
+        let lines = lines.iter().map(String::as_str).collect::<Vec<_>>();
+        let results = parse_many_parallel(&lines);
+        for (idx, result) in results.into_iter().enumerate() {
+            assert_eq!(result.unwrap().shot_number(), Some(idx as u32));
+        }
+    }
+}
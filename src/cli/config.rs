@@ -0,0 +1,111 @@
+//! Applies the user and site config files to the parsed command line. A 
+//! setting from the config files is used wherever the corresponding flag was
+//! not supplied on the command line.
+use super::{Cli, Command, FsOpts};
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use levelspecter::config::Config;
+
+/// The name of the path template in the config files used as the default
+/// `--template`
+pub const TEMPLATE_NAME: &str = "shot";
+
+/// Apply the config to the options of `cli` which were not supplied on the
+/// command line
+///
+/// # Parameters
+///
+/// * `cli` - The parsed command line
+/// * `matches` - The matches `cli` was built from, recording which flags were supplied
+/// * `config` - The loaded config
+pub fn apply(cli: &mut Cli, matches: &ArgMatches, config: &Config) {
+    let subcommand = matches.subcommand().map(|(_, matches)| matches);
+    let explicit = |id: &str| {
+        Some(matches).into_iter().chain(subcommand)
+            .filter(|matches| matches.ids().any(|present| present == id))
+            .any(|matches| matches.value_source(id) == Some(ValueSource::CommandLine))
+    };
+
+    let global = &mut cli.global;
+    if !explicit("case") {
+        global.case = config.format_options.case.into();
+    }
+    if !explicit("show_var") {
+        global.show_var = config.env_vars.show.clone();
+    }
+    if !explicit("sequence_var") {
+        global.sequence_var = config.env_vars.sequence.clone();
+    }
+    if !explicit("shot_var") {
+        global.shot_var = config.env_vars.shot.clone();
+    }
+    global.defaults.parse_options = config.parse_options.clone();
+    global.defaults.format_options = config.format_options.clone();
+    if !config.sources.is_empty() {
+        global.defaults.schema = Some(config.schema.clone());
+    }
+
+    let template = config.templates.get(TEMPLATE_NAME);
+    if let (Some(fs), Some(template)) = (fs_mut(&mut cli.command), template) {
+        if !explicit("template") {
+            fs.template = template.template().to_string();
+        }
+    }
+}
+
+// the filesystem options of the subcommands which have them
+fn fs_mut(command: &mut Command) -> Option<&mut FsOpts> {
+    match command {
+        Command::Expand(args) => Some(&mut args.fs),
+        Command::Ls(args) => Some(&mut args.fs),
+        Command::Path(args) => Some(&mut args.fs),
+        Command::FromPaths(args) => Some(&mut args.fs),
+        Command::Repl(args) => Some(&mut args.fs),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::CaseArg;
+    use clap::{CommandFactory, FromArgMatches};
+    use levelspecter::Case;
+
+    fn parse(args: &[&str], config: &Config) -> Cli {
+        let matches = Cli::command().try_get_matches_from(args).unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        apply(&mut cli, &matches, config);
+        cli
+    }
+
+    #[test]
+    fn command_line_overrides_config() {
+        let config = Config::from_toml_str(r#"
+            [format]
+            case = "lower"
+            separator = "_"
+
+            [templates]
+            shot = "{show}/shots/{sequence}/{shot}"
+
+            [env]
+            show = "JOB"
+        "#).unwrap();
+        let cli = parse(&["levelspecter", "path", "DEV01"], &config);
+        assert_eq!((cli.global.case, cli.global.show_var.as_str()), (CaseArg::Lower, "JOB"));
+        assert_eq!(cli.global.format_options().separator, "_");
+        match cli.command {
+            Command::Path(ref args) => assert_eq!(args.fs.template, "{show}/shots/{sequence}/{shot}"),
+            _ => panic!("expected path"),
+        }
+
+        let cli = parse(&["levelspecter", "path", "--case", "upper", "--show-var", "SHOW", "--template", "{show}", "DEV01"], &config);
+        assert_eq!((cli.global.case, cli.global.show_var.as_str()), (CaseArg::Upper, "SHOW"));
+        assert_eq!(cli.global.format_options().case, Case::Upper);
+        match cli.command {
+            Command::Path(ref args) => assert_eq!(args.fs.template, "{show}"),
+            _ => panic!("expected path"),
+        }
+    }
+}
@@ -0,0 +1,97 @@
+use super::{GlobalOpts, exitcode};
+use clap::ValueEnum;
+use levelspecter::{EnvVars, LevelName, LevelSpec, LevelType};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The shell whose syntax is printed
+    #[arg(long, value_enum, default_value_t = Shell::Sh)]
+    pub shell: Shell,
+
+    /// The levelspec describing the work context
+    pub levelspec: String,
+}
+
+/// The shell dialects env may print
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    /// sh, bash, zsh, and other POSIX shells
+    Sh,
+    Csh,
+    Fish,
+}
+
+impl Shell {
+    /// The command setting a variable
+    pub fn set(self, var: &str, value: &str) -> String {
+        match self {
+            Shell::Sh => format!("export {}={};", var, value),
+            Shell::Csh => format!("setenv {} {};", var, value),
+            Shell::Fish => format!("set -gx {} {};", var, value),
+        }
+    }
+
+    /// The command removing a variable
+    pub fn unset(self, var: &str) -> String {
+        match self {
+            Shell::Sh => format!("unset {};", var),
+            Shell::Csh => format!("unsetenv {};", var),
+            Shell::Fish => format!("set -e {};", var),
+        }
+    }
+}
+
+/// Print shell commands setting the show, sequence, and shot variables to 
+/// the levels of the levelspec, for use with `eval`. Variables for missing 
+/// levels are unset, so that a sequence does not inherit a stale shot. The
+/// exit code is NOT_CONCRETE if the levelspec has wildcards or unresolved
+/// relative levels.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let levelspec = match global.parse(&args.levelspec) {
+        Ok(levelspec) => levelspec,
+        Err(e) => {
+            global.report(&output.error(&args.levelspec, None, &e));
+            return exitcode::for_error(&e);
+        }
+    };
+    if !levelspec.is_concrete() || levelspec.has_relative() {
+        global.report(&format!("{} is not concrete", levelspec));
+        return exitcode::NOT_CONCRETE;
+    }
+    for line in commands(&levelspec, &global.env_vars(), args.shell, |name, level| output.options.format_level(name, level)) {
+        global.print(&line);
+    }
+    exitcode::SUCCESS
+}
+
+// the commands setting or unsetting each variable, in level order
+fn commands<F>(levelspec: &LevelSpec, vars: &EnvVars, shell: Shell, format: F) -> Vec<String>
+where
+    F: Fn(LevelName, &LevelType) -> String
+{
+    let levels = [
+        (LevelName::Show, Some(&levelspec.show)),
+        (LevelName::Sequence, levelspec.sequence.as_ref()),
+        (LevelName::Shot, levelspec.shot.as_ref()),
+    ];
+    levels.iter().map(|(name, level)| match level {
+        Some(level) => shell.set(vars.var(*name), &format(*name, level)),
+        None => shell.unset(vars.var(*name)),
+    }).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn prints_exports_and_unsets_missing_levels() {
+        let levelspec = LevelSpec::from_sequence("DEV01", "RD");
+        let lines = commands(&levelspec, &EnvVars::default(), Shell::Sh, |_, level| level.to_string());
+        assert_eq!(lines, vec!["export DD_SHOW=DEV01;", "export DD_SEQUENCE=RD;", "unset DD_SHOT;"]);
+        let vars = EnvVars::new("JOB", "SEQ", "SHOT");
+        let lines = commands(&levelspec, &vars, Shell::Csh, |_, level| level.to_string());
+        assert_eq!(lines, vec!["setenv JOB DEV01;", "setenv SEQ RD;", "unsetenv SHOT;"]);
+    }
+}
@@ -0,0 +1,50 @@
+//! The exit codes returned by the levelspecter binary. When several inputs
+//! fail, the highest code is returned.
+use levelspecter::LevelSpecterError as LSE;
+
+/// Every input parsed, and every requested check passed
+pub const SUCCESS: i32 = 0;
+/// Nothing matched, or the requested levelspec does not exist
+pub const NO_MATCH: i32 = 1;
+/// An input failed to parse
+pub const PARSE_ERROR: i32 = 2;
+/// An input parsed, but is not concrete
+pub const NOT_CONCRETE: i32 = 3;
+/// A relative level could not be resolved
+pub const RESOLVE_ERROR: i32 = 4;
+/// A file, directory, or stdin could not be read
+pub const IO_ERROR: i32 = 5;
+/// A template or configuration is invalid, or a path does not match it
+pub const CONFIG_ERROR: i32 = 6;
+/// An input breaks the naming rules of a schema (`validate --schema`)
+pub const SCHEMA_VIOLATION: i32 = 7;
+/// The command line is malformed
+pub const USAGE: i32 = 64;
+
+/// The exit code reporting the supplied error
+pub fn for_error(error: &LSE) -> i32 {
+    match error {
+        LSE::ParseError(_)
+        | LSE::NoLevels
+        | LSE::TooManyLevels(_)
+        | LSE::LevelOrderError(_)
+        | LSE::ShotError(_)
+        | LSE::QueryError(_) => PARSE_ERROR,
+        LSE::UnknownShow(_) => NO_MATCH,
+        LSE::RelToAbsError(_) | LSE::EnvError(_) => RESOLVE_ERROR,
+        LSE::IoError(_) | LSE::WatchError(_) | LSE::CsvError(_) | LSE::SerializeError(_) => IO_ERROR,
+        LSE::PathTemplateError(_) | LSE::FormatError(_) | LSE::ConfigError(_) | LSE::RuleError(_) | LSE::Placeholder => CONFIG_ERROR,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn maps_errors_to_codes() {
+        assert_eq!(for_error(&LSE::NoLevels), PARSE_ERROR);
+        assert_eq!(for_error(&LSE::RelToAbsError("x".to_string())), RESOLVE_ERROR);
+        assert_eq!(for_error(&LSE::PathTemplateError("x".to_string())), CONFIG_ERROR);
+    }
+}
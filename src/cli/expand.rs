@@ -0,0 +1,57 @@
+use super::{GlobalOpts, FsOpts, exitcode};
+use levelspecter::ShotIndex;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The pattern to expand, eg `DEV01.%.%`
+    pub pattern: String,
+
+    #[command(flatten)]
+    pub fs: FsOpts,
+}
+
+/// Print the levelspecs on disk matching the pattern, in order. The exit
+/// code is SUCCESS if anything matched, and NO_MATCH if nothing did.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let pattern = match global.parse(&args.pattern) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            global.report(&output.error(&args.pattern, None, &e));
+            return exitcode::for_error(&e);
+        }
+    };
+    let index = match args.fs.template().and_then(|template| ShotIndex::scan(args.fs.root()?, &template)) {
+        Ok(index) => index,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let specs = index.expand(&pattern);
+    for levelspec in &specs {
+        global.print(&output.spec(&levelspec.to_string(), levelspec));
+    }
+    if specs.is_empty() {exitcode::NO_MATCH} else {exitcode::SUCCESS}
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use levelspecter::LevelSpec;
+    use std::fs;
+
+    #[test]
+    fn expands_against_directories_beneath_root() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in &["DEV01/RD/0001", "DEV01/RD/0002", "DEV01/AA/0001"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        let fs_opts = FsOpts { root: root.path().to_path_buf(), template: "{show}/{sequence}/{shot}".to_string() };
+        let index = ShotIndex::scan(&fs_opts.root, &fs_opts.template().unwrap()).unwrap();
+        assert_eq!(index.expand(&LevelSpec::new("DEV01.%.0001").unwrap()), vec![
+            LevelSpec::from_shot("DEV01", "AA", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+        ]);
+    }
+}
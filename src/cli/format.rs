@@ -0,0 +1,68 @@
+use super::{GlobalOpts, exitcode};
+use levelspecter::FormatOptions;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// String placed between levels. Defaults to `.`
+    #[arg(short, long)]
+    pub separator: Option<String>,
+
+    /// Minimum width numeric shots are zero padded to
+    #[arg(short = 'p', long)]
+    pub shot_padding: Option<usize>,
+
+    /// Marker printed for relative levels
+    #[arg(short, long)]
+    pub relative_marker: Option<String>,
+
+    /// The levelspecs to format
+    #[arg(required = true)]
+    pub levelspecs: Vec<String>,
+}
+
+impl Args {
+    /// The FormatOptions described by the arguments
+    pub fn format_options(&self, global: &GlobalOpts) -> FormatOptions {
+        let mut options = global.format_options();
+        if let Some(ref separator) = self.separator {
+            options.separator = separator.clone();
+        }
+        options.shot_padding = self.shot_padding.or(options.shot_padding);
+        options.relative_marker = self.relative_marker.clone().or(options.relative_marker);
+        options
+    }
+}
+
+/// Print each levelspec using the requested FormatOptions
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output().with_options(args.format_options(global));
+    let mut code = exitcode::SUCCESS;
+    for input in &args.levelspecs {
+        match global.parse(input) {
+            Ok(levelspec) => global.print(&output.spec(input, &levelspec)),
+            Err(e) => {
+                global.report(&output.error(input, None, &e));
+                code = code.max(exitcode::for_error(&e));
+            }
+        }
+    }
+    code
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+    use levelspecter::LevelSpec;
+
+    #[test]
+    fn builds_format_options_from_arguments() {
+        let cli = Cli::try_parse_from(&["levelspecter", "--case", "lower", "format", "-s", "_", "-p", "4", "DEV01.RD.1"]).unwrap();
+        let args = match cli.command {
+            Command::Format(ref args) => args,
+            _ => panic!("expected format"),
+        };
+        let ls = LevelSpec::new("DEV01.RD.1").unwrap();
+        assert_eq!(ls.format_with(&args.format_options(&cli.global)), "dev01_rd_0001");
+    }
+}
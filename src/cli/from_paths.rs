@@ -0,0 +1,55 @@
+use super::{GlobalOpts, FsOpts, inputs, exitcode, path::absolute};
+use levelspecter::PathSchema;
+use std::collections::HashSet;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Print each levelspec once, the first time a path within it is seen
+    #[arg(long)]
+    pub unique: bool,
+
+    /// The paths to inspect. `-` reads one per line from stdin, such as the
+    /// output of `find`.
+    #[arg(required = true)]
+    pub paths: Vec<String>,
+
+    #[command(flatten)]
+    pub fs: FsOpts,
+}
+
+/// Print the levelspec each path belongs to. Paths below a shot directory
+/// resolve to the shot. The exit code is CONFIG_ERROR if a path lies 
+/// outside the template.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let schema = match args.fs.template() {
+        Ok(template) => PathSchema::new().with_template(template),
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let mut seen = HashSet::new();
+    let mut code = exitcode::SUCCESS;
+    for input in inputs(&args.paths) {
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                global.report(&format!("Unable to read stdin: {}", e));
+                return exitcode::IO_ERROR;
+            }
+        };
+        match absolute(&input.text).and_then(|path| schema.levelspec_for(path)) {
+            Ok(levelspec) => {
+                if !args.unique || seen.insert(levelspec.clone()) {
+                    global.print(&output.spec(&input.text, &levelspec));
+                }
+            }
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+            }
+        }
+    }
+    code
+}
@@ -0,0 +1,105 @@
+use super::{GlobalOpts, exitcode};
+use levelspecter::LevelSpec;
+use std::collections::BTreeSet;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Number of shot levelspecs to generate
+    #[arg(short, long, default_value_t = 100)]
+    pub count: usize,
+
+    /// Number of shows the shots are spread across
+    #[arg(long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..=10_000))]
+    pub shows: u32,
+
+    /// Number of sequences within each show
+    #[arg(long, default_value_t = 10, value_parser = clap::value_parser!(u32).range(1..=676))]
+    pub seqs: u32,
+
+    /// Seed for the generator. The same seed always produces the same output.
+    #[arg(long, default_value_t = 0)]
+    pub seed: u64,
+}
+
+/// Print randomly generated, valid shot levelspecs, for load testing 
+/// downstream tools and benchmarking the parser
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    for levelspec in generate(&args) {
+        global.print(&output.spec(&levelspec.to_string(), &levelspec));
+    }
+    exitcode::SUCCESS
+}
+
+// generate the levelspecs described by the arguments
+fn generate(args: &Args) -> Vec<LevelSpec> {
+    let mut rng = SplitMix64(args.seed);
+    // shows are three letters followed by two digits, eg ABC01
+    let shows = unique(&mut rng, args.shows as usize, |rng| {
+        format!("{}{}{}{:02}", rng.letter(), rng.letter(), rng.letter(), rng.below(100))
+    });
+    let sequences = shows.iter().map(|_| {
+        unique(&mut rng, args.seqs as usize, |rng| format!("{}{}", rng.letter(), rng.letter()))
+    }).collect::<Vec<_>>();
+    (0..args.count).map(|_| {
+        let show = rng.below(shows.len() as u64) as usize;
+        let sequence = &sequences[show][rng.below(args.seqs as u64) as usize];
+        let shot = format!("{:04}", (rng.below(999) + 1) * 10);
+        LevelSpec::from_shot(shows[show].as_str(), sequence.as_str(), shot.as_str())
+    }).collect()
+}
+
+// draw names until `count` distinct names have been produced
+fn unique<F>(rng: &mut SplitMix64, count: usize, name: F) -> Vec<String>
+where
+    F: Fn(&mut SplitMix64) -> String
+{
+    let mut names = BTreeSet::new();
+    while names.len() < count {
+        names.insert(name(rng));
+    }
+    names.into_iter().collect()
+}
+
+// A small, seedable pseudo random number generator. Its output is fixed by
+// the algorithm, so a seed generates the same levelspecs in every release.
+struct SplitMix64(u64);
+
+impl SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // a number in 0..bound
+    fn below(&mut self, bound: u64) -> u64 {
+        self.next_u64() % bound
+    }
+
+    // an uppercase ascii letter
+    fn letter(&mut self) -> char {
+        (b'A' + self.below(26) as u8) as char
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generates_valid_reproducible_specs() {
+        let args = Args { count: 200, shows: 3, seqs: 5, seed: 42 };
+        let specs = generate(&args);
+        assert_eq!(specs.len(), 200);
+        assert_eq!(specs, generate(&args));
+        for spec in &specs {
+            assert_eq!(LevelSpec::new(&spec.to_string()).as_ref(), Ok(spec));
+        }
+        let shows = specs.iter().map(|spec| spec.show().to_string()).collect::<BTreeSet<_>>();
+        assert!(shows.len() <= 3);
+        assert_ne!(specs, generate(&Args { seed: 7, ..args }));
+    }
+}
@@ -0,0 +1,116 @@
+use super::{GlobalOpts, FsOpts, source_inputs, exitcode};
+use levelspecter::{LevelSpecterError as LSE, LevelResolver, MapResolver, ShotIndex};
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The levelspec whose children are listed. Lists the shows if omitted.
+    /// Wildcards list the children of every match.
+    pub levelspec: Option<String>,
+
+    /// Print the depth and concreteness of each child, and its path when
+    /// listing the filesystem
+    #[arg(short)]
+    pub long: bool,
+
+    /// Read the known levelspecs from a file, one per line, rather than the
+    /// filesystem. `-` reads stdin.
+    #[arg(long, value_name = "FILE")]
+    pub from: Option<PathBuf>,
+
+    #[command(flatten)]
+    pub fs: FsOpts,
+}
+
+/// List the children of a levelspec, in order. The exit code is NO_MATCH 
+/// if the levelspec does not exist.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let parent = match args.levelspec.as_ref().map(|input| global.parse(input)).transpose() {
+        Ok(parent) => parent,
+        Err(e) => {
+            global.report(&output.error(args.levelspec.as_deref().unwrap_or_default(), None, &e));
+            return exitcode::for_error(&e);
+        }
+    };
+    let resolver = match resolver(&args, global) {
+        Ok(resolver) => resolver,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let children = match parent {
+        Some(parent) => {
+            let parents = parent.expand_with(&*resolver).unwrap_or_default();
+            if parents.is_empty() {
+                global.report(&format!("{} not found", parent));
+                return exitcode::NO_MATCH;
+            }
+            parents.iter().flat_map(|parent| resolver.children(parent)).collect::<Vec<_>>()
+        },
+        None => resolver.shows(),
+    };
+    let template = if args.long && args.from.is_none() { args.fs.template().ok() } else { None };
+    for child in &children {
+        let spec = output.spec(&child.to_string(), child);
+        if !args.long {
+            global.print(&spec);
+            continue;
+        }
+        let concrete = if child.is_concrete() && !child.has_relative() {"concrete"} else {"pattern"};
+        match template {
+            Some(ref template) => {
+                let path = child.to_path_with(template, &global.defaults.parse_options.reserved_sequences).map(|path| path.display().to_string()).unwrap_or_default();
+                global.print(&format!("{}\t{}\t{}\t{}", child.levels(), concrete, spec, path))
+            },
+            None => global.print(&format!("{}\t{}\t{}", child.levels(), concrete, spec)),
+        }
+    }
+    exitcode::SUCCESS
+}
+
+// build the resolver listing the levels, either from a list of levelspecs
+// or by scanning the filesystem
+fn resolver(args: &Args, global: &GlobalOpts) -> Result<Box<dyn LevelResolver>, LSE> {
+    let path = match args.from {
+        Some(ref path) => path,
+        None => return Ok(Box::new(ShotIndex::scan(args.fs.root()?, &args.fs.template()?)?)),
+    };
+    let lines = source_inputs(path)?;
+    let mut resolver = MapResolver::new();
+    for input in lines {
+        match global.parse(&input.text) {
+            Ok(levelspec) => { resolver.insert(levelspec); },
+            Err(e) => global.report(&global.output().error(&input.text, input.line, &e)),
+        }
+    }
+    Ok(Box::new(resolver))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+    use levelspecter::LevelSpec;
+    use std::io::Write;
+
+    #[test]
+    fn lists_levels_read_from_a_file() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "DEV01.RD.0001\nDEV01.RD.0002\n\nDEV01.AA.0001").unwrap();
+        let path = file.path().to_str().unwrap();
+        let cli = Cli::try_parse_from(&["levelspecter", "ls", "--from", path, "DEV01"]).unwrap();
+        let args = match cli.command {
+            Command::Ls(ref args) => args,
+            _ => panic!("expected ls"),
+        };
+        let resolver = resolver(args, &cli.global).unwrap();
+        assert_eq!(resolver.shows(), vec![LevelSpec::from_show("DEV01")]);
+        assert_eq!(resolver.children(&LevelSpec::from_show("DEV01")), vec![
+            LevelSpec::from_sequence("DEV01", "RD"),
+            LevelSpec::from_sequence("DEV01", "AA"),
+        ]);
+    }
+}
@@ -0,0 +1,44 @@
+use super::{GlobalOpts, inputs, exitcode};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The pattern to match, eg `DEV01.%.0001`
+    pub pattern: String,
+
+    /// The levelspecs to test. Reads one per line from stdin if omitted or `-`
+    pub levelspecs: Vec<String>,
+}
+
+/// Print the levelspecs matching the pattern. The exit code is SUCCESS if 
+/// anything matched, and NO_MATCH if nothing did. Levelspecs which fail to
+/// parse are reported on stderr and skipped.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let pattern = match global.parse(&args.pattern) {
+        Ok(pattern) => pattern,
+        Err(e) => {
+            global.report(&output.error(&args.pattern, None, &e));
+            return exitcode::for_error(&e);
+        }
+    };
+    let levelspecs = if args.levelspecs.is_empty() { vec!["-".to_string()] } else { args.levelspecs };
+    let mut matched = false;
+    for input in inputs(&levelspecs) {
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                global.report(&format!("Unable to read stdin: {}", e));
+                return exitcode::IO_ERROR;
+            }
+        };
+        match global.parse(&input.text) {
+            Ok(levelspec) if pattern.matches(&levelspec) => {
+                matched = true;
+                global.print(&output.spec(&input.text, &levelspec));
+            }
+            Ok(_) => (),
+            Err(e) => global.report(&output.error(&input.text, input.line, &e)),
+        }
+    }
+    if matched {exitcode::SUCCESS} else {exitcode::NO_MATCH}
+}
@@ -0,0 +1,352 @@
+//! The levelspecter command line interface. Each subcommand lives in its 
+//! own module, exposing an `Args` struct and a `run` function returning
+//! the process exit code. See `exitcode` for the codes returned.
+use clap::{ArgMatches, Parser, Subcommand};
+use levelspecter::{LevelSpec, LevelSpecterError as LSE, ParseOptions, FormatOptions, LevelSchema, EnvVars, EnvResolver, PathTemplate, FormatTemplate};
+use levelspecter::envvars::{DEFAULT_SHOW_VAR, DEFAULT_SEQUENCE_VAR, DEFAULT_SHOT_VAR};
+use std::fs::File;
+use std::io::{self, BufRead};
+use std::path::{Path, PathBuf};
+
+pub mod exitcode;
+#[cfg(feature = "config")]
+pub mod config;
+
+mod output;
+pub use output::{Output, OutputFormat, CaseArg};
+
+mod parse;
+mod validate;
+mod format;
+mod from_paths;
+mod gen;
+mod matches;
+mod env;
+mod expand;
+mod ls;
+mod normalize;
+mod path;
+mod range;
+mod repl;
+mod sort;
+mod tree;
+
+/// Parse, validate, and format levelspecs
+#[derive(Debug, Parser)]
+#[command(name = "levelspecter", version)]
+pub struct Cli {
+    #[command(flatten)]
+    pub global: GlobalOpts,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+/// Options shared by every subcommand
+#[derive(Debug, clap::Args)]
+pub struct GlobalOpts {
+    /// Case conversion applied to levels when printing
+    #[arg(long, value_enum, default_value_t = CaseArg::Preserve, global = true)]
+    pub case: CaseArg,
+
+    /// Output format
+    #[arg(short, long, value_enum, default_value_t = OutputFormat::Text, global = true)]
+    pub output: OutputFormat,
+
+    /// Template levelspecs are rendered with in text output, eg `{show}/{sequence}_{shot}`
+    #[arg(long, value_name = "TEMPLATE", value_parser = parse_format_template, global = true)]
+    pub format: Option<FormatTemplate>,
+
+    /// Print nothing, reporting results through the exit code alone
+    #[arg(short, long, global = true)]
+    pub quiet: bool,
+
+    /// Convert input to uppercase before parsing
+    #[arg(short, long, global = true)]
+    pub uppercase: bool,
+
+    /// Resolve relative levels from environment variables
+    #[arg(short = 'e', long, global = true)]
+    pub resolve_env: bool,
+
+    /// Environment variable holding the show
+    #[arg(long, value_name = "VAR", default_value = DEFAULT_SHOW_VAR, global = true)]
+    pub show_var: String,
+
+    /// Environment variable holding the sequence
+    #[arg(long, value_name = "VAR", default_value = DEFAULT_SEQUENCE_VAR, global = true)]
+    pub sequence_var: String,
+
+    /// Environment variable holding the shot
+    #[arg(long, value_name = "VAR", default_value = DEFAULT_SHOT_VAR, global = true)]
+    pub shot_var: String,
+
+    /// Ignore the user and site config files
+    #[cfg(feature = "config")]
+    #[arg(long, global = true)]
+    pub no_config: bool,
+
+    /// Defaults loaded from the config files
+    #[arg(skip)]
+    pub defaults: Defaults,
+}
+
+/// Defaults which have no command line flag of their own, loaded from the
+/// user and site config files
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Defaults {
+    pub parse_options: ParseOptions,
+    pub format_options: FormatOptions,
+    /// The schema levelspecs are validated against, if one was configured
+    pub schema: Option<LevelSchema>,
+}
+
+impl GlobalOpts {
+    /// The ParseOptions used to parse levelspecs supplied on the command line
+    pub fn parse_options(&self) -> ParseOptions {
+        let uppercase = self.uppercase || self.defaults.parse_options.uppercase;
+        self.defaults.parse_options.clone().with_trim_whitespace(true).with_uppercase(uppercase)
+    }
+
+    /// The FormatOptions levelspecs are printed with
+    pub fn format_options(&self) -> FormatOptions {
+        self.defaults.format_options.clone().with_case(self.case.into())
+    }
+
+    /// The names of the environment variables relative levels are resolved from
+    pub fn env_vars(&self) -> EnvVars {
+        EnvVars::new(self.show_var.as_str(), self.sequence_var.as_str(), self.shot_var.as_str())
+    }
+
+    /// Parse a levelspec supplied on the command line, resolving its relative
+    /// levels from the environment if requested
+    pub fn parse(&self, input: &str) -> Result<LevelSpec, LSE> {
+        self.parse_with(input, &self.parse_options())
+    }
+
+    /// Parse a levelspec supplied on the command line as `parse` does, but
+    /// with the supplied ParseOptions in place of `parse_options`
+    pub fn parse_with(&self, input: &str, options: &ParseOptions) -> Result<LevelSpec, LSE> {
+        let levelspec = options.parse(input)?;
+        if self.resolve_env && levelspec.has_relative() {
+            return levelspec.rel_to_abs_with(&EnvResolver::new(self.env_vars()));
+        }
+        Ok(levelspec)
+    }
+
+    /// The Output used to print results
+    pub fn output(&self) -> Output {
+        Output::new(self.output, self.case.into())
+            .with_options(self.format_options())
+            .with_template(self.format.clone())
+            .with_parse_options(self.parse_options())
+    }
+
+    /// Print a result to stdout, unless quiet
+    pub fn print(&self, line: &str) {
+        if !self.quiet {
+            println!("{}", line);
+        }
+    }
+
+    /// Print a diagnostic to stderr, unless quiet
+    pub fn report(&self, line: &str) {
+        if !self.quiet {
+            eprintln!("{}", line);
+        }
+    }
+}
+
+// parse the --format template, reporting errors as usage errors
+fn parse_format_template(template: &str) -> Result<FormatTemplate, String> {
+    FormatTemplate::new(template).map_err(|e| e.to_string())
+}
+
+/// Apply the user and site config files to the options not supplied on the
+/// command line, unless `--no-config` was supplied
+#[cfg(feature = "config")]
+pub fn configure(mut cli: Cli, matches: &ArgMatches) -> Result<Cli, LSE> {
+    if !cli.global.no_config {
+        config::apply(&mut cli, matches, &levelspecter::config::Config::load()?);
+    }
+    Ok(cli)
+}
+
+/// Config files are only read with the "config" feature
+#[cfg(not(feature = "config"))]
+pub fn configure(cli: Cli, _matches: &ArgMatches) -> Result<Cli, LSE> {
+    Ok(cli)
+}
+
+/// Options describing how levelspecs map onto the filesystem
+#[derive(Debug, clap::Args)]
+pub struct FsOpts {
+    /// The directory containing the shows
+    #[arg(long, default_value = ".")]
+    pub root: PathBuf,
+
+    /// The layout of the levels beneath the root. Templates without a 
+    /// `{root}` token are taken to be relative to the root.
+    #[arg(long, default_value = "{root}/{show}/{sequence}/{shot}")]
+    pub template: String,
+}
+
+impl FsOpts {
+    /// The root directory, resolved against the current directory as input
+    /// paths are
+    pub fn root(&self) -> Result<PathBuf, LSE> {
+        path::absolute(&self.root)
+    }
+
+    /// The PathTemplate described by the options, with its absolute root set
+    pub fn template(&self) -> Result<PathTemplate, LSE> {
+        let template = if self.template.contains("{root}") || self.template.starts_with('/') {
+            self.template.clone()
+        } else {
+            format!("{{root}}/{}", self.template)
+        };
+        Ok(PathTemplate::new(template)?.with_root(self.root()?))
+    }
+}
+
+/// A levelspec supplied on the command line or read from stdin
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Input {
+    /// The line of stdin the levelspec was read from, if any
+    pub line: Option<usize>,
+    pub text: String,
+}
+
+/// Iterate over the levelspecs supplied as arguments. A single `-` reads one
+/// levelspec per line from stdin instead.
+pub fn inputs(args: &[String]) -> Box<dyn Iterator<Item = io::Result<Input>>> {
+    if args.len() == 1 && args[0] == "-" {
+        return Box::new(read_inputs(io::BufReader::new(io::stdin())));
+    }
+    Box::new(args.to_vec().into_iter().map(|text| Ok(Input { line: None, text })))
+}
+
+/// Read one levelspec per line from a file, or from stdin if `source` is `-`
+pub fn source_inputs(source: &Path) -> Result<Vec<Input>, LSE> {
+    let inputs = if source.as_os_str() == "-" {
+        read_inputs(io::BufReader::new(io::stdin())).collect()
+    } else {
+        File::open(source).and_then(|file| read_inputs(io::BufReader::new(file)).collect())
+    };
+    inputs.map_err(|e| LSE::IoError(format!("Unable to read {}: {}", source.display(), e)))
+}
+
+/// Read one levelspec per line, numbering lines from 1 and skipping blank lines
+pub fn read_inputs<R>(reader: R) -> impl Iterator<Item = io::Result<Input>>
+where
+    R: BufRead
+{
+    reader.lines()
+        .enumerate()
+        .map(|(idx, line)| line.map(|text| Input { line: Some(idx + 1), text }))
+        .filter(|input| !matches!(input, Ok(input) if input.text.trim().is_empty()))
+}
+
+#[derive(Debug, Subcommand)]
+pub enum Command {
+    /// Parse levelspecs and print them
+    Parse(parse::Args),
+    /// Check that levelspecs are valid
+    Validate(validate::Args),
+    /// Print levelspecs with a custom separator, case, or padding
+    Format(format::Args),
+    /// Print the levelspecs matching a pattern, exiting non-zero if none match
+    Match(matches::Args),
+    /// Print the levelspecs on disk matching a pattern
+    Expand(expand::Args),
+    /// List the children of a levelspec
+    Ls(ls::Args),
+    /// Convert levelspecs into directories, or directories into levelspecs
+    Path(path::Args),
+    /// Print the levelspec each file or directory belongs to
+    FromPaths(from_paths::Args),
+    /// Explore the levelspecs on disk interactively
+    Repl(repl::Args),
+    /// Print shell commands setting the environment variables of a levelspec
+    Env(env::Args),
+    /// Print randomly generated levelspecs for load testing
+    Gen(gen::Args),
+    /// Rewrite levelspecs in canonical form, reporting what changed
+    Normalize(normalize::Args),
+    /// Print the shots in a numeric range within a sequence
+    Range(range::Args),
+    /// Sort levelspecs naturally, with shots in numeric order
+    Sort(sort::Args),
+    /// Print levelspecs as an indented tree, with counts for each parent
+    Tree(tree::Args),
+}
+
+/// Run the command, returning the exit code
+pub fn run(cli: Cli) -> i32 {
+    match cli.command {
+        Command::Parse(args) => parse::run(args, &cli.global),
+        Command::Validate(args) => validate::run(args, &cli.global),
+        Command::Format(args) => format::run(args, &cli.global),
+        Command::Match(args) => matches::run(args, &cli.global),
+        Command::Expand(args) => expand::run(args, &cli.global),
+        Command::Ls(args) => ls::run(args, &cli.global),
+        Command::Path(args) => path::run(args, &cli.global),
+        Command::FromPaths(args) => from_paths::run(args, &cli.global),
+        Command::Repl(args) => repl::run(args, &cli.global),
+        Command::Env(args) => env::run(args, &cli.global),
+        Command::Gen(args) => gen::run(args, &cli.global),
+        Command::Normalize(args) => normalize::run(args, &cli.global),
+        Command::Range(args) => range::run(args, &cli.global),
+        Command::Sort(args) => sort::run(args, &cli.global),
+        Command::Tree(args) => tree::run(args, &cli.global),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn global_options_follow_subcommands() {
+        let cli = Cli::try_parse_from(&["levelspecter", "parse", "dev01.rd", "-u", "--output", "json"]).unwrap();
+        assert!(cli.global.uppercase);
+        assert_eq!(cli.global.output, OutputFormat::Json);
+        assert_eq!(cli.global.parse("dev01.rd"), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+    }
+
+    #[test]
+    fn resolves_relative_levels_from_configured_variables() {
+        // name every variable, so that the shot is unset whatever DD_SHOT holds
+        std::env::set_var("CLI_TEST_SHOW", "DEV01");
+        std::env::set_var("CLI_TEST_SEQUENCE", "RD");
+        std::env::remove_var("CLI_TEST_SHOT");
+        let cli = Cli::try_parse_from(&[
+            "levelspecter", "parse", "--resolve-env", "--show-var", "CLI_TEST_SHOW", "--sequence-var", "CLI_TEST_SEQUENCE",
+            "--shot-var", "CLI_TEST_SHOT", "..0001"
+        ]).unwrap();
+        assert_eq!(cli.global.parse("..0001"), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+        assert!(cli.global.parse("...").is_err());
+    }
+
+    #[test]
+    fn reads_numbered_lines_skipping_blanks() {
+        let reader = io::Cursor::new("DEV01\n\n  \nDEV01.RD\n");
+        let inputs = read_inputs(reader).map(Result::unwrap).collect::<Vec<_>>();
+        assert_eq!(inputs, vec![
+            Input { line: Some(1), text: "DEV01".to_string() },
+            Input { line: Some(4), text: "DEV01.RD".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn command_line_is_consistent() {
+        use clap::CommandFactory;
+        Cli::command().debug_assert();
+    }
+
+    #[test]
+    fn requires_a_subcommand() {
+        assert!(Cli::try_parse_from(&["levelspecter"]).is_err());
+        assert!(Cli::try_parse_from(&["levelspecter", "parse"]).is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use super::{GlobalOpts, source_inputs, exitcode};
+use levelspecter::{AliasTable, Case, NormalizeAction, NormalizeRules};
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Minimum width numeric shots are zero padded to
+    #[arg(short, long, value_name = "WIDTH")]
+    pub pad: Option<usize>,
+
+    /// Convert levels to uppercase
+    #[arg(long, conflicts_with = "lower")]
+    pub upper: bool,
+
+    /// Convert levels to lowercase
+    #[arg(long)]
+    pub lower: bool,
+
+    /// Replace a show alias with its canonical name, eg `DEVTEST=DEV01`
+    #[arg(long, value_name = "ALIAS=SHOW", value_parser = parse_alias)]
+    pub show_alias: Vec<(String, String)>,
+
+    /// Replace a sequence alias with its canonical name, eg `RND=RD`
+    #[arg(long, value_name = "ALIAS=SEQUENCE", value_parser = parse_alias)]
+    pub sequence_alias: Vec<(String, String)>,
+
+    /// File of levelspecs, one per line. `-` reads stdin.
+    #[arg(default_value = "-")]
+    pub source: PathBuf,
+}
+
+impl Args {
+    /// The NormalizeRules described by the arguments, resolving the supplied
+    /// aliases along with those passed as flags
+    pub fn rules(&self, aliases: AliasTable) -> NormalizeRules {
+        let case = match (self.upper, self.lower) {
+            (true, _) => Case::Upper,
+            (_, true) => Case::Lower,
+            _ => Case::Preserve,
+        };
+        let mut rules = NormalizeRules::default().with_case(case).with_aliases(aliases);
+        rules.shot_padding = self.pad;
+        // flags override configured aliases, including those differing only in case
+        for (alias, canonical) in &self.show_alias {
+            rules = rules.with_show_alias(alias.as_str(), canonical.as_str());
+        }
+        for (alias, canonical) in &self.sequence_alias {
+            rules = rules.with_sequence_alias(alias.as_str(), canonical.as_str());
+        }
+        rules
+    }
+}
+
+// parse an ALIAS=CANONICAL pair, reporting errors as usage errors
+fn parse_alias(input: &str) -> Result<(String, String), String> {
+    match input.split_once('=') {
+        Some((alias, canonical)) if !alias.is_empty() && !canonical.is_empty() => {
+            Ok((alias.to_string(), canonical.to_string()))
+        }
+        _ => Err(format!("'{}' is not of the form ALIAS=CANONICAL", input)),
+    }
+}
+
+/// Print each levelspec in canonical form, reporting on stderr each line 
+/// which changed and why. Lines which fail to parse are reported and omitted.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    // configured aliases are resolved by the rules rather than the parser, so
+    // that the changes they make are reported
+    let rules = args.rules(global.defaults.parse_options.aliases.clone());
+    let parse_options = global.parse_options().with_aliases(AliasTable::default());
+    let inputs = match source_inputs(&args.source) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let mut code = exitcode::SUCCESS;
+    for input in inputs {
+        let levelspec = match global.parse_with(&input.text, &parse_options) {
+            Ok(levelspec) => levelspec,
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+                continue;
+            }
+        };
+        let (normalized, changes) = rules.apply(&levelspec);
+        let text = normalized.to_string();
+        if text != input.text.trim() {
+            let mut actions = changes.iter().map(|change| describe(change.action)).collect::<Vec<_>>();
+            actions.dedup();
+            let reasons = if actions.is_empty() {String::new()} else {format!(" ({})", actions.join(", "))};
+            global.report(&format!("line {}: {} -> {}{}", input.line.unwrap_or_default(), input.text.trim(), text, reasons));
+        }
+        global.print(&output.spec(&input.text, &normalized));
+    }
+    code
+}
+
+// the name of the action in change reports
+fn describe(action: NormalizeAction) -> &'static str {
+    match action {
+        NormalizeAction::Alias => "alias",
+        NormalizeAction::Keyword => "keyword",
+        NormalizeAction::Case => "case",
+        NormalizeAction::Padding => "padding",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+    use levelspecter::LevelSpec;
+
+    #[test]
+    fn builds_rules_from_flags() {
+        let cli = Cli::try_parse_from(&[
+            "levelspecter", "normalize", "--pad", "4", "--upper", "--sequence-alias", "RND=RD", "specs.txt"
+        ]).unwrap();
+        let args = match cli.command {
+            Command::Normalize(ref args) => args,
+            _ => panic!("expected normalize"),
+        };
+        let (normalized, changes) = args.rules(AliasTable::default()).apply(&LevelSpec::from_shot("DEV01", "RND", "1"));
+        assert_eq!(normalized, LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert_eq!(changes.len(), 2);
+        let rules = args.rules(AliasTable::default().with_show_alias("DEVTEST", "DEV01"));
+        assert_eq!(rules.apply(&LevelSpec::from_shot("DEVTEST", "RD", "0001")).0, LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert!(parse_alias("RND").is_err());
+    }
+
+    #[test]
+    fn alias_flags_override_configured_aliases_differing_in_case() {
+        let cli = Cli::try_parse_from(&["levelspecter", "normalize", "--show-alias", "devtest=DEV02", "specs.txt"]).unwrap();
+        let args = match cli.command {
+            Command::Normalize(ref args) => args,
+            _ => panic!("expected normalize"),
+        };
+        let rules = args.rules(AliasTable::default().with_show_alias("DEVTEST", "DEV01"));
+        assert_eq!(rules.apply(&LevelSpec::from_show("DEVTEST")).0, LevelSpec::from_show("DEV02"));
+    }
+}
@@ -0,0 +1,203 @@
+use clap::ValueEnum;
+use levelspecter::{LevelSpec, LevelSpecterError as LSE, FormatOptions, FormatTemplate, Case, ParseOptions};
+
+/// The format results are printed in
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum OutputFormat {
+    /// The levelspec, one per line
+    Text,
+    /// A JSON object per line
+    Json,
+    /// The Rust debug representation
+    Debug,
+}
+
+/// Case conversion applied to printed levels
+#[derive(Debug, PartialEq, Eq, Clone, Copy, ValueEnum)]
+pub enum CaseArg {
+    Preserve,
+    Upper,
+    Lower,
+}
+
+impl From<Case> for CaseArg {
+    fn from(case: Case) -> Self {
+        match case {
+            Case::Preserve => CaseArg::Preserve,
+            Case::Upper => CaseArg::Upper,
+            Case::Lower => CaseArg::Lower,
+        }
+    }
+}
+
+impl From<CaseArg> for Case {
+    fn from(case: CaseArg) -> Self {
+        match case {
+            CaseArg::Preserve => Case::Preserve,
+            CaseArg::Upper => Case::Upper,
+            CaseArg::Lower => Case::Lower,
+        }
+    }
+}
+
+/// Renders results in the requested OutputFormat
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Output {
+    pub format: OutputFormat,
+    pub options: FormatOptions,
+    /// Template used to render levelspecs in text output
+    pub template: Option<FormatTemplate>,
+    /// Options input was parsed with, used to explain parse errors
+    pub parse_options: ParseOptions,
+}
+
+impl Output {
+    /// New up an Output with the default separator
+    pub fn new(format: OutputFormat, case: Case) -> Self {
+        Self { format, options: FormatOptions::default().with_case(case), template: None, parse_options: ParseOptions::strict() }
+    }
+
+    /// Set the options input is parsed with and return self
+    pub fn with_parse_options(mut self, parse_options: ParseOptions) -> Self {
+        self.parse_options = parse_options;
+        self
+    }
+
+    /// Set the template used to render levelspecs in text output and return self
+    pub fn with_template(mut self, template: Option<FormatTemplate>) -> Self {
+        self.template = template;
+        self
+    }
+
+    /// Replace the FormatOptions and return self
+    pub fn with_options(mut self, options: FormatOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Render a LevelSpec parsed from `input`
+    pub fn spec(&self, input: &str, levelspec: &LevelSpec) -> String {
+        match self.format {
+            OutputFormat::Text => match self.template {
+                Some(ref template) => template.render(levelspec, &self.options),
+                None => levelspec.format_with(&self.options),
+            },
+            OutputFormat::Debug => format!("{:?}", levelspec),
+            OutputFormat::Json => {
+                let level = |level: Option<&levelspecter::LevelType>| match level {
+                    Some(level) => json_str(&self.options.case.apply(level.to_str())),
+                    None => "null".to_string(),
+                };
+                format!(
+                    "{{\"input\":{},\"levelspec\":{},\"show\":{},\"sequence\":{},\"shot\":{}}}",
+                    json_str(input),
+                    json_str(&levelspec.format_with(&self.options)),
+                    level(Some(levelspec.show())),
+                    level(levelspec.sequence()),
+                    level(levelspec.shot()),
+                )
+            }
+        }
+    }
+
+    /// Render the failure to process `input`, read from `line` of stdin if
+    /// known. Parse errors in text output show the input with a caret under
+    /// the offending character.
+    pub fn error(&self, input: &str, line: Option<usize>, error: &LSE) -> String {
+        let diagnostic = match error {
+            LSE::ParseError(_) | LSE::NoLevels | LSE::TooManyLevels(_) => self.parse_options.diagnose(input),
+            _ => None,
+        };
+        match (self.format, diagnostic) {
+            (OutputFormat::Json, diagnostic) => {
+                let mut fields = Vec::new();
+                if let Some(line) = line {
+                    fields.push(format!("\"line\":{}", line));
+                }
+                fields.push(format!("\"input\":{}", json_str(input)));
+                fields.push(format!("\"error\":{}", json_str(&error.to_string())));
+                if let Some(diagnostic) = diagnostic {
+                    fields.push(format!("\"column\":{}", diagnostic.column));
+                    fields.push(format!("\"reason\":{}", json_str(&diagnostic.message)));
+                }
+                format!("{{{}}}", fields.join(","))
+            }
+            (_, Some(diagnostic)) => match line {
+                Some(line) => format!("line {}: {}", line, diagnostic.to_string().trim_end()),
+                None => diagnostic.to_string().trim_end().to_string(),
+            },
+            (_, None) => match line {
+                Some(line) => format!("line {}: {}: {}", line, input, error),
+                None => format!("{}: {}", input, error),
+            },
+        }
+    }
+}
+
+/// Render a str as a JSON string literal
+pub fn json_str(input: &str) -> String {
+    let mut out = String::with_capacity(input.len() + 2);
+    out.push('"');
+    for c in input.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_json_with_nulls_for_missing_levels() {
+        let output = Output::new(OutputFormat::Json, Case::Lower);
+        assert_eq!(
+            output.spec("DEV01.RD", &LevelSpec::from_sequence("DEV01", "RD")),
+            r#"{"input":"DEV01.RD","levelspec":"dev01.rd","show":"dev01","sequence":"rd","shot":null}"#
+        );
+    }
+
+    #[test]
+    fn errors_report_stdin_lines() {
+        let error = LSE::RelToAbsError("bad".to_string());
+        assert_eq!(Output::new(OutputFormat::Text, Case::Preserve).error("..0001", Some(3), &error), "line 3: ..0001: RelToAbs Error: bad");
+        assert_eq!(
+            Output::new(OutputFormat::Json, Case::Preserve).error("..0001", None, &error),
+            r#"{"input":"..0001","error":"RelToAbs Error: bad"}"#
+        );
+    }
+
+    #[test]
+    fn parse_errors_point_at_the_offending_character() {
+        let error = LevelSpec::new("DEV01.RD.00X1").unwrap_err();
+        assert_eq!(
+            Output::new(OutputFormat::Text, Case::Preserve).error("DEV01.RD.00X1", None, &error),
+            "error: shots must be numeric\n  |\n  | DEV01.RD.00X1\n  |            ^"
+        );
+        assert_eq!(
+            Output::new(OutputFormat::Json, Case::Preserve).error("DEV01.RD.00X1", Some(2), &error),
+            r#"{"line":2,"input":"DEV01.RD.00X1","error":"Parse Error Unable to parse levelspec: invalid shot at byte 9","column":11,"reason":"shots must be numeric"}"#
+        );
+    }
+
+    #[test]
+    fn renders_text_with_template() {
+        let output = Output::new(OutputFormat::Text, Case::Preserve)
+            .with_template(Some(FormatTemplate::new("{show}/{sequence}_{shot}").unwrap()));
+        assert_eq!(output.spec("DEV01.RD.0001", &LevelSpec::from_shot("DEV01", "RD", "0001")), "DEV01/RD_0001");
+    }
+
+    #[test]
+    fn escapes_json_strings() {
+        assert_eq!(json_str("a\"b\\c\n\u{1}"), r#""a\"b\\c\n\u0001""#);
+    }
+}
@@ -0,0 +1,61 @@
+use super::{GlobalOpts, inputs, exitcode};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The levelspecs to parse, or `-` to read one per line from stdin
+    #[arg(required = true)]
+    pub levelspecs: Vec<String>,
+}
+
+/// Print each levelspec, reporting those which fail to parse on stderr. 
+/// When more than one levelspec is parsed, a summary of the failures is
+/// printed on stderr at the end.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let mut total = 0;
+    let mut code = exitcode::SUCCESS;
+    let mut failures = Vec::new();
+    for input in inputs(&args.levelspecs) {
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                global.report(&format!("Unable to read stdin: {}", e));
+                return exitcode::IO_ERROR;
+            }
+        };
+        total += 1;
+        match global.parse(&input.text) {
+            Ok(levelspec) => global.print(&output.spec(&input.text, &levelspec)),
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+                failures.push(input.line);
+            }
+        }
+    }
+    if total > 1 && !failures.is_empty() {
+        global.report(&summary(total, &failures));
+    }
+    code
+}
+
+// summarize the failures, listing the lines of stdin they were read from
+fn summary(total: usize, failures: &[Option<usize>]) -> String {
+    let lines = failures.iter().flatten().map(ToString::to_string).collect::<Vec<_>>();
+    if lines.is_empty() {
+        format!("{} of {} levelspecs failed to parse", failures.len(), total)
+    } else {
+        format!("{} of {} levelspecs failed to parse (lines {})", failures.len(), total, lines.join(", "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn summarizes_failures() {
+        assert_eq!(summary(10, &[Some(2), Some(7)]), "2 of 10 levelspecs failed to parse (lines 2, 7)");
+        assert_eq!(summary(3, &[None]), "1 of 3 levelspecs failed to parse");
+    }
+}
@@ -0,0 +1,105 @@
+use super::{GlobalOpts, FsOpts, OutputFormat, inputs, exitcode, output::json_str};
+use levelspecter::{LevelSpec, LevelSpecterError as LSE, PathTemplate};
+use std::env;
+use std::path::{Path, PathBuf};
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Convert paths into levelspecs rather than levelspecs into paths
+    #[arg(short, long)]
+    pub reverse: bool,
+
+    /// The levelspecs, or paths when reversing, to convert. `-` reads one
+    /// per line from stdin.
+    #[arg(required = true)]
+    pub inputs: Vec<String>,
+
+    #[command(flatten)]
+    pub fs: FsOpts,
+}
+
+/// Print the directory of each levelspec, or the levelspec of each path when
+/// reversing.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let template = match args.fs.template() {
+        Ok(template) => template,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let mut code = exitcode::SUCCESS;
+    for input in inputs(&args.inputs) {
+        let input = match input {
+            Ok(input) => input,
+            Err(e) => {
+                global.report(&format!("Unable to read stdin: {}", e));
+                return exitcode::IO_ERROR;
+            }
+        };
+        let result = if args.reverse {
+            from_path(&input.text, &template).map(|levelspec| output.spec(&input.text, &levelspec))
+        } else {
+            global.parse(&input.text).and_then(|levelspec| levelspec.to_path_with(&template, &global.defaults.parse_options.reserved_sequences)).map(|path| {
+                match global.output {
+                    OutputFormat::Json => format!("{{\"input\":{},\"path\":{}}}", json_str(&input.text), json_str(&path.to_string_lossy())),
+                    _ => path.display().to_string(),
+                }
+            })
+        };
+        match result {
+            Ok(line) => global.print(&line),
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+            }
+        }
+    }
+    code
+}
+
+// match a path against the template, resolving relative paths against the 
+// current directory
+fn from_path(input: &str, template: &PathTemplate) -> Result<LevelSpec, LSE> {
+    LevelSpec::from_path(absolute(input)?, template)
+}
+
+/// Resolve a relative path against the current directory, dropping any `.`
+/// components so that the result compares equal to other resolved paths
+pub fn absolute<P>(input: P) -> Result<PathBuf, LSE>
+where
+    P: AsRef<Path>
+{
+    let path = input.as_ref();
+    if path.is_absolute() {
+        return Ok(path.components().collect());
+    }
+    let cwd = env::current_dir()
+        .map_err(|e| LSE::EnvError(format!("Unable to read the current directory: {}", e)))?;
+    Ok(cwd.join(path).components().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_through_the_template() {
+        let fs = FsOpts { root: PathBuf::from("/jobs"), template: "{show}/{sequence}/{shot}".to_string() };
+        let template = fs.template().unwrap();
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(ls.to_path(&template), Ok(PathBuf::from("/jobs/DEV01/RD/0001")));
+        assert_eq!(from_path("/jobs/DEV01/RD/0001", &template), Ok(ls));
+        assert_eq!(from_path("/jobs/DEV01/RD", &template), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+        assert!(from_path("/elsewhere/DEV01", &template).is_err());
+    }
+
+    #[test]
+    fn reverses_relative_paths_beneath_the_default_root() {
+        let fs = FsOpts { root: PathBuf::from("."), template: "{root}/{show}/{sequence}/{shot}".to_string() };
+        let template = fs.template().unwrap();
+        assert_eq!(from_path("DEV01/RD/0001", &template), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+        assert_eq!(from_path("./DEV01/RD", &template), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+    }
+}
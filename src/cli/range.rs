@@ -0,0 +1,72 @@
+use super::{GlobalOpts, exitcode};
+use levelspecter::ShotRange;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// The sequence whose shots are generated, such as DEV01.RD
+    pub sequence: String,
+
+    /// The first shot number
+    #[arg(value_parser = parse_shot)]
+    pub start: Shot,
+
+    /// The last shot number, included if reached by the step
+    #[arg(value_parser = parse_shot)]
+    pub end: Shot,
+
+    /// The difference between consecutive shot numbers
+    #[arg(short, long, default_value_t = 1, value_parser = clap::value_parser!(u32).range(1..))]
+    pub step: u32,
+
+    /// Minimum number of digits in each shot. Defaults to the width of the
+    /// start and end arguments, so `0010` pads to 4 digits.
+    #[arg(short, long)]
+    pub padding: Option<usize>,
+}
+
+/// A shot number, along with the number of digits it was written with
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Shot {
+    pub number: u32,
+    pub width: usize,
+}
+
+// parse a shot number argument, reporting errors as usage errors
+fn parse_shot(input: &str) -> Result<Shot, String> {
+    if input.is_empty() || !input.chars().all(|c| c.is_ascii_digit()) {
+        return Err(format!("'{}' is not a shot number", input));
+    }
+    let number = input.parse::<u32>().map_err(|e| e.to_string())?;
+    Ok(Shot { number, width: input.len() })
+}
+
+/// Print the shots from start to end within the sequence
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let padding = args.padding.unwrap_or_else(|| args.start.width.max(args.end.width));
+    let range = global.parse(&args.sequence)
+        .and_then(|sequence| ShotRange::new(&sequence, args.start.number, args.end.number, args.step, padding));
+    let range = match range {
+        Ok(range) => range,
+        Err(e) => {
+            global.report(&output.error(&args.sequence, None, &e));
+            return exitcode::for_error(&e);
+        }
+    };
+    for levelspec in range {
+        global.print(&output.spec(&levelspec.to_string(), &levelspec));
+    }
+    exitcode::SUCCESS
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_shot_width() {
+        assert_eq!(parse_shot("0010"), Ok(Shot { number: 10, width: 4 }));
+        assert!(parse_shot("-1").is_err());
+        assert!(parse_shot("A1").is_err());
+    }
+}
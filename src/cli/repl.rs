@@ -0,0 +1,252 @@
+use super::{GlobalOpts, FsOpts, Output, exitcode};
+use levelspecter::{LevelSpec, PathTemplate, ShotIndex};
+use rustyline::completion::{Completer, Pair};
+use rustyline::error::ReadlineError;
+use rustyline::highlight::Highlighter;
+use rustyline::hint::Hinter;
+use rustyline::history::DefaultHistory;
+use rustyline::validate::Validator;
+use rustyline::{Context, Editor, Helper};
+use std::env;
+use std::path::PathBuf;
+
+// Name of the history file kept in the home directory
+const HISTORY_FILE: &str = ".levelspecter_history";
+
+const COMMANDS: &[&str] = &["parse", "match", "expand", "ls", "path", "help", "quit"];
+
+const HELP: &str = "\
+parse <levelspec>...          print each levelspec
+match <pattern> <levelspec>... print the levelspecs matching the pattern
+expand <pattern>              print the indexed levelspecs matching the pattern
+ls [levelspec]                list the indexed children of a levelspec, or the shows
+path <levelspec>              print the directory of a levelspec
+path -r <path>                print the levelspec of a directory
+help                          print this message
+quit                          leave the repl
+A line without a command is parsed as a levelspec. Tab completes commands and indexed levelspecs.";
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    #[command(flatten)]
+    pub fs: FsOpts,
+}
+
+/// Run an interactive prompt for exploring the levelspecs on disk. The
+/// levelspecs beneath the root are indexed once, at startup.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let template = match args.fs.template() {
+        Ok(template) => template,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let index = args.fs.root().and_then(|root| ShotIndex::scan(root, &template)).unwrap_or_else(|e| {
+        global.report(&format!("{}; completion is disabled", e));
+        ShotIndex::default()
+    });
+    let mut editor = match Editor::<Session, DefaultHistory>::new() {
+        Ok(editor) => editor,
+        Err(e) => {
+            global.report(&format!("Unable to start the repl: {}", e));
+            return exitcode::IO_ERROR;
+        }
+    };
+    editor.set_helper(Some(Session { global, output: global.output(), index, template }));
+    let history = env::var_os("HOME").map(|home| PathBuf::from(home).join(HISTORY_FILE));
+    if let Some(ref history) = history {
+        let _ = editor.load_history(history);
+    }
+    loop {
+        let line = match editor.readline("levelspecter> ") {
+            Ok(line) => line,
+            Err(ReadlineError::Interrupted) | Err(ReadlineError::Eof) => break,
+            Err(e) => {
+                global.report(&format!("Unable to read input: {}", e));
+                break;
+            }
+        };
+        if line.trim().is_empty() {
+            continue;
+        }
+        let _ = editor.add_history_entry(line.as_str());
+        match editor.helper().and_then(|session| session.eval(&line)) {
+            Some(output) => println!("{}", output),
+            None => break,
+        }
+    }
+    if let Some(ref history) = history {
+        let _ = editor.save_history(history);
+    }
+    exitcode::SUCCESS
+}
+
+// The state of a repl session, which also provides tab completion
+struct Session<'a> {
+    global: &'a GlobalOpts,
+    output: Output,
+    index: ShotIndex,
+    template: PathTemplate,
+}
+
+impl<'a> Session<'a> {
+    // evaluate a line, returning the text to print, or None to quit
+    fn eval(&self, line: &str) -> Option<String> {
+        let words = line.split_whitespace().collect::<Vec<_>>();
+        let (command, args) = match words.split_first() {
+            Some((command, args)) => (*command, args),
+            None => return Some(String::new()),
+        };
+        let lines = match command {
+            "quit" | "exit" => return None,
+            "help" => vec![HELP.to_string()],
+            "parse" => args.iter().map(|input| self.parse(input).unwrap_or_else(|e| e)).collect(),
+            "match" => match args.split_first() {
+                Some((pattern, inputs)) => self.matching(pattern, inputs),
+                None => vec!["usage: match <pattern> <levelspec>...".to_string()],
+            },
+            "expand" => match args {
+                [pattern] => self.with_spec(pattern, |pattern| self.index.expand(&pattern)),
+                _ => vec!["usage: expand <pattern>".to_string()],
+            },
+            "ls" => match args {
+                [] => self.render(&self.index.shows()),
+                [parent] => self.with_spec(parent, |parent| self.index.children(&parent)),
+                _ => vec!["usage: ls [levelspec]".to_string()],
+            },
+            "path" => match args {
+                [input] => vec![
+                    self.spec(input)
+                        .and_then(|ls| ls.to_path_with(&self.template, &self.global.defaults.parse_options.reserved_sequences).map_err(|e| self.output.error(input, None, &e)))
+                        .map(|path| path.display().to_string())
+                        .unwrap_or_else(|e| e)
+                ],
+                ["-r", path] => vec![
+                    LevelSpec::from_path(path, &self.template)
+                        .map(|ls| self.output.spec(path, &ls))
+                        .unwrap_or_else(|e| e.to_string())
+                ],
+                _ => vec!["usage: path <levelspec> | path -r <path>".to_string()],
+            },
+            _ if args.is_empty() => vec![self.parse(command).unwrap_or_else(|e| e)],
+            _ => vec![format!("unknown command '{}'. Type help for a list of commands", command)],
+        };
+        Some(lines.join("\n"))
+    }
+
+    // parse a levelspec, rendering the error on failure
+    fn spec(&self, input: &str) -> Result<LevelSpec, String> {
+        self.global.parse(input).map_err(|e| self.output.error(input, None, &e))
+    }
+
+    fn parse(&self, input: &str) -> Result<String, String> {
+        self.spec(input).map(|ls| self.output.spec(input, &ls))
+    }
+
+    fn matching(&self, pattern: &str, inputs: &[&str]) -> Vec<String> {
+        let pattern = match self.spec(pattern) {
+            Ok(pattern) => pattern,
+            Err(e) => return vec![e],
+        };
+        inputs.iter()
+            .filter_map(|input| match self.spec(input) {
+                Ok(ls) if pattern.matches(&ls) => Some(self.output.spec(input, &ls)),
+                Ok(_) => None,
+                Err(e) => Some(e),
+            })
+            .collect()
+    }
+
+    // parse the input and render the LevelSpecs found from it
+    fn with_spec<F>(&self, input: &str, find: F) -> Vec<String>
+    where
+        F: Fn(LevelSpec) -> Vec<LevelSpec>
+    {
+        match self.spec(input) {
+            Ok(ls) => self.render(&find(ls)),
+            Err(e) => vec![e],
+        }
+    }
+
+    fn render(&self, specs: &[LevelSpec]) -> Vec<String> {
+        specs.iter().map(|ls| self.output.spec(&ls.to_string(), ls)).collect()
+    }
+
+    // complete the word ending at `pos`, returning its start and the candidates
+    fn candidates(&self, line: &str, pos: usize) -> (usize, Vec<String>) {
+        let start = line[..pos].rfind(char::is_whitespace).map(|idx| idx + 1).unwrap_or(0);
+        let word = &line[start..pos];
+        if start == 0 {
+            let commands = COMMANDS.iter().filter(|cmd| cmd.starts_with(word)).map(|cmd| cmd.to_string());
+            let specs = self.index.complete(word).into_iter().map(|ls| ls.to_string());
+            return (start, commands.chain(specs).collect());
+        }
+        (start, self.index.complete(word).into_iter().map(|ls| ls.to_string()).collect())
+    }
+}
+
+impl<'a> Completer for Session<'a> {
+    type Candidate = Pair;
+
+    fn complete(&self, line: &str, pos: usize, _ctx: &Context<'_>) -> rustyline::Result<(usize, Vec<Pair>)> {
+        let (start, candidates) = self.candidates(line, pos);
+        Ok((start, candidates.into_iter().map(|candidate| Pair { display: candidate.clone(), replacement: candidate }).collect()))
+    }
+}
+
+impl<'a> Hinter for Session<'a> {
+    type Hint = String;
+}
+
+impl<'a> Highlighter for Session<'a> {}
+
+impl<'a> Validator for Session<'a> {}
+
+impl<'a> Helper for Session<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::Cli;
+    use clap::Parser;
+
+    fn session(global: &GlobalOpts) -> Session<'_> {
+        let fs = FsOpts { root: PathBuf::from("/jobs"), template: "{show}/{sequence}/{shot}".to_string() };
+        Session {
+            global,
+            output: global.output(),
+            index: ShotIndex::new(vec![
+                LevelSpec::from_shot("DEV01", "RD", "0001"),
+                LevelSpec::from_shot("DEV01", "RA", "0001"),
+                LevelSpec::from_show("TEST"),
+            ]),
+            template: fs.template().unwrap(),
+        }
+    }
+
+    fn global() -> GlobalOpts {
+        Cli::try_parse_from(&["levelspecter", "repl"]).unwrap().global
+    }
+
+    #[test]
+    fn evaluates_commands() {
+        let global = global();
+        let session = session(&global);
+        assert_eq!(session.eval("ls DEV01"), Some("DEV01.RA\nDEV01.RD".to_string()));
+        assert_eq!(session.eval("expand DEV01.%.0001"), Some("DEV01.RA.0001\nDEV01.RD.0001".to_string()));
+        assert_eq!(session.eval("match DEV01.% DEV01.RD TEST.RD"), Some("DEV01.RD".to_string()));
+        assert_eq!(session.eval("path DEV01.RD"), Some("/jobs/DEV01/RD".to_string()));
+        assert_eq!(session.eval("path -r /jobs/DEV01/RD/0001"), Some("DEV01.RD.0001".to_string()));
+        assert_eq!(session.eval("DEV01.RD"), Some("DEV01.RD".to_string()));
+        assert_eq!(session.eval("quit"), None);
+    }
+
+    #[test]
+    fn completes_commands_and_indexed_levelspecs() {
+        let global = global();
+        let session = session(&global);
+        assert_eq!(session.candidates("pa", 2), (0, vec!["parse".to_string(), "path".to_string()]));
+        assert_eq!(session.candidates("ls DEV01.R", 10), (3, vec!["DEV01.RA".to_string(), "DEV01.RD".to_string()]));
+    }
+}
@@ -0,0 +1,88 @@
+use super::{GlobalOpts, source_inputs, exitcode};
+use levelspecter::{AssetDevPosition, LevelSpec, NaturalOrd};
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// File of levelspecs, one per line. `-` reads stdin.
+    #[arg(default_value = "-")]
+    pub source: PathBuf,
+
+    /// Remove duplicate levelspecs
+    #[arg(short, long)]
+    pub dedup: bool,
+
+    /// Sort in descending order
+    #[arg(short, long)]
+    pub reverse: bool,
+
+    /// Sort the ASSETDEV sequence after other sequences, rather than first
+    #[arg(long)]
+    pub assetdev_last: bool,
+}
+
+/// Print the levelspecs in natural order. Lines which fail to parse are 
+/// reported on stderr and omitted.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let inputs = match source_inputs(&args.source) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let mut code = exitcode::SUCCESS;
+    let mut specs = Vec::with_capacity(inputs.len());
+    for input in inputs {
+        match global.parse(&input.text) {
+            Ok(levelspec) => specs.push((input.text, levelspec)),
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+            }
+        }
+    }
+    arrange(&args, &mut specs);
+    for (input, levelspec) in &specs {
+        global.print(&output.spec(input, levelspec));
+    }
+    code
+}
+
+// sort the parsed inputs in place, then reverse and dedup as requested
+fn arrange(args: &Args, specs: &mut Vec<(String, LevelSpec)>) {
+    let assetdev = if args.assetdev_last { AssetDevPosition::Last } else { AssetDevPosition::First };
+    let order = NaturalOrd::new().with_assetdev(assetdev);
+    // sort_by is stable, so duplicates stay in input order
+    specs.sort_by(|(_, a), (_, b)| order.compare(a, b));
+    if args.reverse {
+        specs.reverse();
+    }
+    if args.dedup {
+        specs.dedup_by(|(_, a), (_, b)| a == b);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(args: &Args, inputs: &[&str]) -> Vec<String> {
+        let mut specs = inputs.iter()
+            .map(|input| (input.to_string(), LevelSpec::new(input).unwrap()))
+            .collect();
+        arrange(args, &mut specs);
+        specs.into_iter().map(|(_, spec)| spec.to_string()).collect()
+    }
+
+    #[test]
+    fn sorts_shots_numerically_and_dedups() {
+        let mut args = Args { source: PathBuf::from("-"), dedup: true, reverse: false, assetdev_last: false };
+        let inputs = ["DEV01.RD.10", "DEV01.RD.9", "DEV01.ASSETDEV.FOO", "DEV01.RD.9"];
+        assert_eq!(sorted(&args, &inputs), vec!["DEV01.ASSETDEV.FOO", "DEV01.RD.9", "DEV01.RD.10"]);
+        args.reverse = true;
+        args.assetdev_last = true;
+        assert_eq!(sorted(&args, &inputs), vec!["DEV01.ASSETDEV.FOO", "DEV01.RD.10", "DEV01.RD.9"]);
+    }
+}
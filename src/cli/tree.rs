@@ -0,0 +1,93 @@
+use super::{GlobalOpts, Output, source_inputs, exitcode};
+use levelspecter::{LevelName, LevelSpec, LevelTree, sort_specs};
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// File of levelspecs, one per line. `-` reads stdin.
+    #[arg(default_value = "-")]
+    pub source: PathBuf,
+}
+
+/// Print the levelspecs as an indented show → sequence → shot tree. Each 
+/// parent is followed by the number of levelspecs beneath it. Lines which 
+/// fail to parse are reported on stderr and omitted.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let output = global.output();
+    let inputs = match source_inputs(&args.source) {
+        Ok(inputs) => inputs,
+        Err(e) => {
+            global.report(&e.to_string());
+            return exitcode::for_error(&e);
+        }
+    };
+    let mut code = exitcode::SUCCESS;
+    let mut tree = LevelTree::new();
+    for input in inputs {
+        match global.parse(&input.text) {
+            Ok(levelspec) => { tree.insert(levelspec); },
+            Err(e) => {
+                global.report(&output.error(&input.text, input.line, &e));
+                code = code.max(exitcode::for_error(&e));
+            }
+        }
+    }
+    for line in render(&tree, &output) {
+        global.print(&line);
+    }
+    code
+}
+
+// render each node of the tree on its own line, children in natural order
+fn render(tree: &LevelTree, output: &Output) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut shows = tree.shows();
+    sort_specs(&mut shows);
+    for show in &shows {
+        render_node(tree, show, output, &mut lines);
+    }
+    lines
+}
+
+fn render_node(tree: &LevelTree, node: &LevelSpec, output: &Output, lines: &mut Vec<String>) {
+    let levels = node.to_vec_str();
+    let depth = levels.len() - 1;
+    let name = [LevelName::Show, LevelName::Sequence, LevelName::Shot][depth];
+    let label = output.options.format_level(name, levels[depth]);
+    let mut children = tree.children(node);
+    if children.is_empty() {
+        lines.push(format!("{}{}", "  ".repeat(depth), label));
+        return;
+    }
+    // count the inserted levelspecs beneath the node, excluding the node itself
+    let count = tree.subtree(node).len() - tree.contains(node) as usize;
+    lines.push(format!("{}{} ({})", "  ".repeat(depth), label, count));
+    sort_specs(&mut children);
+    for child in &children {
+        render_node(tree, child, output, lines);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::OutputFormat;
+    use levelspecter::Case;
+
+    #[test]
+    fn renders_counts_and_natural_order() {
+        let tree = ["DEV01.RD.10", "DEV01.RD.9", "DEV01.AA", "DEV01.AA.0001", "DEV02"].iter()
+            .map(|input| LevelSpec::new(input).unwrap())
+            .collect::<LevelTree>();
+        let output = Output::new(OutputFormat::Text, Case::Preserve);
+        assert_eq!(render(&tree, &output), vec![
+            "DEV01 (4)",
+            "  AA (1)",
+            "    0001",
+            "  RD (2)",
+            "    9",
+            "    10",
+            "DEV02",
+        ]);
+    }
+}
@@ -0,0 +1,139 @@
+use super::{GlobalOpts, Input, output::json_str, OutputFormat, source_inputs, exitcode};
+#[cfg(feature = "config")]
+use levelspecter::config::Config;
+use levelspecter::{LevelSchema, LevelSpec};
+use std::path::PathBuf;
+
+#[derive(Debug, clap::Args)]
+pub struct Args {
+    /// Require levelspecs to be concrete, without wildcards or relative levels
+    #[arg(long)]
+    pub concrete: bool,
+
+    /// Check levelspecs against the naming rules in a TOML or YAML config file
+    #[cfg(feature = "config")]
+    #[arg(long, value_name = "FILE")]
+    pub schema: Option<PathBuf>,
+
+    /// Read the levelspecs from a file, one per line. `-` reads stdin.
+    #[arg(long, value_name = "FILE", conflicts_with = "levelspecs")]
+    pub from: Option<PathBuf>,
+
+    /// The levelspecs to validate
+    #[arg(required_unless_present = "from")]
+    pub levelspecs: Vec<String>,
+}
+
+/// Report whether each levelspec is valid. The exit code is PARSE_ERROR if
+/// a levelspec fails to parse, NOT_CONCRETE if a levelspec is not 
+/// concrete when `--concrete` is supplied, and SCHEMA_VIOLATION if a 
+/// levelspec breaks the naming rules supplied with `--schema`, or those of 
+/// the user or site config.
+pub fn run(args: Args, global: &GlobalOpts) -> i32 {
+    let schema = match schema(&args, global) {
+        Ok(schema) => schema,
+        Err((code, e)) => {
+            global.report(&e);
+            return code;
+        }
+    };
+    let inputs = match args.from {
+        Some(ref path) => match source_inputs(path) {
+            Ok(inputs) => inputs,
+            Err(e) => {
+                global.report(&e.to_string());
+                return exitcode::for_error(&e);
+            }
+        },
+        None => args.levelspecs.iter().map(|text| Input { line: None, text: text.clone() }).collect(),
+    };
+    let mut code = exitcode::SUCCESS;
+    for input in &inputs {
+        let result = check(&args, global, schema.as_ref(), &input.text);
+        if let Err((failure, _)) = result {
+            code = code.max(failure);
+        }
+        let prefix = input.line.map(|line| format!("line {}: ", line)).unwrap_or_default();
+        let line = match input.line {
+            Some(line) => format!("\"line\":{},", line),
+            None => String::new(),
+        };
+        let text = json_str(&input.text);
+        match (global.output, result) {
+            (OutputFormat::Json, Ok(())) => global.print(&format!("{{{}\"input\":{},\"valid\":true}}", line, text)),
+            (OutputFormat::Json, Err((_, errors))) => {
+                global.print(&format!("{{{}\"input\":{},\"valid\":false,\"error\":{}}}", line, text, json_str(&errors.join("; "))))
+            }
+            (_, Ok(())) => global.print(&format!("{}{}: ok", prefix, input.text)),
+            (_, Err((_, errors))) => for e in errors {
+                global.print(&format!("{}{}: {}", prefix, input.text, e));
+            },
+        }
+    }
+    code
+}
+
+// check a single input, returning the exit code and messages of any failure
+fn check(args: &Args, global: &GlobalOpts, schema: Option<&LevelSchema>, input: &str) -> Result<(), (i32, Vec<String>)> {
+    let levelspec = global.parse(input).map_err(|e| (exitcode::for_error(&e), vec![e.to_string()]))?;
+    if args.concrete && !is_concrete(&levelspec) {
+        return Err((exitcode::NOT_CONCRETE, vec![format!("{} is not concrete", levelspec)]));
+    }
+    if let Some(schema) = schema {
+        schema.validate(&levelspec).map_err(|errors| {
+            (exitcode::SCHEMA_VIOLATION, errors.iter().map(|e| e.to_string()).collect())
+        })?;
+    }
+    Ok(())
+}
+
+// load the schema named by `--schema`, falling back to the configured schema
+#[cfg(feature = "config")]
+fn schema(args: &Args, global: &GlobalOpts) -> Result<Option<LevelSchema>, (i32, String)> {
+    match args.schema {
+        Some(ref path) => Config::from_file(path)
+            .map(|config| Some(config.schema))
+            .map_err(|e| (exitcode::for_error(&e), e.to_string())),
+        None => Ok(global.defaults.schema.clone()),
+    }
+}
+
+#[cfg(not(feature = "config"))]
+fn schema(_args: &Args, global: &GlobalOpts) -> Result<Option<LevelSchema>, (i32, String)> {
+    Ok(global.defaults.schema.clone())
+}
+
+// true if the levelspec has neither wildcards nor relative levels
+fn is_concrete(levelspec: &LevelSpec) -> bool {
+    levelspec.is_concrete() && !levelspec.has_relative()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::{Cli, Command};
+    use clap::Parser;
+    use levelspecter::{Charset, LevelRule};
+
+    #[test]
+    fn concrete_rejects_wildcards_and_relative_levels() {
+        assert!(!is_concrete(&LevelSpec::new("DEV01.%").unwrap()));
+        assert!(!is_concrete(&LevelSpec::new(".RD").unwrap()));
+        assert!(is_concrete(&LevelSpec::new("DEV01.RD").unwrap()));
+    }
+
+    #[test]
+    fn reports_schema_violations() {
+        let cli = Cli::try_parse_from(&["levelspecter", "validate", "DEV01.RD.01"]).unwrap();
+        let args = match cli.command {
+            Command::Validate(ref args) => args,
+            _ => panic!("expected validate"),
+        };
+        let mut schema = LevelSchema::default();
+        schema.shot = LevelRule::new(Charset::Digits).with_length(4, Some(4));
+        assert_eq!(check(args, &cli.global, Some(&schema), "DEV01.RD.0001"), Ok(()));
+        let (code, errors) = check(args, &cli.global, Some(&schema), "DEV01.RD.01").unwrap_err();
+        assert_eq!((code, errors.len()), (exitcode::SCHEMA_VIOLATION, 1));
+        assert_eq!(check(args, &cli.global, None, "DEV01.RD.01"), Ok(()));
+    }
+}
@@ -0,0 +1,498 @@
+//! Load LevelSpecter settings from TOML or YAML files, so that sites may
+//! configure behavior without recompiling. Available with the `config` feature.
+//!
+//! Every section and key is optional; anything omitted keeps its default.
+//! `Config::load` reads the site config followed by the user config, so 
+//! users may override site defaults.
+//!
+//! ```toml
+//! [schema.shot]
+//! charset = "digits"
+//! min_length = 4
+//! max_length = 4
+//!
+//! [schema.sequence]
+//! charset = { custom = "ABCDEFGHIJKLMNOPQRSTUVWXYZ" }
+//! reserved = ["TMP"]
+//!
+//! [format]
+//! case = "upper"
+//! separator = "_"
+//!
+//! [parse]
+//! strictness = "lenient"
+//! allow_relative = false
+//!
+//! [aliases.show]
+//! DEVTEST = "DEV01"
+//!
+//! [aliases.sequence]
+//! RND = "RD"
+//!
+//! [templates]
+//! shot = "{root}/{show}/{sequence}/{shot}"
+//!
+//! [reserved_sequences]
+//! EDIT = {}
+//! COMMON = { template = "{root}/{show}/common/{shot}" }
+//!
+//! [env]
+//! show = "JOB"
+//! ```
+use crate::{aliases, LevelSpecterError as LSE, LevelSchema, LevelRule, Charset, ParseOptions, PathTemplate, EnvVars, FormatOptions, Case};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Environment variable naming the site config file
+pub const SITE_CONFIG_VAR: &str = "LEVELSPECTER_SITE_CONFIG";
+/// The site config file used when `LEVELSPECTER_SITE_CONFIG` is not set
+pub const DEFAULT_SITE_CONFIG: &str = "/etc/levelspecter.toml";
+
+/// Settings loaded from configuration files
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Config {
+    pub schema: LevelSchema,
+    pub parse_options: ParseOptions,
+    pub format_options: FormatOptions,
+    /// Path templates, by name
+    pub templates: BTreeMap<String, PathTemplate>,
+    pub env_vars: EnvVars,
+    /// The files the Config was loaded from, in the order applied
+    pub sources: Vec<PathBuf>,
+}
+
+impl Config {
+    /// Load the site config, then the user config, with each setting in the
+    /// user config overriding the site's. Missing files are skipped, so the 
+    /// default Config is returned if neither exists.
+    ///
+    /// The site config is named by `LEVELSPECTER_SITE_CONFIG`, falling back
+    /// to `/etc/levelspecter.toml`. The user config is 
+    /// `$XDG_CONFIG_HOME/levelspecter.toml`, falling back to 
+    /// `~/.config/levelspecter.toml`.
+    ///
+    /// # Returns
+    /// A Config, or a ConfigError if a file exists but cannot be loaded
+    pub fn load() -> Result<Self, LSE> {
+        Self::load_from(Some(Self::site_path()).into_iter().chain(Self::user_path()))
+    }
+
+    /// Load each existing file in turn, with later files overriding earlier ones
+    ///
+    /// # Parameters
+    ///
+    /// * `paths` - The configuration files, from lowest to highest precedence
+    ///
+    /// # Returns
+    /// A Config, or a ConfigError if a file exists but cannot be loaded
+    pub fn load_from<I, P>(paths: I) -> Result<Self, LSE>
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>
+    {
+        let mut config = Config::default();
+        for path in paths {
+            if path.as_ref().is_file() {
+                config.merge_file(path)?;
+            }
+        }
+        Ok(config)
+    }
+
+    /// The path of the site config file
+    pub fn site_path() -> PathBuf {
+        match env::var_os(SITE_CONFIG_VAR) {
+            Some(path) if !path.is_empty() => PathBuf::from(path),
+            _ => PathBuf::from(DEFAULT_SITE_CONFIG),
+        }
+    }
+
+    /// The path of the user config file, if the home directory is known
+    pub fn user_path() -> Option<PathBuf> {
+        let config_dir = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").filter(|dir| !dir.is_empty()).map(|home| PathBuf::from(home).join(".config")))?;
+        Some(config_dir.join("levelspecter.toml"))
+    }
+
+    /// Apply the settings in a file on top of this Config, choosing the
+    /// format from the file's extension as `from_file` does
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path to the configuration file
+    ///
+    /// # Returns
+    /// Ok, or a ConfigError if the file cannot be read or is invalid
+    pub fn merge_file<P>(&mut self, path: P) -> Result<(), LSE>
+    where
+        P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path)
+            .map_err(|e| LSE::ConfigError(format!("Unable to read {}: {}", path.display(), e)))?;
+        let file: ConfigFile = match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => toml::from_str(&contents)
+                .map_err(|e| LSE::ConfigError(format!("Invalid TOML in {}: {}", path.display(), e)))?,
+            Some("yaml") | Some("yml") => serde_yaml::from_str(&contents)
+                .map_err(|e| LSE::ConfigError(format!("Invalid YAML in {}: {}", path.display(), e)))?,
+            _ => return Err(LSE::ConfigError(format!("Unknown config format for {}", path.display()))),
+        };
+        file.apply(self)?;
+        self.sources.push(path.to_path_buf());
+        Ok(())
+    }
+
+    /// Load a Config from a file, choosing the format from the file's 
+    /// extension: `.toml`, or `.yaml` / `.yml`.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path to the configuration file
+    ///
+    /// # Returns
+    /// A Config, or a ConfigError if the file cannot be read or is invalid
+    pub fn from_file<P>(path: P) -> Result<Self, LSE>
+    where
+        P: AsRef<Path>
+    {
+        let mut config = Config::default();
+        config.merge_file(path)?;
+        Ok(config)
+    }
+
+    /// Load a Config from a TOML string
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use levelspecter::config::Config;
+    ///
+    /// let config = Config::from_toml_str("[env]\nshow = \"JOB\"").unwrap();
+    /// assert_eq!(config.env_vars.show, "JOB");
+    /// ```
+    pub fn from_toml_str(input: &str) -> Result<Self, LSE> {
+        let file: ConfigFile = toml::from_str(input)
+            .map_err(|e| LSE::ConfigError(format!("Invalid TOML: {}", e)))?;
+        let mut config = Config::default();
+        file.apply(&mut config)?;
+        Ok(config)
+    }
+
+    /// Load a Config from a YAML string
+    pub fn from_yaml_str(input: &str) -> Result<Self, LSE> {
+        let file: ConfigFile = serde_yaml::from_str(input)
+            .map_err(|e| LSE::ConfigError(format!("Invalid YAML: {}", e)))?;
+        let mut config = Config::default();
+        file.apply(&mut config)?;
+        Ok(config)
+    }
+}
+
+// The on-disk layout. Fields are optional and override the defaults.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ConfigFile {
+    schema: SchemaFile,
+    format: FormatFile,
+    parse: ParseFile,
+    aliases: AliasesFile,
+    templates: BTreeMap<String, String>,
+    reserved_sequences: BTreeMap<String, ReservedFile>,
+    env: EnvFile,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct SchemaFile {
+    show: Option<RuleFile>,
+    sequence: Option<RuleFile>,
+    shot: Option<RuleFile>,
+    assetdev_shot: Option<RuleFile>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct RuleFile {
+    charset: Option<CharsetFile>,
+    min_length: Option<usize>,
+    max_length: Option<usize>,
+    reserved: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CharsetFile {
+    Digits,
+    Alphanumeric,
+    UpperAlphanumeric,
+    Custom(String),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum CaseFile {
+    Preserve,
+    Upper,
+    Lower,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct FormatFile {
+    case: Option<CaseFile>,
+    separator: Option<String>,
+    shot_padding: Option<usize>,
+    relative_marker: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+enum StrictnessFile {
+    Strict,
+    Lenient,
+}
+
+// `strictness` selects the preset the other keys adjust
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ParseFile {
+    strictness: Option<StrictnessFile>,
+    trim_whitespace: Option<bool>,
+    uppercase: Option<bool>,
+    allow_wildcards: Option<bool>,
+    allow_relative: Option<bool>,
+    shot_padding: Option<usize>,
+}
+
+// Aliases are added to the ParseOptions, so that they are resolved as input is parsed
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct AliasesFile {
+    show: BTreeMap<String, String>,
+    sequence: BTreeMap<String, String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct ReservedFile {
+    template: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+#[serde(default, deny_unknown_fields)]
+struct EnvFile {
+    show: Option<String>,
+    sequence: Option<String>,
+    shot: Option<String>,
+}
+
+impl ConfigFile {
+    // override the settings in `config` with those set in the file
+    fn apply(self, config: &mut Config) -> Result<(), LSE> {
+        let schema = &mut config.schema;
+        self.schema.show.into_iter().for_each(|rule| rule.apply(&mut schema.show));
+        self.schema.sequence.into_iter().for_each(|rule| rule.apply(&mut schema.sequence));
+        self.schema.shot.into_iter().for_each(|rule| rule.apply(&mut schema.shot));
+        self.schema.assetdev_shot.into_iter().for_each(|rule| rule.apply(&mut schema.assetdev_shot));
+
+        let format = &mut config.format_options;
+        if let Some(case) = self.format.case {
+            format.case = match case {
+                CaseFile::Preserve => Case::Preserve,
+                CaseFile::Upper => Case::Upper,
+                CaseFile::Lower => Case::Lower,
+            };
+        }
+        format.separator = self.format.separator.unwrap_or_else(|| format.separator.clone());
+        format.shot_padding = self.format.shot_padding.or(format.shot_padding);
+        format.relative_marker = self.format.relative_marker.or_else(|| format.relative_marker.clone());
+
+        let options = &mut config.parse_options;
+        let parse = self.parse;
+        let aliases = std::mem::take(&mut options.aliases);
+        let reserved_sequences = std::mem::take(&mut options.reserved_sequences);
+        match parse.strictness {
+            Some(StrictnessFile::Strict) => *options = ParseOptions::strict(),
+            Some(StrictnessFile::Lenient) => *options = ParseOptions::lenient(),
+            None => (),
+        }
+        options.aliases = aliases;
+        options.reserved_sequences = reserved_sequences;
+        // later files override aliases which differ only in case
+        for (alias, canonical) in self.aliases.show {
+            aliases::insert(&mut options.aliases.shows, alias, canonical);
+        }
+        for (alias, canonical) in self.aliases.sequence {
+            aliases::insert(&mut options.aliases.sequences, alias, canonical);
+        }
+        options.trim_whitespace = parse.trim_whitespace.unwrap_or(options.trim_whitespace);
+        options.uppercase = parse.uppercase.unwrap_or(options.uppercase);
+        options.allow_wildcards = parse.allow_wildcards.unwrap_or(options.allow_wildcards);
+        options.allow_relative = parse.allow_relative.unwrap_or(options.allow_relative);
+        options.shot_padding = parse.shot_padding.or(options.shot_padding);
+
+        for (name, template) in self.templates {
+            let template = PathTemplate::new(&template)
+                .map_err(|e| LSE::ConfigError(format!("Template '{}': {}", name, e)))?;
+            config.templates.insert(name, template);
+        }
+
+        for (name, reserved) in self.reserved_sequences {
+            let template = reserved.template
+                .map(|template| PathTemplate::new(&template)
+                    .map_err(|e| LSE::ConfigError(format!("Reserved sequence '{}': {}", name, e))))
+                .transpose()?;
+            config.parse_options.reserved_sequences.insert(name, template);
+        }
+        // the schema checks shots within the sequences the parser reserves
+        config.schema.reserved_sequences = config.parse_options.reserved_sequences.clone();
+
+        let vars = &mut config.env_vars;
+        let env = self.env;
+        vars.show = env.show.unwrap_or_else(|| vars.show.clone());
+        vars.sequence = env.sequence.unwrap_or_else(|| vars.sequence.clone());
+        vars.shot = env.shot.unwrap_or_else(|| vars.shot.clone());
+
+        Ok(())
+    }
+}
+
+impl RuleFile {
+    // override the supplied rule with the fields set in the file
+    fn apply(self, rule: &mut LevelRule) {
+        if let Some(charset) = self.charset {
+            rule.charset = match charset {
+                CharsetFile::Digits => Charset::Digits,
+                CharsetFile::Alphanumeric => Charset::Alphanumeric,
+                CharsetFile::UpperAlphanumeric => Charset::UpperAlphanumeric,
+                CharsetFile::Custom(chars) => Charset::Custom(chars),
+            };
+        }
+        if let Some(min_length) = self.min_length {
+            rule.min_length = min_length;
+        }
+        if self.max_length.is_some() {
+            rule.max_length = self.max_length;
+        }
+        rule.reserved.extend(self.reserved);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservedSequences;
+
+    #[test]
+    fn empty_config_is_default() {
+        assert_eq!(Config::from_toml_str("").unwrap(), Config::default());
+    }
+
+    #[test]
+    fn loads_toml() {
+        let config = Config::from_toml_str(r#"
+            [schema.shot]
+            charset = "digits"
+            min_length = 4
+            max_length = 4
+
+            [schema.sequence]
+            charset = { custom = "ABC" }
+            reserved = ["TMP"]
+
+            [parse]
+            allow_relative = false
+
+            [templates]
+            shot = "/jobs/{show}/{sequence}/{shot}"
+
+            [env]
+            show = "JOB"
+        "#).unwrap();
+        assert_eq!(config.schema.shot, LevelRule::new(Charset::Digits).with_length(4, Some(4)));
+        assert_eq!(config.schema.sequence.charset, Charset::Custom("ABC".to_string()));
+        assert_eq!(config.schema.sequence.reserved, vec!["TMP".to_string()]);
+        assert!(!config.parse_options.allow_relative);
+        assert_eq!(config.templates["shot"], PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap());
+        assert_eq!(config.env_vars.show, "JOB");
+        assert_eq!(config.env_vars.shot, EnvVars::default().shot);
+    }
+
+    #[test]
+    fn loads_yaml() {
+        let config = Config::from_yaml_str("parse:\n  uppercase: true\nschema:\n  show:\n    charset: alphanumeric\n").unwrap();
+        assert!(config.parse_options.uppercase);
+        assert_eq!(config.schema.show.charset, Charset::Alphanumeric);
+    }
+
+    // aliases accumulate across files, and survive a strictness preset
+    #[test]
+    fn loads_aliases_into_parse_options() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site.toml");
+        let user = dir.path().join("user.toml");
+        fs::write(&site, "[aliases.show]\nDEVTEST = \"DEV01\"").unwrap();
+        fs::write(&user, "[parse]\nstrictness = \"lenient\"\n[aliases.sequence]\nRND = \"RD\"").unwrap();
+        let config = Config::load_from(&[&site, &user]).unwrap();
+        let expect = crate::AliasTable::default().with_show_alias("DEVTEST", "DEV01").with_sequence_alias("RND", "RD");
+        assert_eq!(config.parse_options.aliases, expect);
+        assert_eq!(config.parse_options.parse("devtest.rnd"), Ok(crate::LevelSpec::from_sequence("DEV01", "RD")));
+    }
+
+    #[test]
+    fn user_aliases_override_site_aliases_differing_in_case() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site.toml");
+        let user = dir.path().join("user.toml");
+        fs::write(&site, "[aliases.show]\nDEVTEST = \"DEV01\"").unwrap();
+        fs::write(&user, "[aliases.show]\ndevtest = \"DEV02\"").unwrap();
+        let config = Config::load_from(&[&site, &user]).unwrap();
+        assert_eq!(config.parse_options.aliases.shows.len(), 1);
+        assert_eq!(config.parse_options.aliases.show("DEVTEST"), Some("DEV02"));
+    }
+
+    #[test]
+    fn loads_reserved_sequences() {
+        let config = Config::from_toml_str("[reserved_sequences]\nEDIT = {}\nCOMMON = { template = \"/jobs/{show}/common/{shot}\" }").unwrap();
+        let expect = ReservedSequences::default()
+            .with_sequence("EDIT")
+            .with_template("COMMON", PathTemplate::new("/jobs/{show}/common/{shot}").unwrap());
+        assert_eq!(config.parse_options.reserved_sequences, expect);
+        assert_eq!(config.schema.reserved_sequences, expect);
+        assert!(Config::from_toml_str("[reserved_sequences.EDIT]\ntemplate = \"/jobs/{bad}\"").is_err());
+    }
+
+    #[test]
+    fn strictness_selects_a_preset() {
+        let config = Config::from_yaml_str("parse:\n  strictness: lenient\n  allow_wildcards: false\n").unwrap();
+        assert_eq!(config.parse_options, ParseOptions::lenient().with_wildcards(false));
+        assert!(Config::from_toml_str("[parse]\nstrictness = \"loose\"").is_err());
+    }
+
+    #[test]
+    fn later_files_override_earlier_ones() {
+        let dir = tempfile::tempdir().unwrap();
+        let site = dir.path().join("site.toml");
+        let user = dir.path().join("user.yaml");
+        fs::write(&site, "[format]\ncase = \"upper\"\nseparator = \"_\"\n[env]\nshow = \"JOB\"").unwrap();
+        fs::write(&user, "format:\n  separator: \"/\"\n").unwrap();
+        let missing = dir.path().join("missing.toml");
+        let config = Config::load_from(&[&site, &missing, &user]).unwrap();
+        assert_eq!(config.format_options, FormatOptions::default().with_case(Case::Upper).with_separator("/"));
+        assert_eq!(config.env_vars.show, "JOB");
+        assert_eq!(config.sources, vec![site, user]);
+        assert_eq!(Config::load_from(&[missing]).unwrap(), Config::default());
+    }
+
+    #[test]
+    fn reports_bad_config() {
+        assert!(Config::from_toml_str("[parse]\nunknown = true").is_err());
+        assert!(Config::from_toml_str("[templates]\nshot = \"/jobs/{bad}\"").is_err());
+        assert!(Config::from_file("levelspecter.ini").is_err());
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{LevelSpec, levelspec::canonical_level};
+use std::collections::{HashMap, HashSet};
+
+/// The ways in which the members of a Conflict differ
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
+pub enum ConflictKind {
+    /// The same LevelSpec appears more than once
+    Duplicate,
+    /// LevelSpecs differ only by case, eg `DEV01.rd` and `DEV01.RD`
+    Case,
+    /// Numeric levels differ only by zero padding, eg `1` and `0001`
+    Padding,
+}
+
+/// A group of LevelSpecs which refer to the same level once case and zero
+/// padding are ignored, ie which are equal as per `LevelSpec::eq_canonical`
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Conflict {
+    /// The conflicting LevelSpecs, in input order, including duplicates
+    pub specs: Vec<LevelSpec>,
+    /// The ways in which the LevelSpecs differ
+    pub kinds: Vec<ConflictKind>,
+}
+
+/// Find LevelSpecs which differ only by case or zero padding, or which are
+/// duplicated. Such entries cause silent data splits downstream.
+/// 
+/// # Parameters
+/// 
+/// * `specs` - The LevelSpecs to check
+/// 
+/// # Returns
+/// The conflicts, ordered by the first appearance of each in the input
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, find_conflicts, ConflictKind};
+/// 
+/// let specs = vec![
+///     LevelSpec::new("DEV01.RD.0001").unwrap(),
+///     LevelSpec::from_shot("DEV01", "RD", "0002"),
+///     LevelSpec { sequence: Some("rd".into()), ..LevelSpec::from_shot("DEV01", "RD", "1") },
+/// ];
+/// let conflicts = find_conflicts(&specs);
+/// assert_eq!(conflicts.len(), 1);
+/// assert_eq!(conflicts[0].specs, vec![specs[0].clone(), specs[2].clone()]);
+/// assert_eq!(conflicts[0].kinds, vec![ConflictKind::Case, ConflictKind::Padding]);
+/// ```
+pub fn find_conflicts<'a, I>(specs: I) -> Vec<Conflict>
+where
+    I: IntoIterator<Item = &'a LevelSpec>
+{
+    let mut groups: Vec<Vec<LevelSpec>> = Vec::new();
+    let mut group_index: HashMap<Vec<String>, usize> = HashMap::new();
+    for levelspec in specs {
+        let key = levels(levelspec, |level| canonical_level(level).to_ascii_uppercase());
+        let idx = *group_index.entry(key).or_insert_with(|| {
+            groups.push(Vec::new());
+            groups.len() - 1
+        });
+        groups[idx].push(levelspec.clone());
+    }
+    groups.into_iter()
+        .filter(|group| group.len() > 1)
+        .map(|specs| {
+            let distinct = |key: &dyn Fn(&LevelSpec) -> Vec<String>| {
+                specs.iter().map(key).collect::<HashSet<_>>().len()
+            };
+            let mut kinds = Vec::new();
+            if distinct(&|ls| levels(ls, str::to_string)) < specs.len() {
+                kinds.push(ConflictKind::Duplicate);
+            }
+            if distinct(&|ls| levels(ls, |level| canonical_level(level).to_string())) > 1 {
+                kinds.push(ConflictKind::Case);
+            }
+            if distinct(&|ls| levels(ls, str::to_ascii_uppercase)) > 1 {
+                kinds.push(ConflictKind::Padding);
+            }
+            Conflict { specs, kinds }
+        })
+        .collect()
+}
+
+// map each level of the LevelSpec through `f`
+fn levels<F>(levelspec: &LevelSpec, f: F) -> Vec<String>
+where
+    F: Fn(&str) -> String
+{
+    levelspec.to_vec_str().into_iter().map(|level| f(level.to_str())).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn shot(show: &str, sequence: &str, shot: &str) -> LevelSpec {
+        LevelSpec {
+            show: show.into(),
+            sequence: Some(sequence.into()),
+            shot: Some(shot.into()),
+        }
+    }
+
+    #[test]
+    fn reports_duplicates() {
+        let specs = vec![shot("DEV01", "RD", "0001"), shot("DEV01", "RD", "0001")];
+        assert_eq!(find_conflicts(&specs), vec![Conflict { specs: specs.clone(), kinds: vec![ConflictKind::Duplicate] }]);
+    }
+
+    #[test]
+    fn groups_by_canonical_form() {
+        let specs = vec![
+            shot("DEV01", "RD", "0001"),
+            shot("DEV01", "AA", "0010"),
+            shot("DEV01", "RD", "01"),
+            shot("dev01", "aa", "0010"),
+            shot("DEV01", "RD", "0002"),
+        ];
+        let conflicts = find_conflicts(&specs);
+        assert_eq!(conflicts, vec![
+            Conflict { specs: vec![specs[0].clone(), specs[2].clone()], kinds: vec![ConflictKind::Padding] },
+            Conflict { specs: vec![specs[1].clone(), specs[3].clone()], kinds: vec![ConflictKind::Case] },
+        ]);
+    }
+
+    #[test]
+    fn conflicts_are_canonically_equal() {
+        let specs = vec![shot("DEV01", "RD", "0001"), shot("dev01", "Rd", "1"), shot("DEV01", "RD", "0002")];
+        for conflict in find_conflicts(&specs) {
+            assert!(conflict.specs.iter().all(|ls| ls.eq_canonical(&conflict.specs[0])));
+        }
+    }
+
+    #[test]
+    fn zero_shots_conflict() {
+        let specs = vec![shot("DEV01", "RD", "0000"), shot("DEV01", "RD", "0")];
+        assert_eq!(find_conflicts(&specs)[0].kinds, vec![ConflictKind::Padding]);
+    }
+
+    #[test]
+    fn distinct_specs_do_not_conflict() {
+        let specs = vec![shot("DEV01", "RD", "0001"), shot("DEV01", "RD", "0010"), LevelSpec::from_sequence("DEV01", "RD")];
+        assert!(find_conflicts(&specs).is_empty());
+    }
+}
@@ -0,0 +1,184 @@
+use crate::{ParseOptions, ReservedSequences, levelspec::ASSETDEV};
+use std::fmt;
+
+/// An explanation of why input failed to parse, locating the offending 
+/// character. The Display implementation renders the input with a caret 
+/// beneath that character, in the style of rustc diagnostics.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::diagnose;
+/// 
+/// let diagnostic = diagnose("DEV01.RD.00X1").unwrap();
+/// assert_eq!(diagnostic.column, 11);
+/// assert_eq!(diagnostic.message, "shots must be numeric");
+/// assert_eq!(diagnostic.to_string(), "error: shots must be numeric\n  |\n  | DEV01.RD.00X1\n  |            ^\n");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseDiagnostic {
+    /// The input which failed to parse, after any trimming or case conversion
+    pub input: String,
+    /// The zero based character position of the offending character
+    pub column: usize,
+    /// A human readable explanation of the failure
+    pub message: String,
+}
+
+impl ParseDiagnostic {
+    pub(crate) fn new(input: &str, offset: usize, message: String) -> Self {
+        Self {
+            input: input.to_string(),
+            column: input[..offset].chars().count(),
+            message,
+        }
+    }
+}
+
+impl fmt::Display for ParseDiagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        writeln!(f, "error: {}", self.message)?;
+        writeln!(f, "  |")?;
+        writeln!(f, "  | {}", self.input)?;
+        writeln!(f, "  | {:width$}^", "", width = self.column)
+    }
+}
+
+/// Explain why the input fails to parse as a levelspec, as `LevelSpec::new`
+/// would parse it.
+/// 
+/// # Parameters
+/// 
+/// * `input` - The str which failed to parse
+/// 
+/// # Returns
+/// A ParseDiagnostic, or None if the input parses
+pub fn diagnose(input: &str) -> Option<ParseDiagnostic> {
+    ParseOptions::strict().diagnose(input)
+}
+
+// Locate the first violation of the levelspec grammar, as a byte offset
+// and message. Offsets are relative to the supplied input.
+pub(crate) fn locate(input: &str, reserved: &ReservedSequences) -> (usize, String) {
+    if input.is_empty() {
+        return (0, "expected a levelspec".to_string());
+    }
+    // as in the parser, excess levels are reported ahead of invalid ones, at
+    // the separator which starts the fourth level
+    if let Some((separator, _)) = input.match_indices('.').nth(2).filter(|_| input != "...") {
+        return (separator, "expected at most 3 levels".to_string());
+    }
+    let mut offset = 0;
+    let mut sequence = "";
+    for (idx, level) in input.split('.').enumerate() {
+        let found = match idx {
+            0 => check_name(level, "shows", false),
+            1 => {
+                sequence = level;
+                check_name(level, "sequences", true)
+            },
+            2 if reserved.contains(sequence) => check_name(level, "shots within reserved sequences", true),
+            2 if is_assetdev(sequence) => check_name(level, "assetdev shots", true),
+            _ => check_shot(level),
+        };
+        if let Some((level_offset, message)) = found {
+            return (offset + level_offset, message);
+        }
+        offset += level.len() + 1;
+    }
+    (0, "unable to parse levelspec".to_string())
+}
+
+fn is_assetdev(sequence: &str) -> bool {
+    if cfg!(feature = "case-insensitive") {
+        sequence.eq_ignore_ascii_case(ASSETDEV)
+    } else {
+        sequence == ASSETDEV
+    }
+}
+
+// check a show or sequence style level, returning the offset of the first
+// offending character within the level
+fn check_name(level: &str, plural: &str, end_alpha: bool) -> Option<(usize, String)> {
+    if level.is_empty() || level == "%" {
+        return None;
+    }
+    if let Some(idx) = level.find('%') {
+        return Some((idx, "wildcards must make up the whole level".to_string()));
+    }
+    for (idx, c) in level.char_indices() {
+        if !c.is_ascii_alphanumeric() {
+            return Some((idx, format!("unexpected character '{}'", c)));
+        }
+        if idx == 0 && !c.is_ascii_alphabetic() {
+            return Some((idx, format!("{} must start with a letter", plural)));
+        }
+        if c.is_ascii_lowercase() && !cfg!(feature = "case-insensitive") {
+            return Some((idx, format!("{} must be uppercase", plural)));
+        }
+    }
+    match level.chars().last() {
+        Some(c) if end_alpha && !c.is_ascii_alphabetic() => {
+            Some((level.len() - 1, format!("{} must end with a letter", plural)))
+        }
+        _ => None,
+    }
+}
+
+// check a numeric shot
+fn check_shot(level: &str) -> Option<(usize, String)> {
+    if level.is_empty() || level == "%" {
+        return None;
+    }
+    if let Some(idx) = level.find('%') {
+        return Some((idx, "wildcards must make up the whole level".to_string()));
+    }
+    level.char_indices()
+        .find(|(_, c)| !c.is_ascii_digit())
+        .map(|(idx, _)| (idx, "shots must be numeric".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn located(input: &str) -> (usize, String) {
+        let diagnostic = diagnose(input).unwrap();
+        (diagnostic.column, diagnostic.message)
+    }
+
+    #[test]
+    fn valid_input_has_no_diagnostic() {
+        assert_eq!(diagnose("DEV01.RD.0001"), None);
+        assert_eq!(diagnose("..0001"), None);
+    }
+
+    #[test]
+    fn locates_offending_characters() {
+        assert_eq!(located("1DEV01"), (0, "shows must start with a letter".to_string()));
+        assert_eq!(located("DEV_01"), (3, "unexpected character '_'".to_string()));
+        assert_eq!(located("DEV01.R%"), (7, "wildcards must make up the whole level".to_string()));
+        assert_eq!(located("DEV01.RD1"), (8, "sequences must end with a letter".to_string()));
+        assert_eq!(located("DEV01.ASSETDEV.FOO1"), (18, "assetdev shots must end with a letter".to_string()));
+    }
+
+    #[test]
+    fn locates_numeric_shots_within_reserved_sequences() {
+        let options = ParseOptions::strict().with_reserved_sequences(crate::reserved::tests::reserved());
+        let diagnostic = options.diagnose("DEV01.EDIT.0001").unwrap();
+        assert_eq!((diagnostic.column, diagnostic.message.as_str()), (11, "shots within reserved sequences must start with a letter"));
+        assert_eq!(diagnose("DEV01.EDIT.0001"), None);
+    }
+
+    #[test]
+    fn reports_extra_levels_at_their_separator() {
+        assert_eq!(located("DEV01.RD.0001.0002"), (13, "expected at most 3 levels".to_string()));
+        assert_eq!(located("A.B.C.D"), (5, "expected at most 3 levels".to_string()));
+    }
+
+    #[cfg(not(feature = "case-insensitive"))]
+    #[test]
+    fn reports_lowercase() {
+        assert_eq!(located("DEV01.Rd"), (7, "sequences must be uppercase".to_string()));
+    }
+}
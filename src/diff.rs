@@ -0,0 +1,123 @@
+use crate::LevelSpec;
+use std::collections::HashSet;
+
+/// The difference between two lists of LevelSpecs, as computed by
+/// `diff_lists`. Lists are treated as sets, so duplicates are reported once.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LevelSpecDiff {
+    /// LevelSpecs in the new list but not the old, in new list order
+    pub added: Vec<LevelSpec>,
+    /// LevelSpecs in the old list but not the new, in old list order
+    pub removed: Vec<LevelSpec>,
+    /// LevelSpecs in both lists, in new list order
+    pub retained: Vec<LevelSpec>,
+}
+
+impl LevelSpecDiff {
+    /// Returns true if the lists contained the same LevelSpecs
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty()
+    }
+
+    /// Patch a set of LevelSpecs, inserting the added LevelSpecs and
+    /// removing the removed ones. Applying the diff to the old list's 
+    /// LevelSpecs yields the new list's.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `set` - The set to patch
+    pub fn apply(&self, set: &mut HashSet<LevelSpec>) {
+        for levelspec in &self.removed {
+            set.remove(levelspec);
+        }
+        set.extend(self.added.iter().cloned());
+    }
+}
+
+/// Compare two lists of LevelSpecs, reporting which were added, removed,
+/// and retained. LevelSpecs are compared exactly, so `DEV01.%` does not
+/// retain `DEV01.RD`.
+/// 
+/// # Parameters
+/// 
+/// * `old` - The original list
+/// * `new` - The updated list
+/// 
+/// # Returns
+/// A LevelSpecDiff
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, diff_lists};
+/// use std::collections::HashSet;
+/// 
+/// let old = vec![LevelSpec::from_shot("DEV01", "RD", "0001"), LevelSpec::from_shot("DEV01", "RD", "0002")];
+/// let new = vec![LevelSpec::from_shot("DEV01", "RD", "0002"), LevelSpec::from_shot("DEV01", "RD", "0003")];
+/// let diff = diff_lists(&old, &new);
+/// assert_eq!(diff.added, vec![LevelSpec::from_shot("DEV01", "RD", "0003")]);
+/// assert_eq!(diff.removed, vec![LevelSpec::from_shot("DEV01", "RD", "0001")]);
+/// 
+/// let mut set = old.into_iter().collect::<HashSet<_>>();
+/// diff.apply(&mut set);
+/// assert_eq!(set, new.into_iter().collect());
+/// ```
+pub fn diff_lists(old: &[LevelSpec], new: &[LevelSpec]) -> LevelSpecDiff {
+    let old_set = old.iter().collect::<HashSet<_>>();
+    let new_set = new.iter().collect::<HashSet<_>>();
+    let mut diff = LevelSpecDiff::default();
+    let mut seen = HashSet::new();
+    for levelspec in new {
+        if !seen.insert(levelspec) {
+            continue;
+        }
+        if old_set.contains(levelspec) {
+            diff.retained.push(levelspec.clone());
+        } else {
+            diff.added.push(levelspec.clone());
+        }
+    }
+    seen.clear();
+    for levelspec in old {
+        if seen.insert(levelspec) && !new_set.contains(levelspec) {
+            diff.removed.push(levelspec.clone());
+        }
+    }
+    diff
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn specs(input: &[&str]) -> Vec<LevelSpec> {
+        input.iter().map(|spec| LevelSpec::from_str(spec).unwrap()).collect()
+    }
+
+    #[test]
+    fn reports_duplicates_once() {
+        let diff = diff_lists(&specs(&["DEV01.RD", "DEV01.RD", "DEV01.AA"]), &specs(&["DEV01.BB", "DEV01.BB", "DEV01.RD"]));
+        assert_eq!(diff, LevelSpecDiff {
+            added: specs(&["DEV01.BB"]),
+            removed: specs(&["DEV01.AA"]),
+            retained: specs(&["DEV01.RD"]),
+        });
+    }
+
+    #[test]
+    fn identical_lists_have_empty_diff() {
+        let list = specs(&["DEV01.RD", "DEV01.AA"]);
+        let diff = diff_lists(&list, &list);
+        assert!(diff.is_empty());
+        assert_eq!(diff.retained, list);
+    }
+
+    #[test]
+    fn apply_leaves_unrelated_specs() {
+        let diff = diff_lists(&specs(&["DEV01.RD"]), &specs(&["DEV01.AA"]));
+        let mut set = specs(&["DEV01.RD", "DEV02"]).into_iter().collect::<HashSet<_>>();
+        diff.apply(&mut set);
+        assert_eq!(set, specs(&["DEV01.AA", "DEV02"]).into_iter().collect());
+    }
+}
@@ -0,0 +1,46 @@
+use crate::LevelName;
+
+/// Default name of the environment variable holding the show
+pub const DEFAULT_SHOW_VAR: &str = "DD_SHOW";
+/// Default name of the environment variable holding the sequence
+pub const DEFAULT_SEQUENCE_VAR: &str = "DD_SEQUENCE";
+/// Default name of the environment variable holding the shot
+pub const DEFAULT_SHOT_VAR: &str = "DD_SHOT";
+
+/// The names of the environment variables used to import and export
+/// a LevelSpec. Defaults to `DD_SHOW`, `DD_SEQUENCE`, and `DD_SHOT`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct EnvVars {
+    pub show: String,
+    pub sequence: String,
+    pub shot: String,
+}
+
+impl EnvVars {
+    /// New up an EnvVars from the names of the show, sequence, and shot variables
+    pub fn new<I>(show: I, sequence: I, shot: I) -> Self
+    where
+        I: Into<String>
+    {
+        Self {
+            show: show.into(),
+            sequence: sequence.into(),
+            shot: shot.into(),
+        }
+    }
+
+    /// Retrieve the name of the variable associated with the supplied level
+    pub fn var(&self, level: LevelName) -> &str {
+        match level {
+            LevelName::Show => &self.show,
+            LevelName::Sequence => &self.sequence,
+            LevelName::Shot => &self.shot,
+        }
+    }
+}
+
+impl Default for EnvVars {
+    fn default() -> Self {
+        Self::new(DEFAULT_SHOW_VAR, DEFAULT_SEQUENCE_VAR, DEFAULT_SHOT_VAR)
+    }
+}
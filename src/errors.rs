@@ -1,4 +1,6 @@
 use failure::Fail;
+use crate::LevelName;
+use std::fmt;
 
 #[derive(Debug, Fail, PartialEq, Eq, Clone)]
 pub enum LevelSpecterError {
@@ -6,9 +8,93 @@ pub enum LevelSpecterError {
     Placeholder,
     
     #[fail(display = "Parse Error {}", _0)]
-    ParseError(String),
+    ParseError(ParseFailure),
     
     #[fail(display = "RelToAbs Error: {}", _0)]
     RelToAbsError(String),
 
-}
\ No newline at end of file
+    #[fail(display = "PathTemplate Error: {}", _0)]
+    PathTemplateError(String),
+
+    #[fail(display = "Env Error: {}", _0)]
+    EnvError(String),
+
+    #[fail(display = "Shot Error: {}", _0)]
+    ShotError(String),
+
+    #[fail(display = "No levels supplied")]
+    NoLevels,
+
+    #[fail(display = "Too many levels: expected at most 3, found {}", _0)]
+    TooManyLevels(usize),
+
+    #[fail(display = "Level Order Error: {}", _0)]
+    LevelOrderError(String),
+
+    #[fail(display = "Query Error: {}", _0)]
+    QueryError(String),
+
+    #[fail(display = "Rule Error: {}", _0)]
+    RuleError(String),
+
+    #[fail(display = "Config Error: {}", _0)]
+    ConfigError(String),
+
+    #[fail(display = "IO Error: {}", _0)]
+    IoError(String),
+
+    #[fail(display = "Watch Error: {}", _0)]
+    WatchError(String),
+
+    #[fail(display = "CSV Error: {}", _0)]
+    CsvError(String),
+
+    #[fail(display = "Serialize Error: {}", _0)]
+    SerializeError(String),
+
+    #[fail(display = "Format Error: {}", _0)]
+    FormatError(String),
+
+    #[fail(display = "Unknown Show: {}", _0)]
+    UnknownShow(String),
+
+}
+/// Why a parse failed. The parser records where it failed rather than
+/// formatting a message, so that callers which only check `is_err()` do not
+/// pay for one. The message is rendered by Display.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum ParseFailure {
+    /// The level starting at byte `position` of the input breaks the naming
+    /// rules for its position
+    InvalidLevel { level: LevelName, position: usize },
+    /// The levels are arranged illegally from byte `position` of the input,
+    /// eg a relative sequence between an absolute show and shot
+    InvalidStructure { position: usize },
+    /// The input is `len` bytes long, more than the `max_len` permitted by
+    /// the ParseLimits in effect
+    TooLong { len: usize, max_len: usize },
+    /// A failure described by a message, for checks beyond the grammar
+    Message(String),
+}
+
+impl fmt::Display for ParseFailure {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseFailure::InvalidLevel { level, position } => {
+                let level = match level {
+                    LevelName::Show => "show",
+                    LevelName::Sequence => "sequence",
+                    LevelName::Shot => "shot",
+                };
+                write!(f, "Unable to parse levelspec: invalid {} at byte {}", level, position)
+            }
+            ParseFailure::InvalidStructure { position } => {
+                write!(f, "Unable to parse levelspec: unexpected level at byte {}", position)
+            }
+            ParseFailure::TooLong { len, max_len } => {
+                write!(f, "Unable to parse levelspec: input is {} bytes, exceeding the limit of {}", len, max_len)
+            }
+            ParseFailure::Message(message) => write!(f, "{}", message),
+        }
+    }
+}
@@ -0,0 +1,257 @@
+//! A C interface to LevelSpec parsing, so that C and C++ applications, such
+//! as DCC plugins, may link against the crate directly. Available with the 
+//! `ffi` feature. The declarations are in `include/levelspecter.h`.
+//!
+//! LevelSpecs are opaque pointers owned by the caller, and released with
+//! `levelspec_free`. Strings returned by the library are owned by the caller
+//! and released with `levelspec_string_free`. Failing calls return a 
+//! LevelSpecStatus, and `levelspec_last_error` explains the most recent 
+//! failure on the calling thread.
+use crate::{LevelSpec, LevelSpecterError as LSE};
+use std::cell::RefCell;
+use std::ffi::{CStr, CString};
+use std::os::raw::{c_char, c_int};
+use std::ptr;
+use std::str::FromStr;
+
+/// The result of a call through the C interface
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LevelSpecStatus {
+    /// The call succeeded
+    Ok = 0,
+    /// A required pointer argument was null
+    NullPointer = 1,
+    /// The input was not valid UTF-8
+    InvalidUtf8 = 2,
+    /// The input is not a valid levelspec
+    ParseError = 3,
+    /// The call failed for another reason
+    Error = 4,
+}
+
+impl From<&LSE> for LevelSpecStatus {
+    fn from(error: &LSE) -> Self {
+        match error {
+            LSE::ParseError(_)
+            | LSE::NoLevels
+            | LSE::TooManyLevels(_)
+            | LSE::LevelOrderError(_)
+            | LSE::ShotError(_) => LevelSpecStatus::ParseError,
+            _ => LevelSpecStatus::Error,
+        }
+    }
+}
+
+thread_local! {
+    static LAST_ERROR: RefCell<Option<CString>> = const { RefCell::new(None) };
+}
+
+// record the message of the most recent failure, returning its status
+fn fail(status: LevelSpecStatus, message: String) -> LevelSpecStatus {
+    let message = CString::new(message.replace('\0', "")).ok();
+    LAST_ERROR.with(|last| *last.borrow_mut() = message);
+    status
+}
+
+// copy a str into a newly allocated C string owned by the caller
+fn to_c_string(value: &str) -> *mut c_char {
+    CString::new(value.replace('\0', "")).map(CString::into_raw).unwrap_or(ptr::null_mut())
+}
+
+/// Parse a levelspec, storing a newly allocated LevelSpec in `out`. 
+///
+/// # Returns
+/// `Ok`, or the status describing the failure, in which case `out` is set
+/// to null.
+///
+/// # Safety
+/// `input` must be null or a valid, nul terminated C string, and `out` must
+/// be null or valid for writes.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_parse(input: *const c_char, out: *mut *mut LevelSpec) -> LevelSpecStatus {
+    if out.is_null() {
+        return fail(LevelSpecStatus::NullPointer, "out is null".to_string());
+    }
+    *out = ptr::null_mut();
+    if input.is_null() {
+        return fail(LevelSpecStatus::NullPointer, "input is null".to_string());
+    }
+    let input = match CStr::from_ptr(input).to_str() {
+        Ok(input) => input,
+        Err(e) => return fail(LevelSpecStatus::InvalidUtf8, format!("input is not valid UTF-8: {}", e)),
+    };
+    match LevelSpec::from_str(input) {
+        Ok(levelspec) => {
+            *out = Box::into_raw(Box::new(levelspec));
+            LevelSpecStatus::Ok
+        }
+        Err(e) => fail(LevelSpecStatus::from(&e), e.to_string()),
+    }
+}
+
+/// Release a LevelSpec returned by `levelspec_parse`. Null is ignored.
+///
+/// # Safety
+/// `levelspec` must be null or a pointer returned by `levelspec_parse` which
+/// has not already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_free(levelspec: *mut LevelSpec) {
+    if !levelspec.is_null() {
+        drop(Box::from_raw(levelspec));
+    }
+}
+
+/// Render the LevelSpec as a string, eg `DEV01.RD.0001`
+///
+/// # Returns
+/// A string to release with `levelspec_string_free`, or null if 
+/// `levelspec` is null
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_to_string(levelspec: *const LevelSpec) -> *mut c_char {
+    match levelspec.as_ref() {
+        Some(levelspec) => to_c_string(&levelspec.to_string()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieve the show of the LevelSpec
+///
+/// # Returns
+/// A string to release with `levelspec_string_free`, or null if 
+/// `levelspec` is null
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_show(levelspec: *const LevelSpec) -> *mut c_char {
+    match levelspec.as_ref() {
+        Some(levelspec) => to_c_string(levelspec.show().to_str()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieve the sequence of the LevelSpec
+///
+/// # Returns
+/// A string to release with `levelspec_string_free`, or null if 
+/// `levelspec` is null or has no sequence
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_sequence(levelspec: *const LevelSpec) -> *mut c_char {
+    match levelspec.as_ref().and_then(|levelspec| levelspec.sequence()) {
+        Some(sequence) => to_c_string(sequence.to_str()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieve the shot of the LevelSpec
+///
+/// # Returns
+/// A string to release with `levelspec_string_free`, or null if 
+/// `levelspec` is null or has no shot
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_shot(levelspec: *const LevelSpec) -> *mut c_char {
+    match levelspec.as_ref().and_then(|levelspec| levelspec.shot()) {
+        Some(shot) => to_c_string(shot.to_str()),
+        None => ptr::null_mut(),
+    }
+}
+
+/// Retrieve the number of levels in the LevelSpec, from 1 to 3
+///
+/// # Returns
+/// The number of levels, or 0 if `levelspec` is null
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_depth(levelspec: *const LevelSpec) -> c_int {
+    levelspec.as_ref().map_or(0, |levelspec| levelspec.levels() as c_int)
+}
+
+/// Returns 1 if the LevelSpec has neither wildcards nor relative levels, 
+/// and 0 otherwise
+///
+/// # Safety
+/// `levelspec` must be null or a valid pointer returned by `levelspec_parse`.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_is_concrete(levelspec: *const LevelSpec) -> c_int {
+    levelspec.as_ref().map_or(0, |levelspec| (levelspec.is_concrete() && !levelspec.has_relative()) as c_int)
+}
+
+/// Describe the most recent failure on the calling thread
+///
+/// # Returns
+/// A string to release with `levelspec_string_free`, or null if no call 
+/// has failed
+#[no_mangle]
+pub extern "C" fn levelspec_last_error() -> *mut c_char {
+    LAST_ERROR.with(|last| {
+        last.borrow().as_ref().map_or(ptr::null_mut(), |message| message.clone().into_raw())
+    })
+}
+
+/// Release a string returned by the library. Null is ignored.
+///
+/// # Safety
+/// `value` must be null or a string returned by the library which has not
+/// already been freed.
+#[no_mangle]
+pub unsafe extern "C" fn levelspec_string_free(value: *mut c_char) {
+    if !value.is_null() {
+        drop(CString::from_raw(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // take ownership of a string returned by the library
+    unsafe fn take(value: *mut c_char) -> Option<String> {
+        if value.is_null() {
+            return None;
+        }
+        let string = CStr::from_ptr(value).to_str().unwrap().to_string();
+        levelspec_string_free(value);
+        Some(string)
+    }
+
+    #[test]
+    fn parses_and_accesses_levels() {
+        let input = CString::new("DEV01.RD.0001").unwrap();
+        let mut levelspec = ptr::null_mut();
+        unsafe {
+            assert_eq!(levelspec_parse(input.as_ptr(), &mut levelspec), LevelSpecStatus::Ok);
+            assert_eq!(take(levelspec_to_string(levelspec)), Some("DEV01.RD.0001".to_string()));
+            assert_eq!(take(levelspec_show(levelspec)), Some("DEV01".to_string()));
+            assert_eq!(take(levelspec_sequence(levelspec)), Some("RD".to_string()));
+            assert_eq!(take(levelspec_shot(levelspec)), Some("0001".to_string()));
+            assert_eq!((levelspec_depth(levelspec), levelspec_is_concrete(levelspec)), (3, 1));
+            levelspec_free(levelspec);
+        }
+    }
+
+    #[test]
+    fn reports_failures() {
+        let input = CString::new("DEV01.RD.bad_line").unwrap();
+        let mut levelspec = ptr::null_mut();
+        unsafe {
+            assert_eq!(levelspec_parse(input.as_ptr(), &mut levelspec), LevelSpecStatus::ParseError);
+            assert!(levelspec.is_null());
+            assert!(take(levelspec_last_error()).is_some());
+            assert_eq!(levelspec_parse(ptr::null(), &mut levelspec), LevelSpecStatus::NullPointer);
+            assert_eq!(levelspec_shot(ptr::null()), ptr::null_mut());
+            levelspec_free(ptr::null_mut());
+        }
+    }
+}
@@ -0,0 +1,116 @@
+use crate::{LevelName, LevelType};
+
+/// Case conversion applied to terms when formatting a LevelSpec
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Case {
+    Preserve,
+    Upper,
+    Lower,
+}
+
+impl Case {
+    /// Apply the case conversion to the supplied str
+    pub fn apply(&self, input: &str) -> String {
+        match self {
+            Case::Preserve => input.to_string(),
+            Case::Upper => input.to_uppercase(),
+            Case::Lower => input.to_lowercase(),
+        }
+    }
+}
+
+/// Options controlling how `LevelSpec::format_with` renders a LevelSpec.
+/// The default options render identically to `Display`.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, FormatOptions, Case};
+///
+/// let options = FormatOptions::default()
+///     .with_separator("_")
+///     .with_case(Case::Lower)
+///     .with_shot_padding(4);
+/// let ls = LevelSpec::from_shot("DEV01", "RD", "10");
+/// assert_eq!(ls.format_with(&options), "dev01_rd_0010");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FormatOptions {
+    /// String placed between levels
+    pub separator: String,
+    /// Case conversion applied to terms
+    pub case: Case,
+    /// Minimum width numeric shots are zero padded to
+    pub shot_padding: Option<usize>,
+    /// Marker rendered for relative levels. None renders an empty segment.
+    pub relative_marker: Option<String>,
+}
+
+impl FormatOptions {
+    /// Set the separator and return self
+    pub fn with_separator<I>(mut self, separator: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.separator = separator.into();
+        self
+    }
+
+    /// Set the case conversion and return self
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Set the minimum width of numeric shots and return self
+    pub fn with_shot_padding(mut self, padding: usize) -> Self {
+        self.shot_padding = Some(padding);
+        self
+    }
+
+    /// Set the marker rendered for relative levels and return self
+    pub fn with_relative_marker<I>(mut self, marker: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.relative_marker = Some(marker.into());
+        self
+    }
+
+    /// Render a single level, applying the case, shot padding, and relative
+    /// marker. The separator is not used.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `name` - The name of the level, which determines whether it is padded
+    /// * `level` - The level to render
+    /// 
+    /// # Returns
+    /// The formatted String
+    pub fn format_level(&self, name: LevelName, level: &LevelType) -> String {
+        match level {
+            LevelType::Relative => self.relative_marker.clone().unwrap_or_default(),
+            LevelType::Wildcard => level.to_string(),
+            LevelType::Pattern(val) => self.case.apply(val),
+            LevelType::Term(val) => {
+                match self.shot_padding {
+                    Some(padding) if name == LevelName::Shot && val.chars().all(|c| c.is_ascii_digit()) => {
+                        format!("{:0>width$}", val, width = padding)
+                    },
+                    _ => self.case.apply(val),
+                }
+            },
+        }
+    }
+}
+
+impl Default for FormatOptions {
+    fn default() -> Self {
+        Self {
+            separator: ".".to_string(),
+            case: Case::Preserve,
+            shot_padding: None,
+            relative_marker: None,
+        }
+    }
+}
@@ -0,0 +1,158 @@
+use crate::{LevelSpec, LevelName, FormatOptions, LevelSpecterError as LSE};
+use std::fmt;
+use std::str::FromStr;
+
+/// A single piece of a parsed FormatTemplate
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Piece {
+    Literal(String),
+    Level(LevelName),
+}
+
+/// Template rendering a LevelSpec into an arbitrary string, such as a 
+/// dailies name or slate text. Templates are made up of literal text and the
+/// `{show}`, `{sequence}`, and `{shot}` tokens. Literal braces are written
+/// `{{` and `}}`. Levels missing from the LevelSpec render as empty strings.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, FormatTemplate, FormatOptions};
+/// 
+/// let template = FormatTemplate::new("{show}/{sequence}_{shot}").unwrap();
+/// let ls = LevelSpec::from_shot("DEV01", "RD", "1");
+/// let options = FormatOptions::default().with_shot_padding(4);
+/// assert_eq!(template.render(&ls, &options), "DEV01/RD_0001");
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct FormatTemplate {
+    template: String,
+    pieces: Vec<Piece>,
+}
+
+impl FormatTemplate {
+    /// New up a FormatTemplate from a str or string.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `template` - The template string, eg `{show}/{sequence}_{shot}`
+    /// 
+    /// # Returns
+    /// A FormatTemplate, or a FormatError if the template contains an unknown
+    /// token or an unmatched brace
+    pub fn new<I>(template: I) -> Result<Self, LSE>
+    where
+        I: AsRef<str>
+    {
+        let template = template.as_ref();
+        let mut pieces = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut token = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(c) => token.push(c),
+                            None => return Err(LSE::FormatError(format!("Unclosed '{{' in template '{}'", template))),
+                        }
+                    }
+                    let level = match token.as_str() {
+                        "show" => LevelName::Show,
+                        "sequence" => LevelName::Sequence,
+                        "shot" => LevelName::Shot,
+                        _ => return Err(LSE::FormatError(format!("Unknown token '{{{}}}' in template '{}'", token, template))),
+                    };
+                    if !literal.is_empty() {
+                        pieces.push(Piece::Literal(std::mem::take(&mut literal)));
+                    }
+                    pieces.push(Piece::Level(level));
+                }
+                '}' => return Err(LSE::FormatError(format!("Unmatched '}}' in template '{}'", template))),
+                c => literal.push(c),
+            }
+        }
+        if !literal.is_empty() {
+            pieces.push(Piece::Literal(literal));
+        }
+        Ok(Self { template: template.to_string(), pieces })
+    }
+
+    /// Render the LevelSpec, applying the case, shot padding, and relative
+    /// marker of the supplied FormatOptions to each level
+    pub fn render(&self, levelspec: &LevelSpec, options: &FormatOptions) -> String {
+        let mut rendered = String::new();
+        for piece in &self.pieces {
+            match piece {
+                Piece::Literal(literal) => rendered.push_str(literal),
+                Piece::Level(name) => {
+                    let level = match name {
+                        LevelName::Show => Some(levelspec.show()),
+                        LevelName::Sequence => levelspec.sequence(),
+                        LevelName::Shot => levelspec.shot(),
+                    };
+                    if let Some(level) = level {
+                        rendered.push_str(&options.format_level(*name, level));
+                    }
+                }
+            }
+        }
+        rendered
+    }
+}
+
+impl FromStr for FormatTemplate {
+    type Err = LSE;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl fmt::Display for FormatTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Case;
+
+    #[test]
+    fn missing_levels_render_empty() {
+        let template = FormatTemplate::new("{show}-{sequence}-{shot}").unwrap();
+        assert_eq!(template.render(&LevelSpec::from_show("DEV01"), &FormatOptions::default()), "DEV01--");
+    }
+
+    #[test]
+    fn applies_format_options_to_levels_only() {
+        let template = FormatTemplate::new("Shot {shot} of {sequence}").unwrap();
+        let options = FormatOptions::default().with_case(Case::Lower);
+        assert_eq!(template.render(&LevelSpec::from_shot("DEV01", "RD", "0001"), &options), "Shot 0001 of rd");
+    }
+
+    #[test]
+    fn escapes_braces() {
+        let template = FormatTemplate::new("{{{show}}}").unwrap();
+        assert_eq!(template.render(&LevelSpec::from_show("DEV01"), &FormatOptions::default()), "{DEV01}");
+    }
+
+    #[test]
+    fn rejects_malformed_templates() {
+        assert!(FormatTemplate::new("{task}").is_err());
+        assert!(FormatTemplate::new("{show").is_err());
+        assert!(FormatTemplate::new("show}").is_err());
+    }
+}
@@ -0,0 +1,104 @@
+use crate::LevelSpec;
+
+/// Find the candidate most similar to the input, for "did you mean" style
+/// suggestions. The input need not be a valid levelspec. Similarity is 
+/// computed from the edit distance between corresponding levels, ignoring
+/// case, with missing or extra levels counting as wholly different.
+/// 
+/// # Parameters
+/// 
+/// * `input` - The str to match, eg `DEV01.RD.001`
+/// * `candidates` - The LevelSpecs to choose from
+/// 
+/// # Returns
+/// The closest candidate and its similarity, from 0.0 (nothing in common)
+/// to 1.0 (identical), or None if there are no candidates. The earliest 
+/// candidate wins ties.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, closest_match};
+/// 
+/// let shots = vec![LevelSpec::from_shot("DEV01", "RD", "0001"), LevelSpec::from_shot("DEV01", "AA", "0001")];
+/// let (closest, similarity) = closest_match("DEV01.RD.001", &shots).unwrap();
+/// assert_eq!(closest, &shots[0]);
+/// assert!(similarity > 0.9);
+/// ```
+pub fn closest_match<'a, I>(input: &str, candidates: I) -> Option<(&'a LevelSpec, f64)>
+where
+    I: IntoIterator<Item = &'a LevelSpec>
+{
+    let input_levels = input.split('.').collect::<Vec<_>>();
+    let mut best: Option<(&'a LevelSpec, f64)> = None;
+    for candidate in candidates {
+        let score = similarity(&input_levels, &candidate.to_vec_string());
+        match best {
+            Some((_, best_score)) if best_score >= score => (),
+            _ => best = Some((candidate, score)),
+        }
+    }
+    best
+}
+
+// similarity of two lists of levels, from 0.0 to 1.0
+fn similarity(input: &[&str], candidate: &[String]) -> f64 {
+    let mut distance = 0;
+    let mut total = 0;
+    for idx in 0..input.len().max(candidate.len()) {
+        let a = input.get(idx).copied().unwrap_or("");
+        let b = candidate.get(idx).map(String::as_str).unwrap_or("");
+        distance += edit_distance(a, b);
+        total += a.chars().count().max(b.chars().count());
+    }
+    if total == 0 {
+        return 1.0;
+    }
+    1.0 - distance as f64 / total as f64
+}
+
+// levenshtein distance between two strs, ignoring ascii case
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for (i, ca) in a.chars().enumerate() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca.eq_ignore_ascii_case(cb) {0} else {1};
+            current[j + 1] = (previous[j] + cost).min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        previous = current;
+    }
+    previous[b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn computes_edit_distance() {
+        assert_eq!(edit_distance("0001", "001"), 1);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("rd", "RD"), 0);
+        assert_eq!(edit_distance("", "RD"), 2);
+    }
+
+    #[test]
+    fn exact_match_scores_one() {
+        let candidates = vec![LevelSpec::from_sequence("DEV01", "RD")];
+        assert_eq!(closest_match("DEV01.RD", &candidates), Some((&candidates[0], 1.0)));
+    }
+
+    #[test]
+    fn penalizes_missing_levels() {
+        let candidates = vec![LevelSpec::from_shot("DEV01", "RD", "0001"), LevelSpec::from_sequence("DEV01", "RD")];
+        assert_eq!(closest_match("DEV01.RD", &candidates).unwrap().0, &candidates[1]);
+        assert_eq!(closest_match("DEV01.RD.0002", &candidates).unwrap().0, &candidates[0]);
+    }
+
+    #[test]
+    fn no_candidates_no_match() {
+        assert_eq!(closest_match("DEV01", &Vec::new()), None);
+    }
+}
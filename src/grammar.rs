@@ -0,0 +1,249 @@
+//! Describes the strings the parser accepts in formats other tools 
+//! understand, so that REST API definitions and form validation stay in
+//! step with the Rust parser.
+use crate::{ParseOptions, levelspec::ASSETDEV};
+use std::collections::BTreeMap;
+
+/// Build a regular expression matching exactly the strings which `options`
+/// parse into a LevelSpec, in the active build. The expression is anchored,
+/// and uses syntax shared by the `regex` crate, JavaScript, and JSON Schema.
+/// 
+/// Named shots are matched within the options' reserved sequences as well as
+/// ASSETDEV, and numbered shots everywhere but the reserved sequences.
+/// 
+/// # Parameters
+/// 
+/// * `options` - The ParseOptions input is parsed with
+/// 
+/// # Returns
+/// The regular expression, as a String
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{levelspec_regex, ParseOptions};
+/// use regex::Regex;
+/// 
+/// let options = ParseOptions::default().with_wildcards(false).with_relative(false);
+/// let re = Regex::new(&levelspec_regex(&options)).unwrap();
+/// assert!(re.is_match("DEV01.RD.0001"));
+/// assert!(!re.is_match("DEV01.%"));
+/// assert!(!re.is_match(".RD.0001"));
+/// ```
+pub fn levelspec_regex(options: &ParseOptions) -> String {
+    let any_case = cfg!(feature = "case-insensitive") || options.uppercase;
+    let (letter, alphanumeric) = classes(any_case);
+    // reserved sequences match regardless of case in case insensitive builds,
+    // and are otherwise only spelled by uppercase sequences
+    let mut reserved = options.reserved_sequences.sequences.keys()
+        .filter(|name| !name.is_empty() && name.chars().all(|c| c.is_ascii_alphanumeric()))
+        .filter(|name| cfg!(feature = "case-insensitive") || !name.bytes().any(|c| c.is_ascii_lowercase()))
+        .map(|name| name.to_ascii_uppercase())
+        .collect::<Vec<_>>();
+    reserved.sort();
+    reserved.dedup();
+    let mut named = reserved.iter().filter(|name| *name != ASSETDEV).map(String::as_str).collect::<Vec<_>>();
+    named.insert(0, ASSETDEV);
+    let named = named.iter().map(|name| literal(name, any_case)).collect::<Vec<_>>();
+    let assetdev = if named.len() == 1 {named[0].clone()} else {format!("(?:{})", named.join("|"))};
+    let digits = match options.shot_padding {
+        Some(padding) if padding > 1 => format!("[0-9]{{{},}}", padding),
+        _ => "[0-9]+".to_string(),
+    };
+    let level = |term: String| if options.allow_wildcards {format!("(?:{}|%)", term)} else {format!("(?:{})", term)};
+
+    let show = level(format!("{}{}*", letter, alphanumeric));
+    // sequences, and shots within ASSETDEV and reserved sequences, begin and end with a letter
+    let name = format!("{l}(?:{a}*{l})?", l = letter, a = alphanumeric);
+    let sequence = level(name.clone());
+    // sequences which may hold numbered shots
+    let numbered = if reserved.is_empty() {sequence.clone()} else {level(name_except(&reserved, any_case))};
+    let shot = level(digits);
+    let assetdev_shot = level(name);
+
+    // case insensitive builds also accept named shots within a wildcard sequence
+    let assetdev = if cfg!(feature = "case-insensitive") {level(assetdev)} else {assetdev};
+
+    let body = if options.allow_relative {
+        let tail = if reserved.is_empty() {
+            format!("{}(?:\\.{}?)?", sequence, shot)
+        } else {
+            format!("{}\\.?|{}\\.{}", sequence, numbered, shot)
+        };
+        format!(
+            "{s}(?:\\.(?:{t}|{d}\\.{a}|\\.)?)?|\\.(?:{t}|{d}\\.{a}|\\.(?:{h}|\\.)?)?",
+            s = show, t = tail, d = assetdev, a = assetdev_shot, h = shot
+        )
+    } else {
+        let tail = if reserved.is_empty() {
+            format!("{}(?:\\.{})?", sequence, shot)
+        } else {
+            format!("{}|{}\\.{}", sequence, numbered, shot)
+        };
+        format!("{}(?:\\.(?:{}|{}\\.{}))?", show, tail, assetdev, assetdev_shot)
+    };
+    if options.trim_whitespace {
+        format!("^\\s*(?:{})\\s*$", body)
+    } else {
+        format!("^(?:{})$", body)
+    }
+}
+
+// the classes matching a letter, and an alphanumeric character
+fn classes(any_case: bool) -> (&'static str, &'static str) {
+    if any_case {("[A-Za-z]", "[A-Za-z0-9]")} else {("[A-Z]", "[A-Z0-9]")}
+}
+
+// match a name, which starts and ends with a letter, other than the supplied
+// uppercase names. The shared syntax has no lookahead, so the names are
+// excluded by walking a trie of them, matching wherever input departs from it.
+fn name_except(excluded: &[String], any_case: bool) -> String {
+    let excluded = excluded.iter().map(String::as_str).collect::<Vec<_>>();
+    suffix_except(&excluded, true, false, any_case)
+}
+
+// match the rest of a name, given the suffixes of the excluded names which
+// share its prefix
+fn suffix_except(excluded: &[&str], first: bool, after_letter: bool, any_case: bool) -> String {
+    let (letter, alphanumeric) = classes(any_case);
+    let mut children = BTreeMap::<char, Vec<&str>>::new();
+    for suffix in excluded {
+        if let Some(c) = suffix.chars().next() {
+            children.entry(c).or_default().push(&suffix[c.len_utf8()..]);
+        }
+    }
+    let mut branches = Vec::new();
+    for (c, suffixes) in &children {
+        if c.is_ascii_alphabetic() || !first {
+            branches.push(format!("{}{}", literal(&c.to_string(), any_case), suffix_except(suffixes, false, c.is_ascii_alphabetic(), any_case)));
+        }
+    }
+    // characters departing from the excluded names end the name freely
+    let departs = |c: &char| !children.contains_key(&c.to_ascii_uppercase());
+    let mut letters = ('A'..='Z').filter(departs).collect::<Vec<_>>();
+    if any_case {
+        letters.extend(('a'..='z').filter(departs));
+    }
+    if let Some(letters) = class(&letters) {
+        branches.push(format!("{}(?:{}*{})?", letters, alphanumeric, letter));
+    }
+    if !first {
+        if let Some(digits) = class(&('0'..='9').filter(departs).collect::<Vec<_>>()) {
+            branches.push(format!("{}{}*{}", digits, alphanumeric, letter));
+        }
+    }
+    // the prefix is itself a name, unless it is excluded
+    let ends = !first && after_letter && !excluded.contains(&"");
+    format!("(?:{}){}", branches.join("|"), if ends {"?"} else {""})
+}
+
+// a character class matching the supplied ascii characters, or None if
+// there are none
+fn class(chars: &[char]) -> Option<String> {
+    let mut ranges: Vec<(char, char)> = Vec::new();
+    for &c in chars {
+        match ranges.last_mut() {
+            Some((_, end)) if *end as u8 + 1 == c as u8 => *end = c,
+            _ => ranges.push((c, c)),
+        }
+    }
+    if ranges.is_empty() {
+        return None;
+    }
+    let ranges = ranges.iter()
+        .map(|&(start, end)| if start == end {start.to_string()} else {format!("{}-{}", start, end)})
+        .collect::<String>();
+    Some(format!("[{}]", ranges))
+}
+
+// match a name literally, or regardless of case
+fn literal(name: &str, any_case: bool) -> String {
+    name.chars().map(|c| {
+        if any_case && c.is_ascii_alphabetic() {
+            format!("[{}{}]", c.to_ascii_uppercase(), c.to_ascii_lowercase())
+        } else {
+            regex::escape(&c.to_string())
+        }
+    }).collect()
+}
+
+/// Build a JSON Schema describing levelspec strings accepted by `options`,
+/// for use in API definitions. Available with the `json` feature.
+/// 
+/// # Parameters
+/// 
+/// * `options` - The ParseOptions input is parsed with
+/// 
+/// # Returns
+/// A JSON Schema for a string property
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{levelspec_json_schema, ParseOptions};
+/// 
+/// let schema = levelspec_json_schema(&ParseOptions::default());
+/// assert_eq!(schema["type"], "string");
+/// ```
+#[cfg(feature = "json")]
+pub fn levelspec_json_schema(options: &ParseOptions) -> serde_json::Value {
+    serde_json::json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "title": "LevelSpec",
+        "description": "A show, sequence, or shot, with levels separated by periods",
+        "type": "string",
+        "pattern": levelspec_regex(options),
+        "examples": ["DEV01", "DEV01.RD", "DEV01.RD.0001"],
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ReservedSequences;
+    use regex::Regex;
+
+    // every combination of up to three levels drawn from a set of names
+    fn corpus() -> Vec<String> {
+        let levels = [
+            "", "DEV01", "dev01", "RD", "R1", "ASSETDEV", "assetdev", "ASSETDEVX", "0001", "01", "FOO1A", "%", "R%", "A_B",
+            "EDIT", "edit", "ED", "EDITS", "ED1T",
+        ];
+        let mut inputs = Vec::new();
+        for a in &levels {
+            inputs.push(a.to_string());
+            for b in &levels {
+                inputs.push(format!("{}.{}", a, b));
+                for c in &levels {
+                    inputs.push(format!("{}.{}.{}", a, b, c));
+                }
+            }
+        }
+        let extras = [" DEV01.RD ", "DEV01.RD.0001.X", "...", "....", ".RD..", "DEV01.RD..", "DEV01...", "..0001.", "...0001"];
+        inputs.extend(extras.iter().map(|input| input.to_string()));
+        inputs
+    }
+
+    #[test]
+    fn agrees_with_the_parser() {
+        let reserved = ReservedSequences::default().with_sequence("EDIT").with_sequence("ed1t").with_sequence("A_B");
+        let options = vec![
+            ParseOptions::default(),
+            ParseOptions::default().with_wildcards(false),
+            ParseOptions::default().with_relative(false),
+            ParseOptions::default().with_wildcards(false).with_relative(false).with_shot_padding(4),
+            ParseOptions::default().with_trim_whitespace(true).with_uppercase(true),
+            ParseOptions::default().with_reserved_sequences(reserved.clone()),
+            ParseOptions::default().with_relative(false).with_reserved_sequences(reserved.clone()),
+            ParseOptions::default().with_wildcards(false).with_shot_padding(4).with_reserved_sequences(reserved.clone()),
+            ParseOptions::default().with_trim_whitespace(true).with_uppercase(true).with_reserved_sequences(reserved),
+        ];
+        for options in options {
+            let re = Regex::new(&levelspec_regex(&options)).unwrap();
+            let disagreements = corpus().into_iter()
+                .filter(|input| re.is_match(input) != options.parse(input).is_ok())
+                .collect::<Vec<_>>();
+            assert!(disagreements.is_empty(), "{:?} with {:?}", disagreements, options);
+        }
+    }
+}
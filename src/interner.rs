@@ -0,0 +1,220 @@
+use crate::{LevelSpec, LevelSpecRef, LevelSpecterError as LSE, LevelTypeRef, LineError};
+use std::collections::HashSet;
+use std::fmt;
+use std::sync::Arc;
+
+/// Pool of shared level names. Parsing many LevelSpecs through an Interner
+/// yields InternedLevelSpecs whose repeated shows and sequences share a
+/// single allocation.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::Interner;
+/// use std::sync::Arc;
+/// 
+/// let mut interner = Interner::new();
+/// let first = interner.parse("DEV01.RD.0001").unwrap();
+/// let second = interner.parse("DEV01.RD.0002").unwrap();
+/// assert!(Arc::ptr_eq(first.show_str(), second.show_str()));
+/// assert_eq!(interner.len(), 4);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Interner {
+    names: HashSet<Arc<str>>,
+}
+
+impl Interner {
+    /// New up an empty Interner
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Retrieve the shared copy of the supplied name, adding it if necessary
+    pub fn intern(&mut self, name: &str) -> Arc<str> {
+        if let Some(existing) = self.names.get(name) {
+            return existing.clone();
+        }
+        let name: Arc<str> = Arc::from(name);
+        self.names.insert(name.clone());
+        name
+    }
+
+    /// Parse the input, interning each level. Parsing is as per `LevelSpec::new`,
+    /// but levels are interned straight from the input, so names seen before
+    /// cost no allocation.
+    pub fn parse(&mut self, input: &str) -> Result<InternedLevelSpec, LSE> {
+        Ok(self.intern_levelspec_ref(&LevelSpecRef::parse(input)?))
+    }
+
+    /// Parse many inputs through this Interner, as per `parse_many`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `lines` - The strs to parse, one LevelSpec each
+    /// 
+    /// # Returns
+    /// A Vec of results in input order. Errors carry the 1-based line number
+    /// of the input which failed to parse.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::Interner;
+    /// 
+    /// let mut interner = Interner::new();
+    /// let results = interner.parse_many(&["DEV01.RD.0001", "DEV01.RD.0002", "DEV01..RD"]);
+    /// assert!(results[0].is_ok() && results[1].is_ok());
+    /// assert_eq!(results[2].as_ref().unwrap_err().line, 3);
+    /// assert_eq!(interner.len(), 4);
+    /// ```
+    pub fn parse_many(&mut self, lines: &[&str]) -> Vec<Result<InternedLevelSpec, LineError>> {
+        let mut results = Vec::with_capacity(lines.len());
+        results.extend(lines.iter().enumerate().map(|(idx, line)| {
+            self.parse(line).map_err(|error| LineError { line: idx + 1, error })
+        }));
+        results
+    }
+
+    /// Intern the levels of an existing LevelSpec
+    pub fn intern_levelspec(&mut self, levelspec: &LevelSpec) -> InternedLevelSpec {
+        self.intern_levelspec_ref(&LevelSpecRef::from(levelspec))
+    }
+
+    /// Intern the levels of a borrowed LevelSpecRef
+    pub fn intern_levelspec_ref(&mut self, levelspec: &LevelSpecRef) -> InternedLevelSpec {
+        InternedLevelSpec {
+            show: self.intern(levelspec.show().to_str()),
+            sequence: levelspec.sequence().map(|level| self.intern(level.to_str())),
+            shot: levelspec.shot().map(|level| self.intern(level.to_str())),
+        }
+    }
+
+    /// Retrieve the number of distinct names interned
+    pub fn len(&self) -> usize {
+        self.names.len()
+    }
+
+    /// Returns true if no names have been interned
+    pub fn is_empty(&self) -> bool {
+        self.names.is_empty()
+    }
+}
+
+/// A LevelSpec whose levels are shared with other InternedLevelSpecs built by
+/// the same Interner. Cloning is cheap, as levels are reference counted. Levels
+/// are stored in their display form, and are accessed as LevelTypeRefs.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+pub struct InternedLevelSpec {
+    show: Arc<str>,
+    sequence: Option<Arc<str>>,
+    shot: Option<Arc<str>>,
+}
+
+impl InternedLevelSpec {
+    /// Retrieve the show
+    pub fn show(&self) -> LevelTypeRef<'_> {
+        LevelTypeRef::from(&*self.show)
+    }
+
+    /// Retrieve the sequence, if present
+    pub fn sequence(&self) -> Option<LevelTypeRef<'_>> {
+        self.sequence.as_deref().map(LevelTypeRef::from)
+    }
+
+    /// Retrieve the shot, if present
+    pub fn shot(&self) -> Option<LevelTypeRef<'_>> {
+        self.shot.as_deref().map(LevelTypeRef::from)
+    }
+
+    /// Retrieve the shared show name
+    pub fn show_str(&self) -> &Arc<str> {
+        &self.show
+    }
+
+    /// Retrieve the shared sequence name, if present
+    pub fn sequence_str(&self) -> Option<&Arc<str>> {
+        self.sequence.as_ref()
+    }
+
+    /// Retrieve the shared shot name, if present
+    pub fn shot_str(&self) -> Option<&Arc<str>> {
+        self.shot.as_ref()
+    }
+
+    /// Convert to an owned LevelSpec
+    pub fn to_levelspec(&self) -> LevelSpec {
+        LevelSpec {
+            show: self.show().to_level_type(),
+            sequence: self.sequence().map(|level| level.to_level_type()),
+            shot: self.shot().map(|level| level.to_level_type()),
+        }
+    }
+}
+
+impl From<&InternedLevelSpec> for LevelSpec {
+    fn from(interned: &InternedLevelSpec) -> Self {
+        interned.to_levelspec()
+    }
+}
+
+impl fmt::Display for InternedLevelSpec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.show)?;
+        if let Some(ref sequence) = self.sequence {
+            write!(f, ".{}", sequence)?;
+        }
+        if let Some(ref shot) = self.shot {
+            write!(f, ".{}", shot)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trips_levelspecs() {
+        let mut interner = Interner::new();
+        for input in &["DEV01", "DEV01.RD", "DEV01.%.0001", ".RD.0001"] {
+            let interned = interner.parse(input).unwrap();
+            assert_eq!(interned.to_levelspec(), LevelSpec::new(input).unwrap());
+            assert_eq!(interned.to_string(), LevelSpec::new(input).unwrap().to_string());
+        }
+    }
+
+    #[test]
+    fn shares_repeated_names() {
+        let mut interner = Interner::new();
+        let specs = (1..=100).map(|shot| interner.parse(&format!("DEV01.RD.{:04}", shot)).unwrap()).collect::<Vec<_>>();
+        assert_eq!(interner.len(), 102);
+        assert!(specs.iter().all(|ls| Arc::ptr_eq(ls.sequence_str().unwrap(), specs[0].sequence_str().unwrap())));
+    }
+
+    #[test]
+    fn exposes_level_types() {
+        let mut interner = Interner::new();
+        let interned = interner.parse("DEV01.%").unwrap();
+        assert_eq!(interned.show(), LevelTypeRef::Term("DEV01"));
+        assert_eq!(interned.sequence(), Some(LevelTypeRef::Wildcard));
+        assert_eq!(interned.shot(), None);
+    }
+
+    #[test]
+    fn reports_parse_errors() {
+        assert!(Interner::new().parse("DEV01..RD.0001").is_err());
+    }
+
+    #[test]
+    fn parse_many_shares_names_across_lines() {
+        let mut interner = Interner::new();
+        let results = interner.parse_many(&["DEV01.RD.0001", "bad_line", "DEV01.RD.0002"]);
+        assert_eq!(results[1].as_ref().unwrap_err().line, 2);
+        let first = results[0].as_ref().unwrap();
+        let last = results[2].as_ref().unwrap();
+        assert!(Arc::ptr_eq(first.show_str(), last.show_str()));
+        assert_eq!(interner.len(), 4);
+    }
+}
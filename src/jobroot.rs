@@ -0,0 +1,134 @@
+use crate::{LevelSpec, ReservedSequences};
+use std::env;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+
+/// Name of the environment variable consulted for the global JobRoot when
+/// none has been set via `JobRoot::set_global`
+pub const JOBS_ROOT_VAR: &str = "DD_JOBS_ROOT";
+/// The global JobRoot used when neither `set_global` nor `DD_JOBS_ROOT` supply one
+pub const DEFAULT_JOBS_ROOT: &str = "/jobs";
+
+static GLOBAL_ROOT: RwLock<Option<JobRoot>> = RwLock::new(None);
+
+/// The directory under which each show's tree lives, so that a LevelSpec
+/// maps to `<root>/<show>/<sequence>/<shot>`. Use a `PathTemplate` for
+/// layouts which differ from this.
+///
+/// `From<&LevelSpec> for PathBuf` uses the global JobRoot, which is, in
+/// order of precedence, the root supplied to `set_global`, the value of
+/// `DD_JOBS_ROOT`, or `/jobs`.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, JobRoot};
+/// use std::path::PathBuf;
+///
+/// let root = JobRoot::new("/mnt/jobs");
+/// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+/// assert_eq!(root.path_for(&ls), PathBuf::from("/mnt/jobs/DEV01/RD/0001"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct JobRoot {
+    root: PathBuf,
+    reserved_sequences: ReservedSequences,
+}
+
+impl JobRoot {
+    /// New up a JobRoot from the directory holding the shows
+    pub fn new<P>(root: P) -> Self
+    where
+        P: AsRef<Path>
+    {
+        Self { root: root.as_ref().to_path_buf(), reserved_sequences: ReservedSequences::default() }
+    }
+
+    /// Set the sequences whose own templates `path_for` renders with, and
+    /// return self. See `ReservedSequences`.
+    pub fn with_reserved_sequences(mut self, reserved_sequences: ReservedSequences) -> Self {
+        self.reserved_sequences = reserved_sequences;
+        self
+    }
+
+    /// Retrieve the global JobRoot. See `JobRoot` for how it is determined.
+    pub fn global() -> Self {
+        let root = GLOBAL_ROOT.read().unwrap_or_else(|e| e.into_inner()).clone();
+        match root {
+            Some(root) => root,
+            None => Self::new(env::var_os(JOBS_ROOT_VAR)
+                .filter(|root| !root.is_empty())
+                .unwrap_or_else(|| DEFAULT_JOBS_ROOT.into())),
+        }
+    }
+
+    /// Set the global JobRoot for the rest of the process, taking precedence
+    /// over `DD_JOBS_ROOT`
+    pub fn set_global(root: JobRoot) {
+        *GLOBAL_ROOT.write().unwrap_or_else(|e| e.into_inner()) = Some(root);
+    }
+
+    /// Retrieve the root directory
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Retrieve the directory of the supplied LevelSpec beneath the root.
+    /// Levels are used verbatim, so wildcards appear as `%`. Relative levels
+    /// end the path; resolve them first via `rel_to_abs`. A sequence among
+    /// the JobRoot's reserved sequences with its own template is rendered
+    /// with it, using the JobRoot as its `{root}` unless the template has a
+    /// root of its own.
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to locate
+    ///
+    /// # Returns
+    /// The LevelSpec's directory
+    pub fn path_for(&self, levelspec: &LevelSpec) -> PathBuf {
+        match levelspec.reserved_template(&self.reserved_sequences) {
+            Some(template) if template.root().is_none() => return template.clone().with_root(&self.root).render_unchecked(levelspec),
+            Some(template) => return template.render_unchecked(levelspec),
+            None => (),
+        }
+        levelspec.to_vec_str().iter()
+            .take_while(|level| !level.is_relative())
+            .fold(self.root.clone(), |path, level| path.join(level.to_str()))
+    }
+}
+
+impl AsRef<Path> for JobRoot {
+    fn as_ref(&self) -> &Path {
+        &self.root
+    }
+}
+
+impl From<&LevelSpec> for PathBuf {
+    /// Locate the LevelSpec beneath the global JobRoot
+    fn from(levelspec: &LevelSpec) -> Self {
+        JobRoot::global().path_for(levelspec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn relative_levels_end_the_path() {
+        let root = JobRoot::new("/jobs");
+        assert_eq!(root.path_for(&LevelSpec::from_str("DEV01.%").unwrap()), PathBuf::from("/jobs/DEV01/%"));
+        assert_eq!(root.path_for(&LevelSpec::from_str("DEV01..").unwrap()), PathBuf::from("/jobs/DEV01"));
+        assert_eq!(root.path_for(&LevelSpec::from_str(".RD").unwrap()), PathBuf::from("/jobs"));
+    }
+
+    #[test]
+    fn conversion_uses_global_root() {
+        JobRoot::set_global(JobRoot::new("/mnt/jobs"));
+        let ls = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(PathBuf::from(&ls), PathBuf::from("/mnt/jobs/DEV01/RD"));
+        assert_eq!(ls.to_path_under(JobRoot::global()), PathBuf::from("/mnt/jobs/DEV01/RD"));
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{LevelSpec, LevelName, LevelType, EnvVars};
+use std::env;
+
+/// Absolute values used to resolve the relative levels of a LevelSpec.
+/// A context is typically built from the environment or from the
+/// LevelSpec of the current shot.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, LevelContext};
+///
+/// let context = LevelContext::from(&LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// let ls = LevelSpec::new("..0002").unwrap();
+/// assert_eq!(ls.rel_to_abs_with(&context), Ok(LevelSpec::from_shot("DEV01", "RD", "0002")));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LevelContext {
+    pub show: Option<String>,
+    pub sequence: Option<String>,
+    pub shot: Option<String>,
+}
+
+impl LevelContext {
+    /// New up a LevelContext from optional show, sequence, and shot values
+    pub fn new<I>(show: Option<I>, sequence: Option<I>, shot: Option<I>) -> Self
+    where
+        I: Into<String>
+    {
+        Self {
+            show: show.map(Into::into),
+            sequence: sequence.map(Into::into),
+            shot: shot.map(Into::into),
+        }
+    }
+
+    /// New up a LevelContext from the `DD_SHOW`, `DD_SEQUENCE`, and `DD_SHOT`
+    /// environment variables
+    pub fn from_env() -> Self {
+        Self::from_env_with(&EnvVars::default())
+    }
+
+    /// New up a LevelContext from the environment variables named by `vars`.
+    /// Unset and empty variables are left as None.
+    pub fn from_env_with(vars: &EnvVars) -> Self {
+        let read = |name: &str| env::var(name).ok().filter(|val| !val.is_empty());
+        Self {
+            show: read(&vars.show),
+            sequence: read(&vars.sequence),
+            shot: read(&vars.shot),
+        }
+    }
+
+    /// Retrieve the value of the supplied level, if present
+    pub fn get(&self, level: LevelName) -> Option<&str> {
+        match level {
+            LevelName::Show => self.show.as_deref(),
+            LevelName::Sequence => self.sequence.as_deref(),
+            LevelName::Shot => self.shot.as_deref(),
+        }
+    }
+}
+
+impl From<&LevelSpec> for LevelContext {
+    /// Build a context from the term levels of a LevelSpec. Wildcard
+    /// and relative levels cannot resolve anything and are skipped.
+    fn from(levelspec: &LevelSpec) -> Self {
+        let term = |level: Option<&LevelType>| match level {
+            Some(LevelType::Term(val)) => Some(val.to_string()),
+            _ => None,
+        };
+        Self {
+            show: term(Some(levelspec.show())),
+            sequence: term(levelspec.sequence()),
+            shot: term(levelspec.shot()),
+        }
+    }
+}
@@ -1,17 +1,4 @@
-#![allow(unused_imports)]
-use nom::{
-    IResult,
-    Err as NomErr,
-    error::ErrorKind,
-    branch::alt,
-    combinator::{all_consuming, map},
-    bytes::complete::{tag, tag_no_case},
-    character::complete::digit1,
-    sequence::{tuple, preceded, terminated },
-    multi::{ fold_many1},
-};
-use crate::{LevelSpecterError, LevelType};
-use aschar_casesensitive::{ upperalphanum1, alpha_alphanum_upper, alpha_alphanum, alpha_alphanum_upper_alpha, alpha_alphanum_alpha};
+use crate::{LevelSpecterError, LevelType, LevelName, LevelSpecRef, ParseFailure, levelspec::ASSETDEV, ReservedSequences};
 
 pub type LevelTypeVec = Vec<LevelType>;
 
@@ -24,7 +11,8 @@ pub type LevelTypeVec = Vec<LevelType>;
 /// # Returns
 /// 
 /// A `Vec` of `LevelType` capturing the show, sequence, shot, if successful. Otherwise,
-/// a LevelSpecterError
+/// a LevelSpecterError. The `Vec` is built from the fixed size result of
+/// `levelspec_ref_parser`, which `LevelSpec::from_str` uses directly.
 /// 
 /// # Example
 /// 
@@ -42,16 +30,50 @@ pub type LevelTypeVec = Vec<LevelType>;
 /// assert_eq!(results, Ok(expect));
 /// ```
 pub fn levelspec_parser(input: &str) -> Result<LevelTypeVec, LevelSpecterError> {
-    match levelparser(input) {
-        Err(_) => Err( LevelSpecterError::ParseError(format!("Unable to parse levelspec for {}", input))),
-        Ok((_,ls)) => Ok(ls),
-    }
+    levelspec_ref_parser(input).map(|levels| levels.to_level_types())
+}
+
+/// Parse a levelspec from a string without allocating, borrowing each level
+/// from the input. This is the parser behind `levelspec_parser` and
+/// `LevelSpecRef::parse`.
+/// 
+/// # Parameters
+/// 
+/// * `input` - str we wish to convert to a levelspec
+/// 
+/// # Returns
+/// 
+/// A `LevelSpecRef` borrowing from `input` if successful. Otherwise, a ParseError
+/// locating the failure, or TooManyLevels if there are more than three levels.
+/// Failing allocates nothing either.
+pub fn levelspec_ref_parser(input: &str) -> Result<LevelSpecRef<'_>, LevelSpecterError> {
+    levelparser(input)
+}
+
+/// Returns true if the input is a levelspec, as `LevelSpec::from_str` would
+/// accept. Neither a LevelSpec nor an error is built, so checking allocates
+/// nothing. Use `is_valid_with` to apply `ParseOptions`.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::is_valid;
+/// 
+/// assert!(is_valid("DEV01.RD.0001"));
+/// assert!(!is_valid("DEV01..0001"));
+/// ```
+pub fn is_valid(input: &str) -> bool {
+    levelparser(input).is_ok()
 }
 
 #[cfg(test)]
 mod levelspec_parser_tests {
     use super::*;
 
+    fn invalid(level: LevelName, position: usize) -> LevelSpecterError {
+        LevelSpecterError::ParseError(ParseFailure::InvalidLevel { level, position })
+    }
+
     //
     // SHOW
     //
@@ -84,31 +106,31 @@ mod levelspec_parser_tests {
         #[test]
         fn cannot_parse_lowercase() {
             let ls = levelspec_parser("dev01");           
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for dev01".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)));
         }
 
         #[test]
         fn cannot_start_with_number() {
             let ls = levelspec_parser("1DEV01");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for 1DEV01".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)));
         }
         
         #[test]
         fn cannot_have_space() {
             let ls = levelspec_parser("DEV 01");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV 01".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)));
         }
         
         #[test]
         fn cannot_have_wildcard_and_chars() {
             let ls = levelspec_parser("DEV%01");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV%01".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)));
         }
 
         #[test]
         fn cannot_have_underscore() {
             let ls = levelspec_parser("DEV_01");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV_01".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)));
         }
 
         #[test]
@@ -189,31 +211,31 @@ mod levelspec_parser_tests {
         #[test]
         fn can_parse_assetdev_lowercase() {
             let ls = levelspec_parser("dev01.assetdev");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for dev01.assetdev".to_string())))
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)))
         }
 
         #[test]
         fn cannot_start_with_number() {
             let ls = levelspec_parser("DEV01.1D");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.1D".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Sequence, 6)));
         }
         
         #[test]
         fn cannot_have_space() {
             let ls = levelspec_parser("DEV01.R D");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.R D".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Sequence, 6)));
         }
         
         #[test]
         fn cannot_have_wildcard_and_chars() {
             let ls = levelspec_parser("DEV01.R%");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.R%".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Sequence, 6)));
         }
 
         #[test]
         fn cannot_have_underscore() {
             let ls = levelspec_parser("DEV01.R_D");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.R_D".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Sequence, 6)));
         }
 
         #[test]
@@ -306,31 +328,31 @@ mod levelspec_parser_tests {
         #[test]
         fn cannot_parse_assetdev_lowercase() {
             let ls = levelspec_parser("dev01.assetdev.foobar");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for dev01.assetdev.foobar".to_string())))
+            assert_eq!(ls, Err(invalid(LevelName::Show, 0)))
         }
 
         #[test]
         fn cannot_start_with_letter() {
             let ls = levelspec_parser("DEV01.RD.R0001");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.RD.R0001".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Shot, 9)));
         }
         
         #[test]
         fn cannot_have_space() {
             let ls = levelspec_parser("DEV01.RD.0 001");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.RD.0 001".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Shot, 9)));
         }
         
         #[test]
         fn cannot_have_wildcard_and_chars() {
             let ls = levelspec_parser("DEV01.RD.00%");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.RD.00%".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Shot, 9)));
         }
 
         #[test]
         fn cannot_have_underscore() {
             let ls = levelspec_parser("DEV01.RD.0_001");
-            assert_eq!(ls, Err(LevelSpecterError::ParseError("Unable to parse levelspec for DEV01.RD.0_001".to_string())));
+            assert_eq!(ls, Err(invalid(LevelName::Shot, 9)));
         }
     }
 
@@ -344,1327 +366,327 @@ mod levelspec_parser_tests {
         }
 }
 
-
-//-------------------//
-//    parse_show     //
-//-------------------//
-
-#[inline]
-fn parse_show(input: &str) -> IResult<&str, &str> {
-    alt((
-        if cfg!(feature = "case-insensitive") {alpha_alphanum} else {alpha_alphanum_upper},
-        tag("%")
-    ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_show {
-    use super::*;
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_show() {
-        let ls = parse_show("dev01");
-        assert_eq!(ls, Ok(("","dev01")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_show() {
-        let ls = rel_shot_alt("dev01");
-        assert_eq!(ls, Err(NomErr::Error(("dev01", ErrorKind::Tag))))
-    }  
-}
-
-//--------------------//
-//     parse_seq      //
-//--------------------//
-#[inline]
-fn parse_seq(input: &str) -> IResult<&str, &str> {
-    alt((
-        preceded(tag("."), if cfg!(feature = "case-insensitive") {alpha_alphanum_alpha} else {alpha_alphanum_upper_alpha}),
-        preceded(tag("."), tag("%"))
-    ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_seq {
-    use super::*;
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_seq() {
-        let ls = parse_seq(".rd");
-        assert_eq!(ls, Ok(("", "rd")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_seq() {
-        let ls = rel_shot_alt(".rd");
-        assert_eq!(ls, Err(NomErr::Error(("rd", ErrorKind::Tag))))
-    }  
-}
-
-//---------------------//
-//      parse_shot     //
-//---------------------//
-#[inline]
-fn parse_shot(input: &str) -> IResult<&str, &str> {
-    alt((
-    preceded(tag("."), digit1 ),
-    preceded(tag("."), tag("%"))
-    ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_shot {
-    use super::*;
-
-    #[test]
-    fn can_parse_shot() {
-        let ls = parse_shot(".0001");
-        assert_eq!(ls, Ok(("", "0001")))
-    }
-}
-
-
-//-----------------------//
-//   parse_assetdev_seq  //
-//-----------------------//
-#[inline]
-#[cfg(feature = "case-insensitive")]
-fn parse_assetdev_seq(input: &str) -> IResult<&str, &str> {
-    alt((
-        preceded(tag("."),tag_no_case("ASSETDEV")),
-        preceded(tag("."), tag("%"))
-    ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_assetdev_seq_case_insensitive {
-    use super::*;
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_assetdev() {
-        let ls = parse_assetdev_seq(".assetdev");
-        assert_eq!(ls, Ok(("","assetdev")))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_assetdev_capital() {
-        let ls = parse_assetdev_seq(".ASSETDEV");
-        assert_eq!(ls, Ok(("","ASSETDEV")))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_wildcard() {
-        let ls = parse_assetdev_seq(".%");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-    
-}
-
-//---------------------------//
-//    parse_assetdev_seq     //
-//---------------------------//
-// parse the assetdev sequence
-#[inline]
-#[cfg(not(feature = "case-insensitive"))]
-fn parse_assetdev_seq(input: &str) -> IResult<&str, &str> {
-    // TODO: this may be a problem as we are not backtracking
-    // if .% is matched here, will that limit shots that are
-    // matched afterwards to the assetdev_shot?. probably. this
-    // is order dependent i would surmise. i should probably 
-    // remove the % as I dont want to match against asssetdev shots 
-    // if the sequence is unknown
-    //alt((
-        preceded(tag("."),tag("ASSETDEV"))//,
-        //preceded(tag("."), tag("%"))
-    //))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_assetdev_seq_case_sensitive {
-    use super::*;
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_assetdev_lower() {
-        let ls = parse_assetdev_seq(".assetdev");
-        assert_eq!(ls, Err(NomErr::Error(("assetdev", ErrorKind::Tag))))
-    }  
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_assetdev_capital() {
-        let ls = parse_assetdev_seq(".ASSETDEV");
-        assert_eq!(ls, Ok(("","ASSETDEV")))
-    }  
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_seq_other_than_assetdev() {
-        let ls = parse_assetdev_seq(".RD");
-        assert_eq!(ls, Err(NomErr::Error(("RD", ErrorKind::Tag))))
-    }  
-
-/*
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_wildcard() {
-        let ls = parse_assetdev_seq(".%");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-    */
-}
-
-
-//---------------------//
-// parse_assetdev_shot //
-//---------------------//
-#[inline]
-fn parse_assetdev_shot(input: &str) -> IResult<&str, &str> {
-    alt((
-        preceded(tag("."), if cfg!(feature = "case-insensitive") {alpha_alphanum_alpha} else {alpha_alphanum_upper_alpha} ),
-        preceded(tag("."), tag("%"))
-    ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_assetdev_shot {
-    use super::*;
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_assetdev_shot_lower() {
-        let ls = parse_assetdev_shot(".foobar");
-        assert_eq!(ls, Err(NomErr::Error(("foobar", ErrorKind::Tag))))
-    }  
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_assetdev_shot_capital() {
-        let ls = parse_assetdev_shot(".FOOBAR");
-        assert_eq!(ls, Ok(("","FOOBAR")))
-    }  
-
-    #[test]
-   #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_wildcard() {
-        let ls = parse_assetdev_shot(".%");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-}
-
 //------------------------//
-//     parse_rel_seq      //
+//       levelparser      //
 //------------------------//
-// parse relative sequence. 
-// EG .RD or .%
-fn parse_rel_seq(input: &str) -> IResult<&str, &str> {
-     alt((
-        preceded(tag("."), if cfg!(feature = "case-insensitive") {alpha_alphanum_alpha} else {alpha_alphanum_upper_alpha}),
-        preceded(tag("."), tag("%")),
-     ))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_rel_seq {
-    use super::*;
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_relseq_lower() {
-        let ls = parse_rel_seq(".rd");
-        assert_eq!(ls, Err(NomErr::Error(("rd", ErrorKind::Tag))))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_relseq_lower() {
-        let ls = parse_rel_seq(".rd");
-        assert_eq!(ls, Ok(("","rd")))
-    }  
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_seq_capital() {
-        let ls = parse_rel_seq(".RD");
-        assert_eq!(ls, Ok(("","RD")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_wildcard() {
-        let ls = parse_rel_seq(".%");
-        assert_eq!(ls, Ok(("","%")))
-    }  
+// The parser makes a single pass over the input, splitting it into levels
+// on periods, and then checks each level against the rules for its position.
+// An empty level is relative. The rules are:
+//
+// * There are at most three levels
+// * A run of one to three periods alone is that many relative levels.
+//   Otherwise, at least one level must be absolute.
+// * A relative sequence may not separate an absolute show and shot, eg
+//   `DEV01..0001`
+// * Shows start with a letter, and are alphanumeric
+// * Sequences start and end with a letter, and are alphanumeric
+// * Shots are numeric, except within ASSETDEV, where they may also follow
+//   the rules for sequences, and within reserved sequences, where they must.
+//   Case insensitive builds also allow named shots within a wildcard sequence.
+// * Any absolute level may be a wildcard, `%`
+//
+// Names are uppercase, unless the crate is built case insensitive.
+fn levelparser(input: &str) -> Result<LevelSpecRef<'_>, LevelSpecterError> {
+    levelparser_with(input, None)
+}
+
+// Parse as `levelparser` does, treating the supplied sequences as reserved
+pub(crate) fn levelparser_with<'a>(input: &'a str, reserved: Option<&ReservedSequences>) -> Result<LevelSpecRef<'a>, LevelSpecterError> {
+    match input {
+        "." => return Ok(LevelSpecRef::from_levels("", None, None)),
+        ".." => return Ok(LevelSpecRef::from_levels("", Some(""), None)),
+        "..." => return Ok(LevelSpecRef::from_levels("", Some(""), Some(""))),
+        // runs of periods are the only entirely relative levelspecs
+        "" => return Err(invalid(ParseFailure::InvalidStructure { position: 0 })),
+        _ => (),
+    }
+    let mut levels = input.split('.');
+    // split always yields at least one level
+    let show = levels.next().unwrap_or_default();
+    let sequence = levels.next();
+    let shot = levels.next();
+    if levels.next().is_some() {
+        // a run of periods alone holds one relative level per period, rather
+        // than one per gap between them
+        let count = if input.bytes().all(|b| b == b'.') { input.len() } else { 4 + levels.count() };
+        return Err(LevelSpecterError::TooManyLevels(count));
+    }
+    validate_levels(show, sequence, shot, reserved)?;
+    Ok(LevelSpecRef::from_levels(show, sequence, shot))
+}
+
+// Check the levels against the rules for their positions. Failures are
+// located by their byte offset within the dotted form of the levels.
+pub(crate) fn validate_levels(show: &str, sequence: Option<&str>, shot: Option<&str>, reserved: Option<&ReservedSequences>) -> Result<(), LevelSpecterError> {
+    let sequence_at = show.len() + 1;
+    let shot_at = sequence_at + sequence.map_or(0, str::len) + 1;
+    if !show.is_empty() && sequence == Some("") && shot.is_some_and(|shot| !shot.is_empty()) {
+        return Err(invalid(ParseFailure::InvalidStructure { position: sequence_at }));
+    }
+    if !level_is(show, is_show) {
+        return Err(invalid(ParseFailure::InvalidLevel { level: LevelName::Show, position: 0 }));
+    }
+    if sequence.is_some_and(|sequence| !level_is(sequence, is_name)) {
+        return Err(invalid(ParseFailure::InvalidLevel { level: LevelName::Sequence, position: sequence_at }));
+    }
+    let shot_rule = match sequence {
+        Some(sequence) if shot.is_some() && reserved.is_some_and(|reserved| reserved.contains(sequence)) => is_name,
+        _ if allows_named_shot(sequence) => is_named_shot,
+        _ => is_shot,
+    };
+    if shot.is_some_and(|shot| !level_is(shot, shot_rule)) {
+        return Err(invalid(ParseFailure::InvalidLevel { level: LevelName::Shot, position: shot_at }));
+    }
+    Ok(())
 }
 
-//--------------------------//
-//  parse_rel_assetdev_seq  //
-//--------------------------//
-// parse relative assetdev sequence, case sensitive version
-// EG .ASSETDEV
 #[inline]
-#[cfg(not(feature = "case-insensitive"))]
-fn parse_rel_assetdev_seq(input: &str) -> IResult<&str, &str> {
-    preceded(tag("."), tag("ASSETDEV"))
-    (input)
+fn invalid(failure: ParseFailure) -> LevelSpecterError {
+    LevelSpecterError::ParseError(failure)
 }
 
-
-//--------------------------//
-//  parse_rel_assetdev_seq  //
-//--------------------------//
-// parse relative assetdev sequence, case insensitive version
-// EG .assetdev or .ASSETDEV
+// relative and wildcard levels are valid in any position
 #[inline]
-#[cfg(feature = "case-insensitive")]
-fn parse_rel_assetdev_seq(input: &str) -> IResult<&str, &str> {
-    preceded(tag("."), tag_no_case("assetdev"))
-    (input)
-}
-
-#[cfg(test)]
-mod parse_rel_assetdev_seq {
-    use super::*;
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_relseq_lower() {
-        let ls = parse_rel_assetdev_seq(".assetdev");
-        assert_eq!(ls, Err(NomErr::Error(("assetdev", ErrorKind::Tag))))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_relseq_lower() {
-        let ls = parse_rel_assetdev_seq(".assetdev");
-        assert_eq!(ls, Ok(("","assetdev")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_seq_capital() {
-        let ls = parse_rel_assetdev_seq(".ASSETDEV");
-        assert_eq!(ls, Ok(("","ASSETDEV")))
-    }  
-
+fn level_is(level: &str, rule: fn(&str) -> bool) -> bool {
+    level.is_empty() || level == "%" || rule(level)
 }
 
-
-//---------------------------//
-//    parse_rel_seq_rel      //
-//---------------------------//
-// parse relative sequence with trailing relative 
-// EG .RD.
 #[inline]
-fn parse_rel_seq_rel(input: &str) -> IResult<&str, &str> {
-    terminated( 
-        alt((
-            parse_rel_assetdev_seq, 
-            parse_rel_seq
-        )), 
-        tag(".")
-    )
-    (input)
+fn is_letter(c: u8) -> bool {
+    if cfg!(feature = "case-insensitive") {c.is_ascii_alphabetic()} else {c.is_ascii_uppercase()}
 }
 
-#[cfg(test)]
-mod parse_rel_seq_rel {
-    use super::*;
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_relseq_lower() {
-        let ls = parse_rel_seq_rel(".rd.");
-        assert_eq!(ls, Err(NomErr::Error(("rd.", ErrorKind::Tag))))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn cannot_parse_relseq_assetdev_lower() {
-        let ls = parse_rel_seq_rel(".assetdev.");
-        assert_eq!(ls, Err(NomErr::Error(("assetdev.", ErrorKind::Tag))))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_relseq_lower() {
-        let ls = parse_rel_seq_rel(".rd.");
-        assert_eq!(ls, Ok(("","rd")))
-    }  
-
-    #[test]
-    #[cfg(feature = "case-insensitive")]
-    fn can_parse_relseq_assetdev_lower() {
-        let ls = parse_rel_seq_rel(".assetdev.");
-        assert_eq!(ls, Ok(("","assetdev")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_seq_capital() {
-        let ls = parse_rel_seq_rel(".RD.");
-        assert_eq!(ls, Ok(("","RD")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_seq_assetdev_capital() {
-        let ls = parse_rel_seq_rel(".ASSETDEV.");
-        assert_eq!(ls, Ok(("","ASSETDEV")))
-    }  
-
-    #[test]
-    #[cfg(not(feature = "case-insensitive"))]
-    fn can_parse_wildcard() {
-        let ls = parse_rel_seq_rel(".%.");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-}
-
-
-//---------------------//
-//   parse_rel_shot    //
-//---------------------//
-// parse relative shot
-// EG ..0001
 #[inline]
-fn parse_rel_shot(input: &str) -> IResult<&str, &str> {
-    preceded(tag("."), parse_shot )(input)
-}
-
-#[cfg(test)]
-mod parse_rel_shot {
-    use super::*;
-
-    #[test]
-    fn can_parse_relshot_lower() {
-        let ls = parse_rel_shot("..0001");
-        assert_eq!(ls, Ok(("","0001")))
-    }  
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = parse_rel_shot("..%");
-        assert_eq!(ls, Ok(("","%")))
-    }  
+fn is_alphanumeric(c: u8) -> bool {
+    is_letter(c) || c.is_ascii_digit()
 }
 
-//----------------------//
-//  parse_show_rel_shot //
-//----------------------//
-
-// EG DEV01..
-fn parse_show_rel_shot(input: &str) -> IResult<&str, &str> {
-    terminated(parse_show, tag(".."))(input)
-}
-
-#[cfg(test)]
-mod parse_show_rel_shot {
-    use super::*;
-
-    #[test]
-    fn can_parse_show_relshot_lower() {
-        let ls = parse_show_rel_shot("DEV01..");
-        assert_eq!(ls, Ok(("","DEV01")))
-    }  
-
-    #[test]
-    fn can_show_parse_wildcard() {
-        let ls = parse_show_rel_shot("%..");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-}
-
-//----------------------//
-//  parse_show_rel_seq //
-//----------------------//
-
-// EG DEV01..
-fn parse_show_rel_seq(input: &str) -> IResult<&str, &str> {
-    terminated(parse_show, tag("."))(input)
-}
-
-#[cfg(test)]
-mod parse_show_rel_seq {
-    use super::*;
-
-    #[test]
-    fn can_parse_show_relshot_lower() {
-        let ls = parse_show_rel_seq("DEV01.");
-        assert_eq!(ls, Ok(("","DEV01")))
-    }  
-
-    #[test]
-    fn can_parse_show_rel_seq_wildcard() {
-        let ls = parse_show_rel_seq("%.");
-        assert_eq!(ls, Ok(("","%")))
-    }  
-}
-
-//----------------------//
-//       shot_alt       //
-//----------------------// 
-// The shot alternative, has a show a sequence, and a shot
-// accumulated into a vector. 
-#[inline]
-// EG DEV01.RD.0001
-fn shot_alt(input: &str) -> IResult<&str, Vec<LevelType>> {
-    map( //used to turn the tuple into a vector
-        alt((
-            tuple((parse_show, parse_assetdev_seq, parse_assetdev_shot)),
-            tuple((parse_show, parse_seq, parse_shot)),
-        )),
-        |item| {
-            let (show, seq, shot) = item;
-            let mut acc = Vec::with_capacity(3);
-            acc.push(LevelType::from(show)); 
-            acc.push(LevelType::from(seq)); 
-            acc.push(LevelType::from(shot));
-            acc
-        }
-    )
-    (input)
-}
-
-#[cfg(test)]
-mod shot_alt {
-    use super::*;
-
-    #[test]
-    fn can_parse() {
-        let ls = shot_alt("DEV01.RS.0001");
-        let expect = vec!["DEV01", "RS", "0001"].iter().map(|x| LevelType::Term(x.to_string())).collect::<Vec<LevelType>>() ;
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[test]
-    fn can_parse_assetdev() {
-        let ls = shot_alt("DEV01.ASSETDEV.FOOBAR");
-        let expect = vec!["DEV01", "ASSETDEV", "FOOBAR"].iter().map(|x| LevelType::Term(x.to_string())).collect::<Vec<LevelType>>();
-        assert_eq!(ls, Ok(("",expect)));
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = shot_alt("dev01.assetdev.foobar");
-        let expect: Vec<LevelType> = vec!["dev01", "assetdev", "foobar"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[cfg(not(feature = "case-insensitive"))]
-    #[test]
-    fn cannot_parse_assetdev_lowercase() {
-        let ls = shot_alt("dev01.assetdev.foobar");
-        assert_eq!(ls, Err(NomErr::Error(("dev01.assetdev.foobar", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn cannot_start_with_letter() {
-        let ls = shot_alt("DEV01.RD.R0001");
-        assert_eq!(ls, Err(NomErr::Error(("R0001", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = shot_alt("DEV01.RD.0 001");
-        let expect: Vec<LevelType> = vec!["DEV01", "RD", "0"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls,  Ok((" 001", expect)));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = shot_alt("DEV01.RD.00%");
-        let expect: Vec<LevelType> = vec!["DEV01", "RD", "00"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("%", expect)));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = shot_alt("DEV01.RD.0_001");
-        let expect: Vec<LevelType> = vec!["DEV01", "RD", "0"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("_001", expect)));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = shot_alt("DEV01.RS.%");
-        let expect: Vec<LevelType> = vec!["DEV01", "RS", "%"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-//-----------------------//
-//       seq_alt         //
-//-----------------------//
-// the sequence alternative has a show and a sequence
-// separated by a period, accumulated into a vector
-#[inline]
-// EG DEV01.RD
-fn seq_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map(
-        tuple((parse_show, parse_seq)),
-        | item| {
-            let mut acc = Vec::with_capacity(2);
-            let (show, seq) = item ;
-            acc.push(LevelType::from(show)); 
-            acc.push(LevelType::from(seq));
-            acc
-        } 
-    )
-    (input)
-}
-
-#[cfg(test)]
-mod seq_alt {
-    use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = seq_alt("DEV01.RD");
-        let expect: LevelTypeVec = vec!["DEV01", "RD"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("",expect)));
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_lowercase() {
-        let ls = seq_alt("dev01.rd");
-        let expect: LevelTypeVec = vec!["dev01", "rd"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[test]
-    fn can_parse_assetdev() {
-        let ls = seq_alt("DEV01.ASSETDEV");
-        let expect: LevelTypeVec = vec!["DEV01", "ASSETDEV"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = seq_alt("dev01.assetdev");
-        let expect: LevelTypeVec = vec!["dev01", "assetdev"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }
-
-    #[cfg(not(feature = "case-insensitive"))]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = seq_alt("dev01.assetdev");
-        assert_eq!(ls, Err(NomErr::Error(("dev01.assetdev", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn cannot_start_with_number() {
-        let ls = seq_alt("DEV01.1D");
-        assert_eq!(ls, Err(NomErr::Error(("1D", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = seq_alt("DEV01.R D");
-        let expect = vec!["DEV01", "R"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok((" D", expect)));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = seq_alt("DEV01.R%");
-        let expect: LevelTypeVec = vec!["DEV01", "R"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("%", expect)));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = seq_alt("DEV01.R_D");
-        let expect: LevelTypeVec = vec!["DEV01", "R"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("_D", expect)));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = seq_alt("DEV01.%");
-        let expect: LevelTypeVec = vec!["DEV01","%"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-//-----------------------//
-//       show_alt        //
-//-----------------------//
-#[inline]
 // EG DEV01
-fn show_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    // unlike the other levels, we cannot keep parsing until we are done, as 
-    // 
-    map(parse_show, |item| { 
-            let mut acc = Vec::new();
-            acc.push(LevelType::from(item)); 
-            acc
-        } 
-    )
-    (input)
+fn is_show(level: &str) -> bool {
+    let bytes = level.as_bytes();
+    is_letter(bytes[0]) && bytes.iter().all(|c| is_alphanumeric(*c))
 }
 
-
-#[cfg(test)]
-mod show_alt {
-    use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = show_alt("DEV01");
-        let expect: LevelTypeVec = vec!["DEV01"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_lowercase() {
-        let ls = show_alt("dev01");
-        let expect = vec!["dev01"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[test]
-    fn cannot_start_with_number() {
-        let ls = show_alt("1DEV01");
-        assert_eq!(ls, Err(NomErr::Error(("1DEV01", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = show_alt("DEV 01");
-        let expect: LevelTypeVec = vec!["DEV"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok((" 01", expect)));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = show_alt("DEV01%");
-        let expect: LevelTypeVec = vec!["DEV01"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("%", expect)));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = show_alt("DEV01_D");
-        let expect: LevelTypeVec = vec!["DEV01"].iter().map(|x| LevelType::Term(x.to_string())).collect();
-        assert_eq!(ls, Ok(("_D", expect)));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = show_alt("%");
-        let expect: LevelTypeVec = vec!["%"].iter().map(|_x| LevelType::Wildcard).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
+// EG RD. Used for sequences, and for shots within reserved sequences
+fn is_name(level: &str) -> bool {
+    let bytes = level.as_bytes();
+    is_show(level) && is_letter(bytes[bytes.len() - 1])
 }
 
-//-----------------------//
-// show_rel_shot_alt     //
-//-----------------------//
-#[inline]
-// DEV01..
-fn show_rel_shot_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to turn the tuple into a vector
-        parse_show_rel_shot,
-        |item| {
-            let mut acc = Vec::with_capacity(2);
-            acc.push(LevelType::from(item));
-            acc.push(LevelType::Relative); 
-            acc.push(LevelType::Relative); 
-            acc
-        } 
-    )
-    (input)
+// EG 0001
+fn is_shot(level: &str) -> bool {
+    level.bytes().all(|c| c.is_ascii_digit())
 }
 
-#[cfg(test)]
-mod show_rel_shot_alt {
-    use super::*;
-
-    #[test]
-    fn can_parse_show_rel_shot_lower() {
-        let ls = show_rel_shot_alt("DEV01..");
-        let expect: LevelTypeVec = vec!["DEV01","",""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }  
-
-    #[test]
-    fn can_show_parse_wildcard() {
-        let ls = show_rel_shot_alt("%..");
-        let expect: LevelTypeVec = vec!["%","",""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }  
+// EG FOO or 0001
+fn is_named_shot(level: &str) -> bool {
+    is_shot(level) || is_name(level)
 }
 
-//-----------------------//
-// show_rel_seq_alt      //
-//-----------------------//
-#[inline]
-// DEV01..
-fn show_rel_seq_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to turn the tuple into a vector
-        parse_show_rel_seq,
-        |item| {
-            let mut acc = Vec::with_capacity(2);
-            acc.push(LevelType::from(item));
-            acc.push(LevelType::Relative); 
-            acc
-        } 
-    )
-    (input)
-}
-
-
-#[cfg(test)]
-mod show_rel_seq_alt {
-    use super::*;
-
-    #[test]
-    fn can_parse_show_rel_seq_lower() {
-        let ls = show_rel_seq_alt("DEV01.");
-        let expect: LevelTypeVec =vec!["DEV01",""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }  
-
-    #[test]
-    fn wont_parse_show_rel_shot_lower() {
-        let ls = show_rel_seq_alt("DEV01..");
-        let expect: LevelTypeVec = vec!["DEV01",""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok((".", expect)))
-    }  
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = show_rel_seq_alt("%.");
-        let expect: LevelTypeVec = vec!["%",""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }  
-}
-
-//--------------------//
-//    rel_seq_alt     //
-//--------------------//
-
-// the sequence alternative has a show and a sequence
-// separated by a period, accumulated into a vector
-#[inline]
-// .RD
-fn rel_seq_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to turn the tuple into a vector
-        alt((
-            parse_rel_assetdev_seq,
-            parse_rel_seq,
-        )),
-        |item| {
-            let mut acc = Vec::with_capacity(2);
-            acc.push(LevelType::Relative); 
-            acc.push(LevelType::from(item));
-            acc
-        } 
-    )
-    (input)
-}
-
-
-#[cfg(test)]
-mod rel_seq_alt {
-    use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = rel_seq_alt(".RD");
-        let expect: LevelTypeVec = vec!["", "RD"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_lowercase() {
-        let ls = rel_seq_alt(".rd");
-        let expect: LevelTypeVec = vec!["", "rd"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[test]
-    fn can_parse_assetdev() {
-        let ls = rel_seq_alt(".ASSETDEV");
-        let expect: LevelTypeVec = vec!["", "ASSETDEV"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_alt(".assetdev");
-        let expect: LevelTypeVec = vec!["", "assetdev"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
+// Named shots are allowed within ASSETDEV, and, in case insensitive builds,
+// within a wildcard sequence
+fn allows_named_shot(sequence: Option<&str>) -> bool {
+    match sequence {
+        Some("%") => cfg!(feature = "case-insensitive"),
+        Some(sequence) if cfg!(feature = "case-insensitive") => sequence.eq_ignore_ascii_case(ASSETDEV),
+        Some(sequence) => sequence == ASSETDEV,
+        None => false,
     }
-
-    #[cfg(not(feature = "case-insensitive"))]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_alt(".assetdev");
-        assert_eq!(ls, Err(NomErr::Error(("assetdev", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn cannot_start_with_number() {
-        let ls = rel_seq_alt(".1D");
-        assert_eq!(ls, Err(NomErr::Error(("1D", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = rel_seq_alt(".R D");
-        let expect:LevelTypeVec = vec!["", "R"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok((" D", expect)));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = rel_seq_alt(".R%");
-        let expect: LevelTypeVec = vec!["", "R"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("%", expect)));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = rel_seq_alt(".R_D");
-        let expect: LevelTypeVec = vec!["", "R"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("_D", expect)));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = rel_seq_alt(".%");
-        let expect: LevelTypeVec = vec!["","%"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-//---------------------//
-//   rel_seq_rel_alt   //
-//---------------------//
-#[inline]
-// EG .RD.
-fn rel_seq_rel_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    fold_many1( //used to turn the tuple into a vector
-        //terminated(parse_rel_seq, tag(".")),
-        parse_rel_seq_rel,
-        Vec::with_capacity(3), 
-        |mut acc: Vec<_>, item| {
-            acc.push(LevelType::Relative); 
-            acc.push(LevelType::from(item));
-            acc.push(LevelType::Relative); 
-            acc
-        } 
-    )
-    (input)
 }
 
 #[cfg(test)]
-mod rel_seq_rel_alt {
+mod levelparser {
     use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = rel_seq_rel_alt(".RD.");
-        let expect: LevelTypeVec = vec!["", "RD", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
 
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_lowercase() {
-        let ls = rel_seq_rel_alt(".rd.");
-        let expect: LevelTypeVec = vec!["", "rd", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-
-    #[test]
-    fn can_parse_assetdev() {
-        let ls = rel_seq_rel_alt(".ASSETDEV.");
-        let expect: LevelTypeVec = vec!["", "ASSETDEV", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }
-
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_rel_alt(".assetdev.");
-        let expect: LevelTypeVec = vec!["", "assetdev", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
+    fn levels(input: &str) -> Option<Vec<&str>> {
+        levelparser(input).ok().map(|ls| ls.iter().map(|level| level.to_str()).collect())
+    }
+
+    // Inputs and results carried over from the tests of the nom parser's
+    // combinators, expressed as whole levelspecs
+    fn check(cases: &[(&str, Option<&[&str]>)]) {
+        for (input, expect) in cases {
+            assert_eq!(levels(input), expect.map(|levels| levels.to_vec()), "{}", input);
+        }
+    }
+
+    #[test]
+    fn agrees_with_the_nom_parser() {
+        check(&[
+            ("DEV01.RD.0001", Some(&["DEV01", "RD", "0001"])),
+            ("..0001", Some(&["", "", "0001"])),
+            ("..%", Some(&["", "", "%"])),
+            ("DEV01..", Some(&["DEV01", "", ""])),
+            ("%..", Some(&["%", "", ""])),
+            ("DEV01.", Some(&["DEV01", ""])),
+            ("%.", Some(&["%", ""])),
+            ("DEV01.RS.0001", Some(&["DEV01", "RS", "0001"])),
+            ("DEV01.ASSETDEV.FOOBAR", Some(&["DEV01", "ASSETDEV", "FOOBAR"])),
+            ("DEV01.RD.R0001", None),
+            ("DEV01.RD.0 001", None),
+            ("DEV01.RD.00%", None),
+            ("DEV01.RD.0_001", None),
+            ("DEV01.RS.%", Some(&["DEV01", "RS", "%"])),
+            ("DEV01.RD", Some(&["DEV01", "RD"])),
+            ("DEV01.ASSETDEV", Some(&["DEV01", "ASSETDEV"])),
+            ("DEV01.1D", None),
+            ("DEV01.R D", None),
+            ("DEV01.R%", None),
+            ("DEV01.R_D", None),
+            ("DEV01.%", Some(&["DEV01", "%"])),
+            ("DEV01", Some(&["DEV01"])),
+            ("1DEV01", None),
+            ("DEV 01", None),
+            ("DEV01%", None),
+            ("DEV01_D", None),
+            ("%", Some(&["%"])),
+            (".RD", Some(&["", "RD"])),
+            (".ASSETDEV", Some(&["", "ASSETDEV"])),
+            (".1D", None),
+            (".R D", None),
+            (".R%", None),
+            (".R_D", None),
+            (".%", Some(&["", "%"])),
+            (".RD.", Some(&["", "RD", ""])),
+            (".ASSETDEV.", Some(&["", "ASSETDEV", ""])),
+            (".1D.", None),
+            (".R D.", None),
+            (".R%.", None),
+            (".R_D.", None),
+            (".%.", Some(&["", "%", ""])),
+            (".RD.0001", Some(&["", "RD", "0001"])),
+            (".ASSETDEV.FOO", Some(&["", "ASSETDEV", "FOO"])),
+            (".1D.0001", None),
+            (".R D.0001", None),
+            (".R%.0001", None),
+            (".R_D.0001", None),
+            (".%.0001", Some(&["", "%", "0001"])),
+            ("DEV01.RD.", Some(&["DEV01", "RD", ""])),
+            ("DEV.ASSETDEV.", Some(&["DEV", "ASSETDEV", ""])),
+            ("DEV.1D.", None),
+            ("DEV.R D.", None),
+            ("DEV.R%.", None),
+            ("DEV.R_D.", None),
+            ("DEV.%.", Some(&["DEV", "%", ""])),
+            ("..00 01", None),
+            ("..0%01", None),
+            ("..00_01", None),
+        ]);
     }
 
     #[cfg(not(feature = "case-insensitive"))]
     #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_rel_alt(".assetdev.");
-        assert_eq!(ls, Err(NomErr::Error((".assetdev.", ErrorKind::Many1))));
-    }
-
-    #[test]
-    fn cannot_start_with_number() {
-        let ls = rel_seq_rel_alt(".1D.");
-        assert_eq!(ls, Err(NomErr::Error((".1D.", ErrorKind::Many1))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = rel_seq_rel_alt(".R D.");
-        assert_eq!(ls,Err(NomErr::Error((".R D.", ErrorKind::Many1))));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = rel_seq_rel_alt(".R%.");
-        assert_eq!(ls, Err(NomErr::Error((".R%.", ErrorKind::Many1))));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = rel_seq_rel_alt(".R_D.");
-        assert_eq!(ls, Err(NomErr::Error((".R_D.", ErrorKind::Many1))));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = rel_seq_rel_alt(".%.");
-        let expect: LevelTypeVec = vec!["","%", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-
-//----------------------//
-//   rel_seq_shot_alt   //
-//----------------------//
-#[inline]
-// EG .RD.0001
-fn rel_seq_shot_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to turn the tuple into a vector
-        alt((
-            tuple((parse_rel_seq, parse_shot)),
-            tuple((parse_rel_assetdev_seq, parse_assetdev_shot))
-        )),
-        |item| {
-            let (seq, shot) = item;
-            let mut acc = Vec::with_capacity(3);
-            acc.push(LevelType::Relative); 
-            acc.push(LevelType::from(seq));
-            acc.push(LevelType::from(shot)); 
-            acc
-        } 
-    )
-    (input)
-}
-
-#[cfg(test)]
-mod rel_seq_shot_alt {
-    use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = rel_seq_shot_alt(".RD.0001");
-        let expect: LevelTypeVec = vec!["", "RD", "0001"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn agrees_with_the_case_sensitive_nom_parser() {
+        check(&[
+            ("dev01", None),
+            (".rd", None),
+            ("DEV01.assetdev.FOO", None),
+            ("DEV01.ASSETDEV.FOO", Some(&["DEV01", "ASSETDEV", "FOO"])),
+            ("DEV01.%.0001", Some(&["DEV01", "%", "0001"])),
+            ("DEV01.ASSETDEV.foobar", None),
+            ("DEV01.ASSETDEV.%", Some(&["DEV01", "ASSETDEV", "%"])),
+            (".assetdev", None),
+            (".rd.", None),
+            (".assetdev.", None),
+            ("dev01.assetdev.foobar", None),
+            ("dev01.assetdev", None),
+            (".assetdev.foo", None),
+            ("dev.assetdev.", None),
+        ]);
     }
 
     #[cfg(feature = "case-insensitive")]
     #[test]
-    fn can_parse_lowercase() {
-        let ls = rel_seq_shot_alt(".rd.0001");
-        let expect: LevelTypeVec = vec!["", "rd", "0001"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn agrees_with_the_case_insensitive_nom_parser() {
+        check(&[
+            ("dev01", Some(&["dev01"])),
+            ("DEV01.rd", Some(&["DEV01", "rd"])),
+            ("DEV01.assetdev.FOO", Some(&["DEV01", "assetdev", "FOO"])),
+            ("DEV01.ASSETDEV.FOO", Some(&["DEV01", "ASSETDEV", "FOO"])),
+            ("DEV01.%.0001", Some(&["DEV01", "%", "0001"])),
+            (".rd", Some(&["", "rd"])),
+            (".assetdev", Some(&["", "assetdev"])),
+            (".rd.", Some(&["", "rd", ""])),
+            (".assetdev.", Some(&["", "assetdev", ""])),
+            ("dev01.assetdev.foobar", Some(&["dev01", "assetdev", "foobar"])),
+            ("dev01.rd", Some(&["dev01", "rd"])),
+            ("dev01.assetdev", Some(&["dev01", "assetdev"])),
+            (".rd.0001", Some(&["", "rd", "0001"])),
+            (".assetdev.foo", Some(&["", "assetdev", "foo"])),
+            ("dev.rd.", Some(&["dev", "rd", ""])),
+            ("dev.assetdev.", Some(&["dev", "assetdev", ""])),
+        ]);
     }
 
     #[test]
-    fn can_parse_assetdev() {
-        let ls = rel_seq_shot_alt(".ASSETDEV.FOO");
-        let expect: LevelTypeVec = vec!["", "ASSETDEV", "FOO"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
+    fn rejects_more_than_three_levels() {
+        assert_eq!(levels("DEV01.RD.0001.X"), None);
+        assert_eq!(levels("...."), None);
+        assert_eq!(levels(".RD..RD."), None);
     }
 
-    #[cfg(feature = "case-insensitive")]
-    #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_shot_alt(".assetdev.foo");
-        let expect: LevelTypeVec = vec!["", "assetdev", "foo"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
-    }
-
-    #[cfg(not(feature = "case-insensitive"))]
     #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = rel_seq_shot_alt(".assetdev.foo");
-        assert_eq!(ls, Err(NomErr::Error(("assetdev.foo", ErrorKind::Tag))));
+    fn rejects_relative_sequence_between_absolute_levels() {
+        assert_eq!(levels("DEV01..0001"), None);
+        assert_eq!(levels("DEV01..%"), None);
     }
 
     #[test]
-    fn cannot_start_with_number() {
-        let ls = rel_seq_shot_alt(".1D.0001");
-        assert_eq!(ls, Err(NomErr::Error(("1D.0001", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = rel_seq_shot_alt(".R D.0001");
-        assert_eq!(ls,Err(NomErr::Error(("R D.0001", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = rel_seq_shot_alt(".R%.0001");
-        assert_eq!(ls, Err(NomErr::Error(("R%.0001", ErrorKind::Tag))));
+    fn rejects_empty_input() {
+        assert_eq!(levels(""), None);
     }
 
     #[test]
-    fn cannot_have_underscore() {
-        let ls = rel_seq_shot_alt(".R_D.0001");
-        assert_eq!(ls, Err(NomErr::Error(("R_D.0001", ErrorKind::Tag))));
+    fn counts_excess_levels() {
+        assert_eq!(levelparser("A.B.C.D"), Err(LevelSpecterError::TooManyLevels(4)));
+        assert_eq!(levelparser("DEV01.RD.0001.X.Y"), Err(LevelSpecterError::TooManyLevels(5)));
+        assert_eq!(levelparser("...."), Err(LevelSpecterError::TooManyLevels(4)));
+        assert_eq!(levelparser("....."), Err(LevelSpecterError::TooManyLevels(5)));
+        assert_eq!(levelparser("...0001"), Err(LevelSpecterError::TooManyLevels(4)));
     }
 
     #[test]
-    fn can_parse_wildcard() {
-        let ls = rel_seq_shot_alt(".%.0001");
-        let expect: LevelTypeVec = vec!["","%", "0001"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn locates_failures() {
+        assert_eq!(levelparser("DEV01..0001"), Err(invalid(ParseFailure::InvalidStructure { position: 6 })));
+        assert_eq!(levelparser(""), Err(invalid(ParseFailure::InvalidStructure { position: 0 })));
+        assert_eq!(levelparser(".R_D.0001"), Err(invalid(ParseFailure::InvalidLevel { level: LevelName::Sequence, position: 1 })));
+        assert_eq!(levelparser("DEV01.RD.00X1"), Err(invalid(ParseFailure::InvalidLevel { level: LevelName::Shot, position: 9 })));
     }
-}
 
-
-//------------------------//
-//    show_seq_rel_alt    //
-//------------------------//
-#[inline]
-// EG DEV01.RD.
-fn show_seq_rel_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to turn the tuple into a vector
-        tuple((parse_show, terminated(parse_seq, tag(".")))), 
-        |item| {
-            let (show, seq) = item;
-            let mut acc = Vec::with_capacity(3);
-            acc.push(LevelType::from(show));
-            acc.push(LevelType::from(seq)); 
-            acc.push(LevelType::Relative); 
-            acc
-        } 
-    )
-    (input)
-}
-
-
-#[cfg(test)]
-mod show_seq_rel_alt {
-    use super::*;
-        
     #[test]
-    fn can_parse() {
-        let ls = show_seq_rel_alt("DEV01.RD.");
-        let expect: LevelTypeVec = vec!["DEV01", "RD", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn sequences_must_end_with_a_letter() {
+        assert_eq!(levels("DEV01.RD1"), None);
+        assert_eq!(levels(".RD1"), None);
+        assert_eq!(levels("DEV01.R1D"), Some(vec!["DEV01", "R1D"]));
     }
 
-    #[cfg(feature = "case-insensitive")]
     #[test]
-    fn can_parse_lowercase() {
-        let ls = show_seq_rel_alt("dev.rd.");
-        let expect: LevelTypeVec = vec!["dev", "rd", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn sequences_starting_with_assetdev_are_sequences() {
+        assert_eq!(levels(".ASSETDEVX"), Some(vec!["", "ASSETDEVX"]));
+        assert_eq!(levels(".ASSETDEVX."), Some(vec!["", "ASSETDEVX", ""]));
+        assert_eq!(levels("DEV01.ASSETDEVX.FOO"), None);
     }
 
     #[test]
-    fn can_parse_assetdev() {
-        let ls = show_seq_rel_alt("DEV.ASSETDEV.");
-        let expect: LevelTypeVec = vec!["DEV", "ASSETDEV", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
+    fn assetdev_shots_may_be_named_or_numeric() {
+        assert_eq!(levels("DEV01.ASSETDEV.FOO1A"), Some(vec!["DEV01", "ASSETDEV", "FOO1A"]));
+        assert_eq!(levels(".ASSETDEV.0001"), Some(vec!["", "ASSETDEV", "0001"]));
+        assert_eq!(levels("DEV01.ASSETDEV.0001"), Some(vec!["DEV01", "ASSETDEV", "0001"]));
+        assert_eq!(levels(".ASSETDEV.01"), Some(vec!["", "ASSETDEV", "01"]));
+        assert_eq!(levels("DEV01.ASSETDEV.FOO1"), None);
+        assert_eq!(levels("DEV01.RD.FOO"), None);
     }
 
     #[cfg(feature = "case-insensitive")]
     #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = show_seq_rel_alt("dev.assetdev.");
-        let expect: LevelTypeVec = vec!["dev", "assetdev", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)))
+    fn wildcard_sequence_allows_named_shots() {
+        assert_eq!(levels("dev01.%.foo1a"), Some(vec!["dev01", "%", "foo1a"]));
+        assert_eq!(levels(".%.foo1a"), Some(vec!["", "%", "foo1a"]));
     }
 
     #[cfg(not(feature = "case-insensitive"))]
     #[test]
-    fn can_parse_assetdev_lowercase() {
-        let ls = show_seq_rel_alt("dev.assetdev.");
-        assert_eq!(ls, Err(NomErr::Error(("dev.assetdev.", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn cannot_start_with_number() {
-        let ls = show_seq_rel_alt("DEV.1D.");
-        assert_eq!(ls, Err(NomErr::Error(("1D.", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_space() {
-        let ls = show_seq_rel_alt("DEV.R D.");
-        assert_eq!(ls,Err(NomErr::Error((" D.", ErrorKind::Tag))));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = show_seq_rel_alt("DEV.R%.");
-        assert_eq!(ls, Err(NomErr::Error(("%.", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = show_seq_rel_alt("DEV.R_D.");
-        assert_eq!(ls, Err(NomErr::Error(("_D.", ErrorKind::Tag))));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = show_seq_rel_alt("DEV.%.");
-        let expect: LevelTypeVec = vec!["DEV","%", ""].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-//----------------------//
-//    rel_shot_alt      //
-//----------------------//
-#[inline]
-// EG ..0001
-fn rel_shot_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to place into a vector
-        parse_rel_shot, 
-        | item| { 
-            let mut acc = Vec::with_capacity(3);
-            acc.push(LevelType::Relative);
-            acc.push(LevelType::Relative);
-            acc.push(LevelType::from(item)); 
-            acc
-        } 
-    )
-    (input)
-}
-
-
-#[cfg(test)]
-mod rel_shot_alt {
-    use super::*;
-        
-    #[test]
-    fn can_parse() {
-        let ls = rel_shot_alt("..0001");
-        let expect: LevelTypeVec = vec!["", "", "0001"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
+    fn wildcard_sequence_requires_numeric_shots() {
+        assert_eq!(levels("DEV01.%.FOO1A"), None);
     }
- 
-    #[test]
-    fn cannot_have_space() {
-        let ls = rel_shot_alt("..00 01");
-        let expect: LevelTypeVec = vec!["", "", "00"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok((" 01", expect)));
-    }
-    
-    #[test]
-    fn cannot_have_wildcard_and_chars() {
-        let ls = rel_shot_alt("..0%01");
-        let expect: LevelTypeVec = vec!["", "", "0"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("%01", expect)));
-    }
-
-    #[test]
-    fn cannot_have_underscore() {
-        let ls = rel_shot_alt("..00_01");
-        let expect: LevelTypeVec = vec!["", "", "00"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("_01", expect)));
-    }
-
-    #[test]
-    fn can_parse_wildcard() {
-        let ls = rel_shot_alt("..%");
-        let expect: LevelTypeVec = vec!["","", "%"].iter().map(|x| LevelType::from(*x)).collect();
-        assert_eq!(ls, Ok(("", expect)));
-    }
-}
-
-fn rel_only_shot_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to place into a vector
-        tag("..."), 
-        | _item| { 
-            let mut acc = Vec::with_capacity(3);
-            acc.push(LevelType::Relative);
-            acc.push(LevelType::Relative);
-            acc.push(LevelType::Relative); 
-            acc
-        } 
-    )
-    (input)
-}
-
-fn rel_only_seq_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to place into a vector
-        tag(".."), 
-        | _item| { 
-            let mut acc = Vec::with_capacity(2);
-            acc.push(LevelType::Relative);
-            acc.push(LevelType::Relative);
-            acc
-        } 
-    )
-    (input)
-}
-
-fn rel_only_show_alt(input: &str) -> IResult<&str, LevelTypeVec> {
-    map( //used to place into a vector
-        tag("."), 
-        | _item| { 
-            let mut acc = Vec::with_capacity(1);
-            acc.push(LevelType::Relative);
-            acc
-        } 
-    )
-    (input)
-}
-//------------------------//
-//       levelparser      //
-//------------------------//
-fn levelparser(input: &str) -> IResult<&str, LevelTypeVec> {
-    let (leftover, result) = all_consuming(
-        alt(( // order is critical fyi
-            rel_shot_alt,
-            rel_seq_shot_alt,
-            rel_seq_rel_alt,
-            rel_seq_alt,
-            shot_alt,
-            show_rel_shot_alt,
-            show_seq_rel_alt,
-            seq_alt,
-            show_rel_seq_alt,
-            show_alt,
-            rel_only_shot_alt,
-            rel_only_seq_alt,
-            rel_only_show_alt,
-        )))
-     (input)?;
-
-    Ok((leftover, result))
 }
@@ -1,15 +1,63 @@
-use crate::{LevelSpecterError as LSE, levelspec_parser, LevelType};
+use crate::{LevelSpecterError as LSE, binary, uri, levelspec_ref_parser, levelparser::validate_levels, LevelSpecRef, LevelType, LevelKind, PathTemplate, PathSchema, JobRoot, EnvVars, FormatOptions, LevelResolver, resolver, NormalizeRules, AliasTable, ShowRegistry, LongDisplay, ParseOptions, ParseLimits, NormalizeChange, LevelSchema, ValidationError, ReservedSequences};
 use  std::str::FromStr;
 use std::fmt;
+use std::path::{Path, PathBuf};
+use std::collections::HashMap;
+use std::borrow::Cow;
+use std::env;
+use std::convert::TryFrom;
+#[cfg(feature = "async")]
+use crate::AsyncLevelResolver;
 
-#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Clone, Copy)]
 pub enum LevelName {
     Show,
     Sequence,
     Shot,
 }
 
-#[derive(Debug, PartialEq, Eq, Clone)]
+// The names of the levels, in order
+const LEVEL_NAMES: [LevelName; 3] = [LevelName::Show, LevelName::Sequence, LevelName::Shot];
+
+/// Name of the special sequence whose shots are alphanumeric
+pub const ASSETDEV: &str = "ASSETDEV";
+
+/// Strip the zero padding from a numeric level, leaving other levels as they
+/// are. Together with ignoring ASCII case, this yields the canonical form of a
+/// level used by `LevelSpec::eq_canonical`.
+pub(crate) fn canonical_level(level: &str) -> &str {
+    if level.is_empty() || !level.bytes().all(|c| c.is_ascii_digit()) {
+        return level;
+    }
+    let trimmed = level.trim_start_matches('0');
+    if trimmed.is_empty() {"0"} else {trimmed}
+}
+
+/// Classification of a LevelSpec's sequence
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy)]
+pub enum SequenceKind {
+    /// A regular, named sequence
+    Standard,
+    /// The ASSETDEV special sequence
+    AssetDev,
+    /// A sequence reserved by the site, such as COMMON. See `ReservedSequences`.
+    Reserved,
+    Wildcard,
+    Pattern,
+    Relative,
+}
+
+/// The levels of a LevelSpec, typed by depth. Returned by `LevelSpec::components`
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum LevelSpecComponents<'a> {
+    Show(&'a LevelType),
+    Sequence(&'a LevelType, &'a LevelType),
+    Shot(&'a LevelType, &'a LevelType, &'a LevelType),
+}
+
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
+#[cfg_attr(feature = "diesel", derive(diesel::expression::AsExpression, diesel::deserialize::FromSqlRow))]
+#[cfg_attr(feature = "diesel", diesel(sql_type = diesel::sql_types::Text))]
 pub struct LevelSpec {
     pub show: LevelType,
     pub sequence: Option<LevelType>,
@@ -43,18 +91,49 @@ impl LevelSpec {
         LevelSpec::from_str(levelspec.as_ref())
     }
     
+    /// Parse a LevelSpecRef, borrowing each level from the input rather than
+    /// allocating. See `LevelSpecRef`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelTypeRef};
+    /// 
+    /// let ls = LevelSpec::parse_ref("DEV01.RD.0001").unwrap();
+    /// assert_eq!(ls.shot(), Some(LevelTypeRef::Term("0001")));
+    /// ```
+    pub fn parse_ref(input: &str) -> Result<LevelSpecRef<'_>, LSE> {
+        LevelSpecRef::parse(input)
+    }
+
+    /// New up a LevelSpec from a str, applying the supplied ParseOptions.
+    /// See `ParseOptions::parse`.
+    pub fn parse_with(input: &str, options: &ParseOptions) -> Result<LevelSpec, LSE> {
+        options.parse(input)
+    }
+
+    /// New up a LevelSpec from untrusted input, enforcing the supplied
+    /// ParseLimits. See `ParseLimits::parse`.
+    pub fn parse_with_limits(input: &str, limits: &ParseLimits) -> Result<LevelSpec, LSE> {
+        limits.parse(input)
+    }
+
     /// Convert to uppercase
     pub fn set_upper(&mut self) {
-        if let LevelType::Term(ref mut show) = self.show {*show = show.to_uppercase()}
-        if let Some(LevelType::Term(ref mut sequence)) = self.sequence {*sequence = sequence.to_uppercase()}
-        if let Some(LevelType::Term(ref mut shot)) = self.shot {*shot = shot.to_uppercase()}
+        // only convert terms with lowercase chars, so that borrowed terms stay borrowed
+        let upper = |term: &mut Cow<'static, str>| {
+            if term.chars().any(|c| c.is_lowercase()) {
+                *term = Cow::Owned(term.to_uppercase())
+            }
+        };
+        if let LevelType::Term(ref mut show) = self.show {upper(show)}
+        if let Some(LevelType::Term(ref mut sequence)) = self.sequence {upper(sequence)}
+        if let Some(LevelType::Term(ref mut shot)) = self.shot {upper(shot)}
     }
 
     /// Convert to uppercase and return self. Used to chain after from
     pub fn upper(mut self) -> Self {
-        if let LevelType::Term(ref mut show) = self.show {*show = show.to_uppercase()}
-        if let Some(LevelType::Term(ref mut sequence)) = self.sequence {*sequence = sequence.to_uppercase()}
-        if let Some(LevelType::Term(ref mut shot)) = self.shot {*shot = shot.to_uppercase()}
+        self.set_upper();
         self
     }
 
@@ -73,54 +152,155 @@ impl LevelSpec {
     where
         P: Fn(LevelName) -> Option<String>
     {
-        let mut return_value = self.clone();
-
-        if return_value.show.is_relative() {
-            let new_show_val = replacer(LevelName::Show)
-                .ok_or(LSE::RelToAbsError(format!("Unable to retrieve {:?} in rel_to_abs",
-                LevelName::Show)))?;
-            let new_show = LevelType::from(new_show_val.as_ref());
+        self.rel_to_abs_try(|level| Ok(replacer(level)))
+    }
 
-            if new_show.is_relative() { 
-                return Err(LSE::RelToAbsError(format!("show returned by closure is relative '{}'", new_show_val))) ;
+    /// Fallible variant of `rel_to_abs`, for replacers which may fail, such as
+    /// those backed by a database. Errors returned by the replacer are 
+    /// propagated unchanged.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `replacer` - Closure which takes a LevelName and returns a 
+    ///   `Result<Option<String>, E>`
+    /// 
+    /// # Returns
+    /// A new LevelSpec without any relative components if successful.
+    /// Otherwise, the replacer's error, or a LevelSpecterError converted into `E`
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelName, LevelSpecterError};
+    /// 
+    /// let ls = LevelSpec::new("..0001").unwrap();
+    /// let result = ls.rel_to_abs_try(|level| match level {
+    ///     LevelName::Show => Ok(Some("DEV01".to_string())),
+    ///     _ => Err(LevelSpecterError::RelToAbsError("database unavailable".to_string())),
+    /// });
+    /// assert_eq!(result, Err(LevelSpecterError::RelToAbsError("database unavailable".to_string())));
+    /// ```
+    pub fn rel_to_abs_try<P, E>(&self, replacer: P) -> Result<Self, E>
+    where
+        P: Fn(LevelName) -> Result<Option<String>, E>,
+        E: From<LSE>
+    {
+        // retrieve the replacement for a level, verifying that it is not itself relative
+        let replace = |level: LevelName, name: &str| -> Result<LevelType, E> {
+            let new_val = replacer(level)?
+                .ok_or_else(|| LSE::RelToAbsError(format!("Unable to retrieve {:?} in rel_to_abs", level)))?;
+            let new_level = LevelType::from(new_val.as_ref());
+            if new_level.is_relative() {
+                return Err(LSE::RelToAbsError(format!("{} returned by closure is relative '{}'", name, new_val)).into());
             }
+            Ok(new_level)
+        };
+
+        let mut return_value = self.clone();
 
-            return_value.show = new_show;
+        if return_value.show.is_relative() {
+            return_value.show = replace(LevelName::Show, "show")?;
         }
 
         if let Some(ref seq) = return_value.sequence {
             if seq.is_relative() {
-                let new_seq_val = replacer(LevelName::Sequence)
-                .ok_or(LSE::RelToAbsError(format!("Unable to retrieve {:?} in rel_to_abs",
-                LevelName::Sequence)))?;
-                let new_seq = LevelType::from(new_seq_val.as_ref());
-
-                if new_seq.is_relative() {
-                    return Err(LSE::RelToAbsError(format!("sequence returned by closure is relative '{}'", new_seq_val))) ;
-                }
-
-                return_value.sequence = Some(new_seq);
+                return_value.sequence = Some(replace(LevelName::Sequence, "sequence")?);
             }
         }
 
         if let Some(ref shot) = return_value.shot {
             if shot.is_relative() {
-                let new_shot_val = replacer(LevelName::Shot)
-                .ok_or(LSE::RelToAbsError(format!("Unable to retrieve {:?} in rel_to_abs",
-                LevelName::Shot)))?;
-                let new_shot = LevelType::from(new_shot_val.as_ref());
+                return_value.shot = Some(replace(LevelName::Shot, "shot")?);
+            }
+        }
+        Ok(return_value)
+    }
 
-                if new_shot.is_relative() {
-                    return Err(LSE::RelToAbsError(format!("shot returned by closure is relative '{}'", new_shot_val))) ;
-                }
+    /// Return a new LevelSpec instance with any relative LevelTypes replaced
+    /// by the corresponding values from the supplied resolver, such as a 
+    /// LevelContext.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `resolver` - LevelResolver supplying absolute values for relative levels
+    /// 
+    /// # Returns
+    /// A new LevelSpec without any relative components if successful
+    /// Otherwise, a LevelSpecterError if the resolver lacks a required level
+    pub fn rel_to_abs_with<R>(&self, resolver: &R) -> Result<Self, LSE>
+    where
+        R: LevelResolver + ?Sized
+    {
+        self.rel_to_abs(|level| resolver.resolve(level, self))
+    }
 
-                return_value.shot = Some(new_shot);
+    /// Return a new LevelSpec relative to the supplied base. Leading levels
+    /// which match the base are replaced with relative levels. Replacement 
+    /// stops at the first level which differs, as a sequence is only meaningful
+    /// within its show, and a shot within its sequence. The last level is
+    /// always kept absolute, as entirely relative levels cannot be written as
+    /// text, so `DEV01.RD` relative to itself is `.RD`. This is the inverse
+    /// of `rel_to_abs_with`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `base` - The LevelSpec to express self relative to
+    /// 
+    /// # Returns
+    /// A new, possibly relative, LevelSpec
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::new("DEV01.RD.0001").unwrap();
+    /// let base = LevelSpec::new("DEV01.RD").unwrap();
+    /// assert_eq!(ls.abs_to_rel(&base), LevelSpec::new("..0001").unwrap());
+    /// ```
+    pub fn abs_to_rel(&self, base: &LevelSpec) -> Self {
+        let matches = |level: Option<&LevelType>, base_level: Option<&LevelType>| {
+            match (level, base_level) {
+                (Some(level), Some(base_level)) => level.is_term() && level == base_level,
+                _ => false,
             }
+        };
+
+        let mut return_value = self.clone();
+        if self.sequence.is_none() || !matches(Some(&self.show), Some(&base.show)) {
+            return return_value;
         }
-        Ok(return_value)
+        return_value.show = LevelType::Relative;
+
+        if self.shot.is_none() || !matches(self.sequence(), base.sequence()) {
+            return return_value;
+        }
+        return_value.sequence = Some(LevelType::Relative);
+        return_value
     }
 
-    /// new up a show
+    /// New up a LevelSpec from static strs. Terms borrow their input rather
+    /// than allocating. Like `from_shot`, inputs are not validated.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_static("DEV01", Some("RD"), Some("0001"));
+    /// assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+    /// ```
+    pub fn from_static(show: &'static str, sequence: Option<&'static str>, shot: Option<&'static str>) -> Self {
+        let ls = Self {
+            show: LevelType::from_static(show),
+            sequence: sequence.map(LevelType::from_static),
+            shot: sequence.and(shot).map(LevelType::from_static),
+        };
+        if cfg!(feature = "case-insensitive") {ls} else {ls.upper()}
+    }
+
+    /// New up a show. The input is not validated, so this may build a
+    /// LevelSpec which no input would parse to; see `try_from_show`.
     pub fn from_show<I>(input: I ) -> Self
     where 
         I: AsRef<str>
@@ -132,7 +312,7 @@ impl LevelSpec {
         };
         if cfg!(feature = "case-insensitive") {ls} else {ls.upper()}
     }
-    /// new up a sequence
+    /// New up a sequence. The inputs are not validated; see `try_from_sequence`.
     pub fn from_sequence<I>(show: I, sequence: I ) -> Self  
     where 
         I: AsRef<str>
@@ -145,6 +325,8 @@ impl LevelSpec {
         if cfg!(feature = "case-insensitive") {ls} else {ls.upper()}
     }
 
+    /// New up a shot. The inputs are not validated, which allows patterns
+    /// such as `R%`; see `try_from_shot`.
     pub fn from_shot<I>(show: I, sequence: I, shot: I) -> Self  
     where 
         I: AsRef<str>
@@ -157,19 +339,84 @@ impl LevelSpec {
         if cfg!(feature = "case-insensitive") {ls} else {ls.upper()}
     }
 
+    /// New up a show, validating it as the parser would. As with `from_show`,
+    /// the input is uppercased unless the crate is built case insensitive.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `input` - The show
+    /// 
+    /// # Returns
+    /// A LevelSpec, or a ParseError naming the invalid level
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// assert_eq!(LevelSpec::try_from_show("DEV01"), Ok(LevelSpec::from_show("DEV01")));
+    /// assert!(LevelSpec::try_from_show("DEV 01").is_err());
+    /// ```
+    pub fn try_from_show<I>(input: I) -> Result<Self, LSE>
+    where 
+        I: AsRef<str>
+    {
+        Self::from_show(input).validated()
+    }
+
+    /// New up a sequence, validating each level as the parser would. See
+    /// `try_from_show`.
+    pub fn try_from_sequence<I>(show: I, sequence: I) -> Result<Self, LSE>
+    where 
+        I: AsRef<str>
+    {
+        Self::from_sequence(show, sequence).validated()
+    }
+
+    /// New up a shot, validating each level as the parser would. Patterns
+    /// are rejected, as are shots which are not numeric outside of ASSETDEV.
+    /// See `try_from_show`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecterError, LevelName, ParseFailure};
+    /// 
+    /// assert!(LevelSpec::try_from_shot("DEV01", "RD", "0001").is_ok());
+    /// assert_eq!(
+    ///     LevelSpec::try_from_shot("DEV01", "R_D", "0001"),
+    ///     Err(LevelSpecterError::ParseError(ParseFailure::InvalidLevel { level: LevelName::Sequence, position: 6 }))
+    /// );
+    /// ```
+    pub fn try_from_shot<I>(show: I, sequence: I, shot: I) -> Result<Self, LSE>
+    where 
+        I: AsRef<str>
+    {
+        Self::from_shot(show, sequence, shot).validated()
+    }
+
+    // Check the levels as the parser would
+    pub(crate) fn validated(self) -> Result<Self, LSE> {
+        let levels = LevelSpecRef::from(&self);
+        validate_levels(levels.show.to_str(), levels.sequence.map(|level| level.to_str()), levels.shot.map(|level| level.to_str()), None)?;
+        Ok(self)
+    }
+
+   /// Returns true if no level is a wildcard or pattern
    pub fn is_concrete(&self) -> bool {
-        if self.show.is_wildcard() {
+        let is_abstract = |level: &LevelType| level.is_wildcard() || level.is_pattern();
+        if is_abstract(&self.show) {
            return false;
         }
         
         if let Some(ref ls) = self.sequence {
-            if ls.is_wildcard() {
+            if is_abstract(ls) {
                 return false
             }
         }
         
         if let Some(ref ls) = self.shot {
-            if ls.is_wildcard() {
+            if is_abstract(ls) {
                 return false
             }
         }
@@ -177,84 +424,1281 @@ impl LevelSpec {
    }
 
 
-    /// Retrieve the show if it exists. Otherwise return None
-    pub fn show(&self) -> &LevelType {
-        &self.show
+    /// Returns true if any level of the LevelSpec is relative
+    pub fn has_relative(&self) -> bool {
+        !self.relative_levels().is_empty()
     }
 
-    /// Retrieve the sequence as a string wrapped in an Option
-    pub fn sequence(&self) -> Option<&LevelType> {
-        if let Some(ref val) = self.sequence {
-            Some(val)
-        } else {
-            None
+    /// Retrieve the names of the relative levels, which must be resolved via 
+    /// `rel_to_abs` before the LevelSpec may be considered absolute.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelName};
+    /// 
+    /// let ls = LevelSpec::new("..0001").unwrap();
+    /// assert_eq!(ls.relative_levels(), vec![LevelName::Show, LevelName::Sequence]);
+    /// ```
+    pub fn relative_levels(&self) -> Vec<LevelName> {
+        self.levels_where(LevelType::is_relative)
+    }
+
+    /// Retrieve the names of the wildcard and pattern levels. These are the 
+    /// levels which must be enumerated in order to expand the LevelSpec.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelName};
+    /// 
+    /// let ls = LevelSpec::new("DEV01.%.%").unwrap();
+    /// assert_eq!(ls.wildcard_levels(), vec![LevelName::Sequence, LevelName::Shot]);
+    /// ```
+    pub fn wildcard_levels(&self) -> Vec<LevelName> {
+        self.levels_where(|level| level.is_wildcard() || level.is_pattern())
+    }
+
+    // retrieve the names of the levels satisfying the predicate
+    fn levels_where<P>(&self, predicate: P) -> Vec<LevelName>
+    where
+        P: Fn(&LevelType) -> bool
+    {
+        let mut names = Vec::new();
+        if predicate(&self.show) {
+            names.push(LevelName::Show);
         }
+        if let Some(ref sequence) = self.sequence {
+            if predicate(sequence) {
+                names.push(LevelName::Sequence);
+            }
+        }
+        if let Some(ref shot) = self.shot {
+            if predicate(shot) {
+                names.push(LevelName::Shot);
+            }
+        }
+        names
     }
 
-    /// Retrieve the sequence as a string wrapped in an Option
-    pub fn shot(&self) -> Option<&LevelType> {
-        if let Some(ref val) = self.shot {
-            Some(val)
+    /// Retrieve the number of populated levels, from 1 (show) to 3 (shot)
+    pub fn levels(&self) -> usize {
+        match self.components() {
+            LevelSpecComponents::Show(..) => 1,
+            LevelSpecComponents::Sequence(..) => 2,
+            LevelSpecComponents::Shot(..) => 3,
+        }
+    }
+
+    /// Returns true if the sequence level is populated
+    pub fn has_sequence(&self) -> bool {
+        self.sequence.is_some()
+    }
+
+    /// Returns true if the shot level is populated. A shot is only
+    /// populated beneath a sequence, so this is the same as `is_shot`.
+    pub fn has_shot(&self) -> bool {
+        self.is_shot()
+    }
+
+    /// Returns true if the LevelSpec is show level (eg `DEV01`)
+    pub fn is_show(&self) -> bool {
+        self.sequence.is_none() && self.shot.is_none()
+    }
+
+    /// Returns true if the LevelSpec is sequence level (eg `DEV01.RD`)
+    pub fn is_sequence(&self) -> bool {
+        self.sequence.is_some() && self.shot.is_none()
+    }
+
+    /// Returns true if the LevelSpec is shot level (eg `DEV01.RD.0001`)
+    pub fn is_shot(&self) -> bool {
+        self.sequence.is_some() && self.shot.is_some()
+    }
+
+    /// Classify the sequence, if the LevelSpec has one. Case insensitive
+    /// builds recognize ASSETDEV regardless of case. No sequence is reported
+    /// as Reserved; use `sequence_kind_with` for that.
+    pub fn sequence_kind(&self) -> Option<SequenceKind> {
+        let sequence = self.sequence.as_ref()?;
+        let kind = match sequence.kind() {
+            LevelKind::Term => SequenceKind::Standard,
+            LevelKind::AssetDev => SequenceKind::AssetDev,
+            LevelKind::Pattern => SequenceKind::Pattern,
+            LevelKind::Wildcard => SequenceKind::Wildcard,
+            LevelKind::Relative => SequenceKind::Relative,
+        };
+        Some(kind)
+    }
+
+    /// Classify the sequence, as `sequence_kind` does, reporting those among
+    /// the supplied ReservedSequences as `SequenceKind::Reserved`
+    ///
+    /// # Parameters
+    ///
+    /// * `reserved` - The sequences the site reserves
+    pub fn sequence_kind_with(&self, reserved: &ReservedSequences) -> Option<SequenceKind> {
+        match self.sequence_kind()? {
+            SequenceKind::Standard if reserved.contains(self.sequence.as_ref()?.to_str()) => Some(SequenceKind::Reserved),
+            kind => Some(kind),
+        }
+    }
+
+    /// Returns true if the sequence is the ASSETDEV special sequence
+    pub fn is_assetdev(&self) -> bool {
+        self.sequence_kind() == Some(SequenceKind::AssetDev)
+    }
+
+    /// Returns true if the supplied concrete LevelSpec matches self. LevelSpecs
+    /// of differing depth never match. Wildcards match any term, patterns match
+    /// terms fitting the pattern, terms match equal terms (ignoring case in 
+    /// case-insensitive builds), and relative levels match nothing; resolve
+    /// them via `rel_to_abs` first.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let pattern = LevelSpec::new("DEV01.%.0001").unwrap();
+    /// assert!(pattern.matches(&LevelSpec::from_shot("DEV01", "RD", "0001")));
+    /// assert!(!pattern.matches(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+    /// ```
+    pub fn matches(&self, other: &LevelSpec) -> bool {
+        let pattern = self.to_vec_str();
+        let concrete = other.to_vec_str();
+        pattern.len() == concrete.len()
+            && pattern.iter().zip(concrete.iter()).all(|(p, c)| p.matches(c))
+    }
+
+    /// Retrieve every LevelSpec in `universe` matching self. Entries of the 
+    /// universe which are not concrete and absolute are skipped.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `universe` - The concrete LevelSpecs to match against, eg those known
+    ///   to the production database
+    /// 
+    /// # Returns
+    /// Vec of matching LevelSpecs, in the universe's order
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let universe = vec![
+    ///     LevelSpec::from_shot("DEV01", "RD", "0001"),
+    ///     LevelSpec::from_shot("DEV01", "RD", "0002"),
+    ///     LevelSpec::from_shot("DEV01", "AA", "0001"),
+    /// ];
+    /// let pattern = LevelSpec::new("DEV01.RD.%").unwrap();
+    /// assert_eq!(pattern.expand_against(&universe), vec![&universe[0], &universe[1]]);
+    /// ```
+    pub fn expand_against<'a, I>(&self, universe: I) -> Vec<&'a LevelSpec>
+    where
+        I: IntoIterator<Item = &'a LevelSpec>
+    {
+        universe.into_iter()
+            .filter(|candidate| candidate.is_concrete() && !candidate.has_relative())
+            .filter(|candidate| self.matches(candidate))
+            .collect()
+    }
+
+    /// Convert to a SQL LIKE pattern matching the display form of the concrete
+    /// LevelSpecs which self matches. Wildcards become `_%`, pattern `%` and
+    /// `?` become `%` and `_`, and any `%`, `_`, or `\` within a term is
+    /// escaped with `\`. Note that, unlike `matches`, a SQL `%` may also match
+    /// across the `.` separating levels, so pair the pattern with a depth check
+    /// (the "diesel" feature's `matches_spec` does this for you). Whether terms
+    /// compare ignoring case is up to the database's collation.
+    ///
+    /// # Returns
+    /// The LIKE pattern, or a RelToAbsError if self has relative levels
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use levelspecter::LevelSpec;
+    ///
+    /// let pattern = LevelSpec::from_shot("DEV01", "%", "00?0");
+    /// assert_eq!(pattern.to_sql_like().unwrap(), "DEV01._%.00_0");
+    /// ```
+    pub fn to_sql_like(&self) -> Result<String, LSE> {
+        if self.has_relative() {
+            return Err(LSE::RelToAbsError(format!(
+                "Unable to convert relative levelspec '{}' to a SQL LIKE pattern", self
+            )));
+        }
+        // escape LIKE's special characters, leaving the pattern wildcards,
+        // when supplied, to be translated
+        let escape = |level: &str, wildcards: &[char]| level.chars().fold(String::new(), |mut like, c| {
+            match c {
+                '%' | '?' if wildcards.contains(&c) => like.push(if c == '%' {'%'} else {'_'}),
+                '%' | '_' | '\\' => {
+                    like.push('\\');
+                    like.push(c);
+                },
+                _ => like.push(c),
+            }
+            like
+        });
+        let levels = self.to_vec_str().iter().map(|level| match level {
+            LevelType::Term(term) => escape(term, &[]),
+            LevelType::Pattern(pattern) => escape(pattern, &['%', '?']),
+            LevelType::Wildcard => "_%".to_string(),
+            LevelType::Relative => String::new(),
+        }).collect::<Vec<_>>();
+        Ok(levels.join("."))
+    }
+
+    /// Render the LevelSpec as a single argument for a POSIX shell command
+    /// line. LevelSpecs consisting solely of names and separators are left as
+    /// is. Otherwise, the LevelSpec is single quoted, so that wildcards are
+    /// neither globbed nor treated as job specifications.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// assert_eq!(LevelSpec::new("DEV01.RD.0001").unwrap().to_shell_arg(), "DEV01.RD.0001");
+    /// assert_eq!(LevelSpec::new("DEV01.%.0001").unwrap().to_shell_arg(), "'DEV01.%.0001'");
+    /// ```
+    pub fn to_shell_arg(&self) -> String {
+        let input = self.to_string();
+        if !input.is_empty() && input.chars().all(|c| c.is_ascii_alphanumeric() || c == '.') {
+            input
         } else {
-            None
+            format!("'{}'", input.replace('\'', "'\\''"))
+        }
+    }
+
+    /// Encode as a versioned header followed by a compact binary record. See
+    /// the `binary` module for the layout, and `binary::write_snapshot` for
+    /// encoding many LevelSpecs at once.
+    /// 
+    /// # Returns
+    /// The encoded bytes, or a SerializeError if a level is longer than
+    /// `u16::MAX` bytes
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_sequence("DEV01", "RD");
+    /// assert_eq!(LevelSpec::from_bytes(&ls.to_bytes().unwrap()), Ok(ls));
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LSE> {
+        binary::encode(self)
+    }
+
+    /// New up a LevelSpec from the output of `to_bytes`
+    /// 
+    /// # Parameters
+    /// 
+    /// * `bytes` - The encoded LevelSpec
+    /// 
+    /// # Returns
+    /// A LevelSpec, or a SerializeError if the bytes are truncated, require a
+    /// newer release to read, or hold invalid levels
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LSE> {
+        binary::decode(bytes)
+    }
+
+    /// Render as a `levelspec://` URI, eg `levelspec://DEV01/RD/0001`, for
+    /// embedding in hyperlinks. See the `uri` module.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(ls.to_uri(), "levelspec://DEV01/RD/0001");
+    /// ```
+    pub fn to_uri(&self) -> String {
+        uri::to_uri(self, &[])
+    }
+
+    /// Render as a `levelspec://` URI with the supplied query parameters,
+    /// such as a version.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `query` - Key value pairs, written in order and percent encoded
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(ls.to_uri_with_query(&[("version", "3")]), "levelspec://DEV01/RD/0001?version=3");
+    /// ```
+    pub fn to_uri_with_query(&self, query: &[(&str, &str)]) -> String {
+        uri::to_uri(self, query)
+    }
+
+    /// New up a LevelSpec from a `levelspec://` URI, ignoring any query
+    /// parameters. Use `from_uri_with_query` to also retrieve them.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `uri` - The URI, eg `levelspec://DEV01/RD/0001?version=3`
+    /// 
+    /// # Returns
+    /// A LevelSpec, or a ParseError if the URI is malformed or its levels
+    /// do not form a legal levelspec
+    pub fn from_uri(uri: &str) -> Result<Self, LSE> {
+        uri::from_uri(uri).map(|(levelspec, _)| levelspec)
+    }
+
+    /// New up a LevelSpec from a `levelspec://` URI, along with its decoded
+    /// query parameters.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let (ls, query) = LevelSpec::from_uri_with_query("levelspec://DEV01/RD/0001?version=3").unwrap();
+    /// assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+    /// assert_eq!(query["version"], "3");
+    /// ```
+    pub fn from_uri_with_query(uri: &str) -> Result<(Self, HashMap<String, String>), LSE> {
+        uri::from_uri(uri)
+    }
+
+    /// Retrieve the LevelSpecs known to the supplied resolver which match self.
+    /// Relative levels are resolved first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `resolver` - LevelResolver enumerating the levels which exist
+    /// 
+    /// # Returns
+    /// The matching LevelSpecs, in the resolver's order, or a LevelSpecterError
+    /// if a relative level cannot be resolved
+    pub fn expand_with<R>(&self, resolver: &R) -> Result<Vec<LevelSpec>, LSE>
+    where
+        R: LevelResolver + ?Sized
+    {
+        let pattern = if self.has_relative() { self.rel_to_abs_with(resolver)? } else { self.clone() };
+        Ok(resolver::expand(resolver, &pattern))
+    }
+
+    /// The async counterpart of `rel_to_abs_with`. Relative levels are 
+    /// resolved in order, from show to shot.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `resolver` - AsyncLevelResolver supplying absolute values for relative levels
+    /// 
+    /// # Returns
+    /// A new LevelSpec without any relative components if successful
+    /// Otherwise, a LevelSpecterError if the resolver lacks a required level
+    #[cfg(feature = "async")]
+    pub async fn rel_to_abs_async<R>(&self, resolver: &R) -> Result<Self, LSE>
+    where
+        R: AsyncLevelResolver + ?Sized
+    {
+        let values = resolver::resolve_async(resolver, self).await;
+        self.rel_to_abs(|level| {
+            values.iter().find(|(name, _)| *name == level).and_then(|(_, value)| value.clone())
+        })
+    }
+
+    /// The async counterpart of `expand_with`. Relative levels are resolved 
+    /// first.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `resolver` - AsyncLevelResolver enumerating the levels which exist
+    /// 
+    /// # Returns
+    /// The matching LevelSpecs, in the resolver's order, or a LevelSpecterError
+    /// if a relative level cannot be resolved
+    #[cfg(feature = "async")]
+    pub async fn expand_async<R>(&self, resolver: &R) -> Result<Vec<LevelSpec>, LSE>
+    where
+        R: AsyncLevelResolver + ?Sized
+    {
+        let pattern = if self.has_relative() { self.rel_to_abs_async(resolver).await? } else { self.clone() };
+        Ok(resolver::expand_async(resolver, &pattern).await)
+    }
+
+    /// Return a copy of the LevelSpec with levels deeper than `level` removed.
+    /// LevelSpecs which are already at or above `level` are returned unchanged.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelName};
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(ls.truncate_to(LevelName::Sequence), LevelSpec::from_sequence("DEV01", "RD"));
+    /// assert_eq!(ls.truncate_to(LevelName::Show), LevelSpec::from_show("DEV01"));
+    /// ```
+    pub fn truncate_to(&self, level: LevelName) -> Self {
+        let mut return_value = self.clone();
+        match level {
+            LevelName::Show => {
+                return_value.sequence = None;
+                return_value.shot = None;
+            },
+            LevelName::Sequence => return_value.shot = None,
+            LevelName::Shot => (),
+        }
+        return_value
+    }
+
+    /// Overlay another LevelSpec onto self, returning the result. Term levels
+    /// of the overlay replace the corresponding levels of self, while relative,
+    /// wildcard, and pattern levels of the overlay leave self's levels
+    /// untouched, as they do not name a level. Levels the overlay has but self
+    /// lacks are appended as-is, patterns included.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `overlay` - The LevelSpec whose terms take precedence
+    /// 
+    /// # Returns
+    /// A new LevelSpec
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// let overlay = LevelSpec::new("..0002").unwrap();
+    /// assert_eq!(ls.merge(&overlay), LevelSpec::from_shot("DEV01", "RD", "0002"));
+    /// ```
+    pub fn merge(&self, overlay: &LevelSpec) -> Self {
+        let merge_level = |base: Option<&LevelType>, over: Option<&LevelType>| {
+            match (base, over) {
+                (_, Some(over)) if over.is_term() => Some(over.clone()),
+                (Some(base), _) => Some(base.clone()),
+                (None, over) => over.cloned(),
+            }
+        };
+        let show = if overlay.show.is_term() {overlay.show.clone()} else {self.show.clone()};
+        let sequence = merge_level(self.sequence(), overlay.sequence());
+        let shot = match sequence {
+            Some(_) => merge_level(self.shot(), overlay.shot()),
+            None => None,
+        };
+        LevelSpec { show, sequence, shot }
+    }
+
+    /// Rank the LevelSpec by how concrete it is, so that rule systems may 
+    /// select the most specific of several matching patterns. Scores compare,
+    /// in order of precedence:
+    /// 
+    /// 1. depth - deeper LevelSpecs outrank shallower ones
+    /// 2. the number of terms (relative levels count as terms, as they resolve to one)
+    /// 3. the number of patterns
+    /// 4. the position of the terms, then of the patterns - a term at a deeper
+    ///    level outranks one at a shallower level, so `%.RD.%` outranks `DEV01.%.%`
+    /// 
+    /// Wildcards contribute nothing.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let general = LevelSpec::new("DEV01.%.%").unwrap();
+    /// let specific = LevelSpec::new("DEV01.RD.%").unwrap();
+    /// assert!(specific.specificity() > general.specificity());
+    /// ```
+    pub fn specificity(&self) -> u32 {
+        let levels = self.to_vec_str();
+        let (mut terms, mut patterns) = (0, 0);
+        let (mut term_positions, mut pattern_positions) = (0, 0);
+        for (idx, level) in levels.iter().enumerate() {
+            if level.is_pattern() {
+                patterns += 1;
+                pattern_positions |= 1 << idx;
+            } else if !level.is_wildcard() {
+                terms += 1;
+                term_positions |= 1 << idx;
+            }
+        }
+        (levels.len() as u32) << 16 | terms << 12 | patterns << 8 | term_positions << 4 | pattern_positions
+    }
+
+    /// Retrieve the levels as a LevelSpecComponents, permitting exhaustive 
+    /// matching on the depth of the LevelSpec. As with `to_vec_str`, a shot
+    /// without a sequence is ignored.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecComponents};
+    /// 
+    /// let ls = LevelSpec::from_sequence("DEV01", "RD");
+    /// match ls.components() {
+    ///     LevelSpecComponents::Show(show) => println!("show {}", show),
+    ///     LevelSpecComponents::Sequence(show, seq) => assert_eq!(format!("{}.{}", show, seq), "DEV01.RD"),
+    ///     LevelSpecComponents::Shot(..) => panic!("not a shot"),
+    /// }
+    /// ```
+    pub fn components(&self) -> LevelSpecComponents<'_> {
+        match (&self.sequence, &self.shot) {
+            (Some(sequence), Some(shot)) => LevelSpecComponents::Shot(&self.show, sequence, shot),
+            (Some(sequence), None) => LevelSpecComponents::Sequence(&self.show, sequence),
+            (None, _) => LevelSpecComponents::Show(&self.show),
+        }
+    }
+
+    /// Retrieve the show if it exists. Otherwise return None
+    pub fn show(&self) -> &LevelType {
+        &self.show
+    }
+
+    /// Retrieve the sequence as a string wrapped in an Option
+    pub fn sequence(&self) -> Option<&LevelType> {
+        if let Some(ref val) = self.sequence {
+            Some(val)
+        } else {
+            None
+        }
+    }
+
+    /// Retrieve the sequence as a string wrapped in an Option
+    pub fn shot(&self) -> Option<&LevelType> {
+        if let Some(ref val) = self.shot {
+            Some(val)
+        } else {
+            None
+        }
+    }
+
+    /// Retrieve the shot as a number. Returns None if there is no shot, or
+    /// if the shot is a wildcard, relative, or non-numeric (eg an ASSETDEV shot)
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// assert_eq!(LevelSpec::new("DEV01.RD.0010").unwrap().shot_number(), Some(10));
+    /// assert_eq!(LevelSpec::new("DEV01.RD.%").unwrap().shot_number(), None);
+    /// ```
+    pub fn shot_number(&self) -> Option<u32> {
+        self.numeric_shot().and_then(|shot| shot.parse::<u32>().ok())
+    }
+
+    /// Retrieve the number of digits in the shot, including leading zeros.
+    /// Returns None under the same conditions as `shot_number`.
+    pub fn shot_padding(&self) -> Option<usize> {
+        self.numeric_shot().map(|shot| shot.len())
+    }
+
+    /// Return a new LevelSpec with the shot number incremented by `step`.
+    /// See `offset_shot` for details.
+    pub fn next_shot(&self, step: u32) -> Result<Self, LSE> {
+        self.offset_shot(i64::from(step))
+    }
+
+    /// Return a new LevelSpec with the shot number decremented by `step`.
+    /// See `offset_shot` for details.
+    pub fn prev_shot(&self, step: u32) -> Result<Self, LSE> {
+        self.offset_shot(-i64::from(step))
+    }
+
+    /// Return a new LevelSpec with `delta` added to the shot number. The 
+    /// padding of the original shot is preserved.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `delta` - The amount to add to the shot number. May be negative.
+    /// 
+    /// # Returns
+    /// A new LevelSpec if successful. Otherwise, a LevelSpecterError if the shot
+    /// is not numeric or the result falls outside the legal range of shot numbers.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0010");
+    /// assert_eq!(ls.offset_shot(5), Ok(LevelSpec::from_shot("DEV01", "RD", "0015")));
+    /// assert_eq!(ls.prev_shot(10), Ok(LevelSpec::from_shot("DEV01", "RD", "0000")));
+    /// ```
+    pub fn offset_shot(&self, delta: i64) -> Result<Self, LSE> {
+        let (number, padding) = match (self.shot_number(), self.shot_padding()) {
+            (Some(number), Some(padding)) => (number, padding),
+            _ => return Err(LSE::ShotError(format!("shot of '{}' is not numeric", self))),
+        };
+        let new_number = i64::from(number) + delta;
+        if new_number < 0 || new_number > i64::from(u32::MAX) {
+            return Err(LSE::ShotError(format!("offsetting '{}' by {} is out of range", self, delta)));
+        }
+        let mut return_value = self.clone();
+        return_value.shot = Some(LevelType::Term(format!("{:0width$}", new_number, width = padding).into()));
+        Ok(return_value)
+    }
+
+    // retrieve the shot term if it is made up entirely of digits
+    fn numeric_shot(&self) -> Option<&str> {
+        match self.shot {
+            Some(LevelType::Term(ref shot)) if !shot.is_empty() && shot.chars().all(|c| c.is_ascii_digit()) => {
+                Some(shot)
+            },
+            _ => None
+        }
+    }
+
+    /// Convert to a vector of &str
+    pub fn to_vec_str<'a>(&'a self) -> Vec<&'a LevelType> {
+        let mut vec_strs = Vec::<&'a LevelType>::new();
+        //let val = self.show.to_str();
+        vec_strs.push(self.show());
+        if let Some(ref val) = self.sequence {
+            vec_strs.push(val);
+            if let Some(ref val) = self.shot {
+                vec_strs.push(val);
+            }
+        }
+        vec_strs
+    }
+
+    /// Retrieve the show, sequence, and shot as display form str slices,
+    /// without cloning.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::new("DEV01.%").unwrap();
+    /// assert_eq!(ls.as_str_tuple(), ("DEV01", Some("%"), None));
+    /// ```
+    pub fn as_str_tuple(&self) -> (&str, Option<&str>, Option<&str>) {
+        (
+            self.show.to_str(),
+            self.sequence.as_ref().map(LevelType::to_str),
+            self.shot.as_ref().map(LevelType::to_str),
+        )
+    }
+
+    /// Convert to a vector of owned Strings, in display form
+    pub fn to_vec_string(&self) -> Vec<String> {
+        self.to_vec_str().iter().map(|level| level.to_string()).collect()
+    }
+
+    /// Consume the LevelSpec, converting it to a vector of Strings in display form
+    pub fn into_vec(self) -> Vec<String> {
+        let mut vec_strings = Vec::with_capacity(3);
+        vec_strings.push(self.show.into_string());
+        if let Some(sequence) = self.sequence {
+            vec_strings.push(sequence.into_string());
+            if let Some(shot) = self.shot {
+                vec_strings.push(shot.into_string());
+            }
+        }
+        vec_strings
+    }
+
+    /// Render the LevelSpec as a string, controlling the separator, case, shot
+    /// padding, and representation of relative levels.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `options` - The FormatOptions controlling the output
+    /// 
+    /// # Returns
+    /// The formatted String
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, FormatOptions};
+    /// 
+    /// let ls = LevelSpec::new("..0001").unwrap();
+    /// let options = FormatOptions::default().with_relative_marker("<rel>");
+    /// assert_eq!(ls.format_with(&options), "<rel>.<rel>.0001");
+    /// ```
+    pub fn format_with(&self, options: &FormatOptions) -> String {
+        self.to_vec_str()
+            .iter()
+            .zip(LEVEL_NAMES.iter())
+            .map(|(level, name)| options.format_level(*name, level))
+            .collect::<Vec<_>>()
+            .join(&options.separator)
+    }
+
+    /// Check each level against the supplied schema. Unlike parsing, this may be
+    /// used to check LevelSpecs constructed via `from_show`, `from_sequence`,
+    /// and `from_shot`, which perform no validation.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `schema` - The LevelSchema describing the site naming rules
+    /// 
+    /// # Returns
+    /// Ok if valid. Otherwise, a Vec of every ValidationError found
+    pub fn validate(&self, schema: &LevelSchema) -> Result<(), Vec<ValidationError>> {
+        schema.validate(self)
+    }
+
+    /// Apply site rules for case, shot padding, aliases, and keywords, returning
+    /// the canonical LevelSpec along with a report of what changed.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `rules` - The NormalizeRules to apply
+    /// 
+    /// # Returns
+    /// A tuple of the normalized LevelSpec and the list of changes made
+    pub fn normalize(&self, rules: &NormalizeRules) -> (Self, Vec<NormalizeChange>) {
+        rules.apply(self)
+    }
+
+    /// Replace the show and sequence with their canonical names, if they are
+    /// aliases in the supplied table. See `AliasTable`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `aliases` - The AliasTable to resolve against
+    /// 
+    /// # Returns
+    /// A new LevelSpec with its aliases resolved
+    pub fn resolve_aliases(&self, aliases: &AliasTable) -> Self {
+        aliases.resolve(self)
+    }
+
+    /// Returns true if the LevelSpecs refer to the same level once their
+    /// canonical forms are compared. Levels are compared ignoring ASCII case,
+    /// and numeric levels by value, ignoring zero padding, so `dev01.rd.1`
+    /// equals `DEV01.RD.0001`. Wildcards and relative levels equal only their
+    /// own kind. Site rules, such as aliases, are not applied; normalize both
+    /// LevelSpecs first to take them into account.
+    /// 
+    /// This is the equivalence `find_conflicts` groups LevelSpecs by, and
+    /// should be used when deduplicating LevelSpecs from different sources.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `other` - The LevelSpec to compare against
+    /// 
+    /// # Returns
+    /// true if the LevelSpecs are canonically equal
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert!(ls.eq_canonical(&LevelSpec { shot: Some("1".into()), ..ls.clone() }));
+    /// assert!(!ls.eq_canonical(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+    /// ```
+    pub fn eq_canonical(&self, other: &LevelSpec) -> bool {
+        let eq = |left: Option<&LevelType>, right: Option<&LevelType>| match (left, right) {
+            (Some(left), Some(right)) => {
+                left.is_relative() == right.is_relative()
+                    && canonical_level(left.to_str()).eq_ignore_ascii_case(canonical_level(right.to_str()))
+            },
+            (None, None) => true,
+            _ => false,
+        };
+        eq(Some(&self.show), Some(&other.show))
+            && eq(self.sequence.as_ref(), other.sequence.as_ref())
+            && eq(self.shot.as_ref(), other.shot.as_ref())
+    }
+
+    /// Render the LevelSpec as a path using the supplied PathTemplate. Use
+    /// `to_path_with` to render reserved sequences with their own templates.
+    /// Rendering is fallible because a template whose `{root}` is unset would
+    /// otherwise yield a path relative to `/`, silently pointing callers at
+    /// the wrong tree; the error reports the misconfiguration instead.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `template` - The PathTemplate describing the filesystem layout
+    /// 
+    /// # Returns
+    /// A PathBuf, in which levels missing from the LevelSpec truncate the
+    /// path, or a PathTemplateError if the template's `{root}` is unset
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, PathTemplate};
+    /// use std::path::{Path, PathBuf};
+    /// 
+    /// let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+    /// let ls = LevelSpec::from_sequence("DEV01", "RD");
+    /// assert_eq!(ls.to_path(&template), Ok(PathBuf::from("/jobs/DEV01/RD")));
+    /// ```
+    pub fn to_path(&self, template: &PathTemplate) -> Result<PathBuf, LSE> {
+        template.render(self)
+    }
+
+    /// Render the LevelSpec as a path using the supplied PathTemplate, as
+    /// `to_path` does. A reserved sequence with its own template is rendered
+    /// with that template instead, inheriting the supplied template's root if
+    /// it has none. See `ReservedSequences`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `template` - The PathTemplate describing the filesystem layout
+    /// * `reserved` - The sequences the site reserves
+    /// 
+    /// # Returns
+    /// A PathBuf, or a PathTemplateError if the `{root}` of the template
+    /// used is unset
+    pub fn to_path_with(&self, template: &PathTemplate, reserved: &ReservedSequences) -> Result<PathBuf, LSE> {
+        match self.reserved_template(reserved) {
+            Some(reserved) => match template.root() {
+                Some(root) if reserved.root().is_none() => reserved.clone().with_root(root).render(self),
+                _ => reserved.render(self),
+            },
+            None => template.render(self),
+        }
+    }
+
+    // the template of the reserved sequence, if the sequence is one and has one
+    pub(crate) fn reserved_template<'a>(&self, reserved: &'a ReservedSequences) -> Option<&'a PathTemplate> {
+        match self.sequence {
+            Some(LevelType::Term(ref sequence)) => reserved.template(sequence),
+            _ => None,
+        }
+    }
+
+    /// Render the LevelSpec as a directory beneath the supplied root, eg
+    /// `<root>/DEV01/RD/0001`. `PathBuf::from(&levelspec)` does the same
+    /// beneath the global JobRoot. See `JobRoot::path_for`.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `root` - The directory holding the shows, such as a JobRoot
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// use std::path::PathBuf;
+    /// 
+    /// let ls = LevelSpec::from_sequence("DEV01", "RD");
+    /// assert_eq!(ls.to_path_under("/jobs"), PathBuf::from("/jobs/DEV01/RD"));
+    /// ```
+    pub fn to_path_under<P>(&self, root: P) -> PathBuf
+    where
+        P: AsRef<Path>
+    {
+        JobRoot::new(root).path_for(self)
+    }
+
+    /// New up a LevelSpec from a path using the supplied PathTemplate. This
+    /// is the inverse of `to_path`. Use `PathTemplate::match_path` to also
+    /// retrieve the values of custom tokens.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `path` - The path to match, which may stop short of the full template
+    /// * `template` - The PathTemplate describing the filesystem layout
+    /// 
+    /// # Returns
+    /// A LevelSpec, or a PathTemplateError if the path does not match
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, PathTemplate};
+    /// 
+    /// let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+    /// let ls = LevelSpec::from_path("/jobs/DEV01/RD", &template).unwrap();
+    /// assert_eq!(ls, LevelSpec::from_sequence("DEV01", "RD"));
+    /// ```
+    pub fn from_path<P>(path: P, template: &PathTemplate) -> Result<Self, LSE>
+    where
+        P: AsRef<Path>
+    {
+        template.match_path(path).map(|(levelspec, _)| levelspec)
+    }
+
+    /// New up the LevelSpec enclosing the current working directory, using
+    /// the supplied PathSchema. See `PathSchema::levelspec_for`.
+    /// 
+    /// # Returns
+    /// A LevelSpec, an EnvError if the current directory cannot be read, or
+    /// a PathTemplateError if it is not within a LevelSpec's directory
+    pub fn from_cwd(schema: &PathSchema) -> Result<Self, LSE> {
+        let cwd = env::current_dir()
+            .map_err(|e| LSE::EnvError(format!("Unable to read current directory: {}", e)))?;
+        schema.levelspec_for(cwd)
+    }
+
+    /// New up a LevelSpec from the `DD_SHOW`, `DD_SEQUENCE`, and `DD_SHOT`
+    /// environment variables. See `from_env_with` for details.
+    pub fn from_env() -> Result<Self, LSE> {
+        Self::from_env_with(&EnvVars::default())
+    }
+
+    /// New up a LevelSpec from the environment variables named by `vars`.
+    /// The show variable must be set. The sequence and shot variables are
+    /// optional; however, the shot is only read if the sequence is set. Empty
+    /// variables are treated as unset. Each value is a single level, built
+    /// and validated as per `try_from_shot`, so values holding a `.` are
+    /// rejected rather than read as several levels.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `vars` - The names of the show, sequence, and shot variables
+    /// 
+    /// # Returns
+    /// A LevelSpec instance, an EnvError if the show is unset or a value
+    /// holds a `.`, or a ParseError if a value is not a legal level
+    pub fn from_env_with(vars: &EnvVars) -> Result<Self, LSE> {
+        let read = |name: &str| match env::var(name).ok().filter(|val| !val.is_empty()) {
+            Some(val) if val.contains('.') => Err(LSE::EnvError(format!("{} holds more than one level: {}", name, val))),
+            val => Ok(val),
+        };
+
+        let show = read(&vars.show)?
+            .ok_or_else(|| LSE::EnvError(format!("{} is not set", vars.show)))?;
+        match read(&vars.sequence)? {
+            None => LevelSpec::try_from_show(show),
+            Some(sequence) => match read(&vars.shot)? {
+                None => LevelSpec::try_from_sequence(show, sequence),
+                Some(shot) => LevelSpec::try_from_shot(show, sequence, shot),
+            },
+        }
+    }
+
+    /// Convert to a map of `DD_SHOW`, `DD_SEQUENCE`, and `DD_SHOT` variables.
+    /// See `to_env_map_with` for details.
+    pub fn to_env_map(&self) -> HashMap<String, String> {
+        self.to_env_map_with(&EnvVars::default())
+    }
+
+    /// Convert to a map of environment variable name to value, for each
+    /// level present in the LevelSpec. Values are in display form.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `vars` - The names of the show, sequence, and shot variables
+    /// 
+    /// # Returns
+    /// HashMap of variable name to value
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, EnvVars};
+    /// 
+    /// let ls = LevelSpec::from_sequence("DEV01", "RD");
+    /// let map = ls.to_env_map_with(&EnvVars::default());
+    /// assert_eq!(map.get("DD_SHOW").map(|x| x.as_str()), Some("DEV01"));
+    /// assert_eq!(map.get("DD_SEQUENCE").map(|x| x.as_str()), Some("RD"));
+    /// assert_eq!(map.get("DD_SHOT"), None);
+    /// ```
+    pub fn to_env_map_with(&self, vars: &EnvVars) -> HashMap<String, String> {
+        let mut map = HashMap::new();
+        map.insert(vars.show.clone(), self.show.to_string());
+        if let Some(ref sequence) = self.sequence {
+            map.insert(vars.sequence.clone(), sequence.to_string());
+        }
+        if let Some(ref shot) = self.shot {
+            map.insert(vars.shot.clone(), shot.to_string());
+        }
+        map
+    }
+
+    /// Set the `DD_SHOW`, `DD_SEQUENCE`, and `DD_SHOT` variables in the
+    /// current process environment. See `apply_to_env_with` for details.
+    pub fn apply_to_env(&self) {
+        self.apply_to_env_with(&EnvVars::default())
+    }
+
+    /// Set the variables named by `vars` in the current process environment.
+    /// Variables for levels missing from the LevelSpec are removed, so that
+    /// a sequence does not inherit a stale shot.
+    pub fn apply_to_env_with(&self, vars: &EnvVars) {
+        let map = self.to_env_map_with(vars);
+        for name in &[&vars.show, &vars.sequence, &vars.shot] {
+            match map.get(name.as_str()) {
+                Some(val) => env::set_var(name, val),
+                None => env::remove_var(name),
+            }
+        }
+    }
+
+}
+
+impl FromStr for LevelSpec {
+    type Err = LSE;
+
+    /// Parse a levelspec. The parser yields a fixed size LevelSpecRef, so
+    /// the only allocations are those of the LevelSpec's own terms.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        levelspec_ref_parser(s).map(|levels| levels.to_levelspec())
+    }
+}
+
+// LevelSpecs are stored in text columns using their display form, and 
+// validated when read back
+#[cfg(feature = "sqlx")]
+impl<DB: sqlx::Database> sqlx::Type<DB> for LevelSpec
+where
+    String: sqlx::Type<DB>
+{
+    fn type_info() -> DB::TypeInfo {
+        <String as sqlx::Type<DB>>::type_info()
+    }
+
+    fn compatible(ty: &DB::TypeInfo) -> bool {
+        <String as sqlx::Type<DB>>::compatible(ty)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'q, DB: sqlx::Database> sqlx::Encode<'q, DB> for LevelSpec
+where
+    String: sqlx::Encode<'q, DB>
+{
+    fn encode_by_ref(&self, buf: &mut <DB as sqlx::database::HasArguments<'q>>::ArgumentBuffer) -> sqlx::encode::IsNull {
+        <String as sqlx::Encode<'q, DB>>::encode(self.to_string(), buf)
+    }
+}
+
+#[cfg(feature = "sqlx")]
+impl<'r, DB: sqlx::Database> sqlx::Decode<'r, DB> for LevelSpec
+where
+    &'r str: sqlx::Decode<'r, DB>
+{
+    fn decode(value: <DB as sqlx::database::HasValueRef<'r>>::ValueRef) -> Result<Self, sqlx::error::BoxDynError> {
+        let input = <&'r str as sqlx::Decode<'r, DB>>::decode(value)?;
+        LevelSpec::from_str(input).map_err(|e| e.to_string().into())
+    }
+}
+
+// diesel binds text by reference on some backends, which a LevelSpec cannot
+// provide, so writing is implemented for postgres only. Reading works on any
+// backend.
+#[cfg(feature = "diesel")]
+impl diesel::serialize::ToSql<diesel::sql_types::Text, diesel::pg::Pg> for LevelSpec {
+    fn to_sql<'b>(&'b self, out: &mut diesel::serialize::Output<'b, '_, diesel::pg::Pg>) -> diesel::serialize::Result {
+        use std::io::Write;
+        write!(out, "{}", self)?;
+        Ok(diesel::serialize::IsNull::No)
+    }
+}
+
+#[cfg(feature = "diesel")]
+impl<DB: diesel::backend::Backend> diesel::deserialize::FromSql<diesel::sql_types::Text, DB> for LevelSpec
+where
+    String: diesel::deserialize::FromSql<diesel::sql_types::Text, DB>
+{
+    fn from_sql(bytes: DB::RawValue<'_>) -> diesel::deserialize::Result<Self> {
+        let input = <String as diesel::deserialize::FromSql<diesel::sql_types::Text, DB>>::from_sql(bytes)?;
+        LevelSpec::from_str(&input).map_err(|e| e.to_string().into())
+    }
+}
+
+// Human readable formats, such as JSON, use the display form. Binary formats,
+// such as bincode, use the compact versioned layout of `to_bytes`
+#[cfg(feature = "serde")]
+impl serde::Serialize for LevelSpec {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        if serializer.is_human_readable() {
+            serializer.collect_str(self)
+        } else {
+            serializer.serialize_bytes(&self.to_bytes().map_err(serde::ser::Error::custom)?)
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LevelSpec {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct LevelSpecVisitor;
+
+        impl<'de> serde::de::Visitor<'de> for LevelSpecVisitor {
+            type Value = LevelSpec;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                write!(f, "a levelspec string or encoded levelspec bytes")
+            }
+
+            fn visit_str<E: serde::de::Error>(self, input: &str) -> Result<LevelSpec, E> {
+                LevelSpec::from_str(input).map_err(E::custom)
+            }
+
+            fn visit_bytes<E: serde::de::Error>(self, input: &[u8]) -> Result<LevelSpec, E> {
+                LevelSpec::from_bytes(input).map_err(E::custom)
+            }
+
+            fn visit_seq<A: serde::de::SeqAccess<'de>>(self, mut seq: A) -> Result<LevelSpec, A::Error> {
+                let mut bytes = Vec::new();
+                while let Some(byte) = seq.next_element::<u8>()? {
+                    bytes.push(byte);
+                }
+                LevelSpec::from_bytes(&bytes).map_err(serde::de::Error::custom)
+            }
+        }
+
+        if deserializer.is_human_readable() {
+            deserializer.deserialize_str(LevelSpecVisitor)
+        } else {
+            deserializer.deserialize_bytes(LevelSpecVisitor)
+        }
+    }
+}
+
+/// Separator used by the alternate (`{:#}`) Display form of a LevelSpec
+pub const PATH_SEPARATOR: &str = "/";
+
+impl LevelSpec {
+    // write the levels of the LevelSpec, separated by `sep`. A shot without a
+    // sequence, which only the public fields can build, is written with a
+    // relative sequence in between.
+    fn fmt_separated(&self, f: &mut fmt::Formatter, sep: &str) -> fmt::Result {
+        match self {
+            LevelSpec{show, sequence: Some(seq), shot: Some(sht)} => {
+                write!(f, "{}{}{}{}{}", show, sep, seq, sep, sht)
+            },
+            LevelSpec{show, sequence: None, shot: Some(sht)} => {
+                write!(f, "{}{}{}{}", show, sep, sep, sht)
+            },
+            LevelSpec{show, sequence: Some(seq), shot: None } => {
+                write!(f, "{}{}{}", show, sep, seq)
+            },
+            LevelSpec{show, sequence: None, shot: None } => {
+                write!(f, "{}", show)
+            },
+        }
+    }
+
+    /// Render the LevelSpec in dotted form, as Display does, provided that
+    /// the result parses back into the same shape. Display renders any
+    /// LevelSpec, including ones built via the public fields which no input
+    /// could produce.
+    /// 
+    /// # Returns
+    /// The dotted form, or a LevelOrderError if it would parse into other
+    /// levels, as with a shot without a sequence, a relative sequence between
+    /// an absolute show and shot, or entirely relative levels, which render
+    /// one period short. A ParseError is returned if a level is not legal.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelType};
+    /// 
+    /// assert_eq!(LevelSpec::from_shot("DEV01", "RD", "0001").to_string_checked(), Ok("DEV01.RD.0001".to_string()));
+    /// 
+    /// let ls = LevelSpec { show: LevelType::from("DEV01"), sequence: None, shot: Some(LevelType::from("0001")) };
+    /// assert_eq!(ls.to_string(), "DEV01..0001");
+    /// assert!(ls.to_string_checked().is_err());
+    /// ```
+    pub fn to_string_checked(&self) -> Result<String, LSE> {
+        match self {
+            LevelSpec{show, sequence: None, shot: Some(shot)} => Err(LSE::LevelOrderError(format!(
+                "shot '{}' may not appear without a sequence under show '{}'", shot, show))),
+            LevelSpec{show, sequence: Some(sequence), shot: Some(shot)}
+                if sequence.is_relative() && !show.is_relative() && !shot.is_relative() => {
+                Err(LSE::LevelOrderError(format!(
+                    "relative sequence may not appear between show '{}' and shot '{}'", show, shot)))
+            },
+            _ => {
+                let rendered = self.to_string();
+                let parsed = levelspec_ref_parser(&rendered)?;
+                let expected = self.to_vec_str().iter().map(|level| level.is_relative()).collect::<Vec<_>>();
+                let found = parsed.iter().map(|level| level.is_relative()).collect::<Vec<_>>();
+                if found == expected {
+                    Ok(rendered)
+                } else {
+                    Err(LSE::LevelOrderError(format!("'{}' parses into different levels", rendered)))
+                }
+            },
         }
     }
 
-    /// Convert to a vector of &str
-    pub fn to_vec_str<'a>(&'a self) -> Vec<&'a LevelType> {
-        let mut vec_strs = Vec::<&'a LevelType>::new();
-        //let val = self.show.to_str();
-        vec_strs.push(self.show());
-        if let Some(ref val) = self.sequence {
-            vec_strs.push(val);
-            if let Some(ref val) = self.shot {
-                vec_strs.push(val);
-            }
-        }
-        vec_strs
+    /// Retrieve a Display adapter which renders the LevelSpec with the supplied
+    /// separator between levels.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelSpec;
+    /// 
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(format!("{}", ls.display_separated("::")), "DEV01::RD::0001");
+    /// ```
+    pub fn display_separated<'a>(&'a self, separator: &'a str) -> SeparatedDisplay<'a> {
+        SeparatedDisplay { levelspec: self, separator }
     }
 
+    /// Retrieve a Display adapter which renders the show's long name from the
+    /// supplied registry, followed by the remaining levels, for reports.
+    /// Shows missing from the registry are rendered by their code.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, ShowRegistry};
+    /// 
+    /// let registry = ShowRegistry::default().with_show("DEV01", "Development");
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(format!("{}", ls.display_long(&registry)), "Development RD.0001");
+    /// ```
+    pub fn display_long<'a>(&'a self, registry: &'a ShowRegistry) -> LongDisplay<'a> {
+        LongDisplay { levelspec: self, registry }
+    }
 }
 
-impl FromStr for LevelSpec {
-    type Err = LSE;
+/// Display adapter returned by `LevelSpec::display_separated`
+#[derive(Debug, Clone, Copy)]
+pub struct SeparatedDisplay<'a> {
+    levelspec: &'a LevelSpec,
+    separator: &'a str,
+}
 
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let mut levels = levelspec_parser(s)?;
+impl<'a> fmt::Display for SeparatedDisplay<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        self.levelspec.fmt_separated(f, self.separator)
+    }
+}
+
+impl TryFrom<Vec<LevelType>> for LevelSpec {
+    type Error = LSE;
+
+    /// Build a LevelSpec from a show, sequence, shot ordered Vec of LevelTypes,
+    /// such as that returned by `levelspec_parser`. As with the parser, a
+    /// relative sequence between an absolute show and an absolute shot is
+    /// rejected.
+    fn try_from(levels: Vec<LevelType>) -> Result<Self, Self::Error> {
         match levels.len() {
-            3 => {
-                let shot = levels.pop();
-                let sequence = levels.pop();
-                let show = levels.pop().unwrap();
-                Ok(LevelSpec{show, sequence, shot})
-            },
-            2 => {
-                let sequence = levels.pop();
-                let show = levels.pop().unwrap();
-                Ok(LevelSpec{show, sequence, shot:None})
-            },
-            1 => {
-                Ok(LevelSpec{show:levels.pop().unwrap(), sequence:None, shot:None})
-            },
-            _ => panic!("cannot create levelspec with more than 3 levels")
+            0 => return Err(LSE::NoLevels),
+            1..=3 => (),
+            count => return Err(LSE::TooManyLevels(count)),
+        }
+        if levels.len() == 3 && levels[1].is_relative() && !levels[0].is_relative() && !levels[2].is_relative() {
+            return Err(LSE::LevelOrderError(format!(
+                "relative sequence may not appear between show '{}' and shot '{}'", levels[0], levels[2])));
         }
+        let mut levels = levels.into_iter();
+        let show = levels.next().unwrap();
+        let sequence = levels.next();
+        let shot = levels.next();
+        Ok(LevelSpec{show, sequence, shot})
     }
 }
 
 impl fmt::Display for LevelSpec {
+    /// Renders the LevelSpec in dotted form, eg `DEV01.RD.0001`. The alternate
+    /// form (`{:#}`) renders the path form, eg `DEV01/RD/0001`.
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        match self {
-            LevelSpec{show, sequence: Some(seq), shot: Some(sht)} => {
-                write!(f, "{}.{}.{}", show, seq, sht)
-            },
-            LevelSpec{show, sequence: Some(seq), shot: None } => {
-                write!(f, "{}.{}", show, seq)
-            },
-            LevelSpec{show, sequence: None, shot: None } => {
-                write!(f, "{}", show)
-            },
-            _ => panic!("non legal levelspec")
-        }
+        let sep = if f.alternate() {PATH_SEPARATOR} else {"."};
+        self.fmt_separated(f, sep)
     }
 }
 
@@ -262,6 +1706,7 @@ impl fmt::Display for LevelSpec {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::LevelContext;
 
     #[test]
     fn can_replace_relative_shot_with_absolute() {
@@ -276,6 +1721,97 @@ mod tests {
 
         assert_eq!(new_ls, Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
     }
+    #[derive(Debug, PartialEq)]
+    enum ResolverError {
+        Offline,
+        LevelSpecter(LSE),
+    }
+
+    impl From<LSE> for ResolverError {
+        fn from(err: LSE) -> Self {
+            ResolverError::LevelSpecter(err)
+        }
+    }
+
+    #[test]
+    fn rel_to_abs_try_propagates_replacer_error() {
+        let ls = LevelSpec::from_str("..0001").unwrap();
+        let new_ls = ls.rel_to_abs_try(|_| Err(ResolverError::Offline));
+        assert_eq!(new_ls, Err(ResolverError::Offline));
+    }
+
+    #[test]
+    fn rel_to_abs_try_converts_missing_level_error() {
+        let ls = LevelSpec::from_str(".RD").unwrap();
+        let new_ls = ls.rel_to_abs_try(|_| Ok::<_, ResolverError>(None));
+        assert_eq!(
+            new_ls,
+            Err(ResolverError::LevelSpecter(LSE::RelToAbsError("Unable to retrieve Show in rel_to_abs".to_string())))
+        );
+    }
+
+    #[test]
+    fn rel_to_abs_rejects_relative_replacement() {
+        let ls = LevelSpec::from_str(".RD").unwrap();
+        let new_ls = ls.rel_to_abs(|_| Some("".to_string()));
+        assert_eq!(new_ls, Err(LSE::RelToAbsError("show returned by closure is relative ''".to_string())));
+    }
+
+    #[test]
+    fn can_make_shot_relative_to_sequence() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(ls.abs_to_rel(&LevelSpec::from_sequence("DEV01", "RD")), LevelSpec::from_str("..0001").unwrap());
+        assert_eq!(ls.abs_to_rel(&LevelSpec::from_sequence("DEV01", "AA")), LevelSpec::from_str(".RD.0001").unwrap());
+    }
+
+    #[test]
+    fn abs_to_rel_stops_at_first_difference() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(ls.abs_to_rel(&LevelSpec::from_shot("DEV02", "RD", "0001")), ls);
+    }
+
+    #[test]
+    fn abs_to_rel_keeps_the_last_level_absolute() {
+        let cases = [
+            ("DEV01", "DEV01", "DEV01"),
+            ("DEV01", "DEV01.RD", "DEV01"),
+            ("DEV01.RD", "DEV01.RD", ".RD"),
+            ("DEV01.RD", "DEV01.RD.0001", ".RD"),
+            ("DEV01.RD.0001", "DEV01.RD.0001", "..0001"),
+        ];
+        for (input, base, expect) in &cases {
+            let rel = LevelSpec::from_str(input).unwrap().abs_to_rel(&LevelSpec::from_str(base).unwrap());
+            assert_eq!(rel, LevelSpec::from_str(expect).unwrap());
+            assert_eq!(LevelSpec::from_str(&rel.to_string()), Ok(rel));
+        }
+    }
+
+    #[test]
+    fn abs_to_rel_round_trips_with_rel_to_abs() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        let base = LevelSpec::from_shot("DEV01", "RD", "0002");
+        let rel = ls.abs_to_rel(&base);
+        assert_eq!(rel, LevelSpec::from_str("..0001").unwrap());
+        assert_eq!(rel.rel_to_abs_with(&LevelContext::from(&base)), Ok(ls));
+    }
+
+    #[test]
+    fn can_replace_relative_levels_from_context() {
+        let ls = LevelSpec::from_str(".RD.").unwrap();
+        let context = LevelContext::new(Some("DEV01"), None, Some("0002"));
+        assert_eq!(ls.rel_to_abs_with(&context), Ok(LevelSpec::from_shot("DEV01", "RD", "0002")));
+    }
+
+    #[test]
+    fn rel_to_abs_with_fails_when_context_lacks_level() {
+        let ls = LevelSpec::from_str("..0001").unwrap();
+        let context = LevelContext::from(&LevelSpec::from_show("DEV01"));
+        assert_eq!(
+            ls.rel_to_abs_with(&context),
+            Err(LSE::RelToAbsError("Unable to retrieve Sequence in rel_to_abs".to_string()))
+        );
+    }
+
     #[test]
     fn can_parse_show() {
         let result = LevelSpec::from_str("DEV01");
@@ -291,6 +1827,53 @@ mod tests {
         assert_eq!(result, expect);
     }
 
+    #[test]
+    fn eq_canonical_ignores_case_and_padding() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0010");
+        let loose = LevelSpec { show: "dev01".into(), sequence: Some("rd".into()), shot: Some("10".into()) };
+        assert!(ls.eq_canonical(&loose) && loose.eq_canonical(&ls));
+        assert!(LevelSpec::from_shot("DEV01", "RD", "0000").eq_canonical(&LevelSpec::from_shot("DEV01", "RD", "0")));
+        assert!(!ls.eq_canonical(&LevelSpec::from_shot("DEV01", "RD", "0100")));
+        assert!(!ls.eq_canonical(&LevelSpec::from_sequence("DEV01", "RD")));
+        assert!(!ls.eq_canonical(&LevelSpec::new(".RD.0010").unwrap()));
+        assert!(LevelSpec::new("DEV01.%").unwrap().eq_canonical(&LevelSpec::new("DEV01.%").unwrap()));
+    }
+
+    #[test]
+    fn try_from_validates_each_level() {
+        let invalid = |level, position| Err(LSE::ParseError(crate::ParseFailure::InvalidLevel { level, position }));
+        assert_eq!(LevelSpec::try_from_show("DEV01"), Ok(LevelSpec::from_show("DEV01")));
+        assert_eq!(LevelSpec::try_from_show("DEV 01"), invalid(LevelName::Show, 0));
+        assert_eq!(LevelSpec::try_from_sequence("DEV01", "%"), Ok(LevelSpec::from_sequence("DEV01", "%")));
+        assert_eq!(LevelSpec::try_from_sequence("DEV01", "RD1"), invalid(LevelName::Sequence, 6));
+        assert_eq!(LevelSpec::try_from_shot("DEV01", "ASSETDEV", "FOO"), Ok(LevelSpec::from_shot("DEV01", "ASSETDEV", "FOO")));
+        assert_eq!(LevelSpec::try_from_shot("DEV01", "RD", "X"), invalid(LevelName::Shot, 9));
+        assert_eq!(LevelSpec::try_from_shot("DEV01", "RD", "00?0"), invalid(LevelName::Shot, 9));
+        assert!(matches!(LevelSpec::try_from_shot("DEV01", "", "0001"), Err(LSE::ParseError(crate::ParseFailure::InvalidStructure { .. }))));
+    }
+
+    #[test]
+    fn display_renders_shot_without_sequence() {
+        let ls = LevelSpec { show: LevelType::from("DEV01"), sequence: None, shot: Some(LevelType::from("0001")) };
+        assert_eq!(ls.to_string(), "DEV01..0001");
+        assert_eq!(format!("{:#}", ls), "DEV01//0001");
+        assert!(matches!(ls.to_string_checked(), Err(LSE::LevelOrderError(_))));
+        let ls = LevelSpec { sequence: Some(LevelType::Relative), ..ls };
+        assert!(matches!(ls.to_string_checked(), Err(LSE::LevelOrderError(_))));
+        assert_eq!(LevelSpec::new(".RD.0001").unwrap().to_string_checked(), Ok(".RD.0001".to_string()));
+    }
+
+    #[test]
+    fn checked_display_rejects_entirely_relative_levels() {
+        let ls = LevelSpec { show: LevelType::Relative, sequence: None, shot: None };
+        assert_eq!(ls.to_string(), "");
+        assert!(ls.to_string_checked().is_err());
+        let ls = LevelSpec { show: LevelType::Relative, sequence: Some(LevelType::Relative), shot: None };
+        assert_eq!(ls.to_string(), ".");
+        assert!(matches!(ls.to_string_checked(), Err(LSE::LevelOrderError(_))));
+        assert!(LevelSpec::from_show("bad name").to_string_checked().is_err());
+    }
+
     #[cfg(not(feature = "case-insensitive"))]
     #[test]
     fn cannot_parse_show_with_lowercase_name() {
@@ -298,7 +1881,7 @@ mod tests {
         assert_eq!(
             result, 
             Err(LSE::ParseError(
-                "Unable to parse levelspec for dev01".to_string())));
+                crate::ParseFailure::InvalidLevel { level: LevelName::Show, position: 0 })));
     }
 
     #[test]
@@ -340,7 +1923,7 @@ mod tests {
         assert_eq!(
             result, 
             Err(LSE::ParseError(
-                "Unable to parse levelspec for dev01.rd.0001".to_string()))
+                crate::ParseFailure::InvalidLevel { level: LevelName::Show, position: 0 }))
         );
     }
 
@@ -538,7 +2121,7 @@ mod tests {
     fn can_get_show_from_levelspec() {
         let ls = LevelSpec::from_show("DEV01");
         let show = ls.show();
-        assert_eq!(show, &LevelType::Term("DEV01".to_string()));
+        assert_eq!(show, &LevelType::Term("DEV01".into()));
         assert_eq!(ls.sequence(), None);
         assert_eq!(ls.shot(), None);
     }
@@ -546,17 +2129,387 @@ mod tests {
     #[test]
     fn can_get_sequence_from_levelspec() {
         let ls = LevelSpec::from_sequence("DEV01","RD");
-        assert_eq!(ls.show(), &LevelType::Term("DEV01".to_string()));
-        assert_eq!(ls.sequence(), Some(&LevelType::Term("RD".to_string())));
+        assert_eq!(ls.show(), &LevelType::Term("DEV01".into()));
+        assert_eq!(ls.sequence(), Some(&LevelType::Term("RD".into())));
         assert_eq!(ls.shot(), None);
     }
 
     #[test]
     fn can_get_shot_from_levelspec() {
         let ls = LevelSpec::from_shot("DEV01","RD", "0001");
-        assert_eq!(ls.show(), &LevelType::Term("DEV01".to_string()));
-        assert_eq!(ls.sequence(), Some(&LevelType::Term("RD".to_string())));
-        assert_eq!(ls.shot(), Some(&LevelType::Term("0001".to_string())));
+        assert_eq!(ls.show(), &LevelType::Term("DEV01".into()));
+        assert_eq!(ls.sequence(), Some(&LevelType::Term("RD".into())));
+        assert_eq!(ls.shot(), Some(&LevelType::Term("0001".into())));
+    }
+
+    #[test]
+    fn from_static_does_not_allocate_uppercase_terms() {
+        let ls = LevelSpec::from_static("DEV01", Some("RD"), Some("0001"));
+        assert!(matches!(ls.show, LevelType::Term(Cow::Borrowed(_))));
+        assert!(matches!(ls.sequence, Some(LevelType::Term(Cow::Borrowed(_)))));
+        assert!(matches!(ls.shot, Some(LevelType::Term(Cow::Borrowed(_)))));
+    }
+
+    #[test]
+    fn alternate_display_renders_path_form() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(format!("{:#}", ls), "DEV01/RD/0001");
+        assert_eq!(format!("{:#}", LevelSpec::from_sequence("DEV01", "RD")), "DEV01/RD");
+        assert_eq!(format!("{}", ls), "DEV01.RD.0001");
+    }
+
+    #[test]
+    fn can_get_components() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(
+            ls.components(),
+            LevelSpecComponents::Shot(&LevelType::from("DEV01"), &LevelType::from("RD"), &LevelType::from("0001"))
+        );
+        let ls = LevelSpec::from_show("DEV01");
+        assert_eq!(ls.components(), LevelSpecComponents::Show(&LevelType::from("DEV01")));
+    }
+
+    #[test]
+    fn matches_requires_same_depth() {
+        let pattern = LevelSpec::from_str("DEV01.%").unwrap();
+        assert!(pattern.matches(&LevelSpec::from_sequence("DEV01", "RD")));
+        assert!(!pattern.matches(&LevelSpec::from_shot("DEV01", "RD", "0001")));
+        assert!(!pattern.matches(&LevelSpec::from_show("DEV01")));
+    }
+
+    #[test]
+    fn relative_levels_do_not_match() {
+        let pattern = LevelSpec::from_str(".RD.0001").unwrap();
+        assert!(!pattern.matches(&LevelSpec::from_shot("DEV01", "RD", "0001")));
+    }
+
+    #[test]
+    fn expand_against_skips_non_concrete_entries() {
+        let universe = vec![
+            LevelSpec::from_str("DEV01.RD.%").unwrap(),
+            LevelSpec::from_str("DEV01.RD.0001").unwrap(),
+            LevelSpec::from_str("..0002").unwrap(),
+        ];
+        let pattern = LevelSpec::from_str("DEV01.%.%").unwrap();
+        assert_eq!(pattern.expand_against(&universe), vec![&universe[1]]);
+    }
+
+    #[test]
+    fn specificity_ranks_depth_then_terms_then_position() {
+        let score = |spec: &str| LevelSpec::from_str(spec).unwrap().specificity();
+        assert!(score("%.%.%") > score("DEV01.RD"));
+        assert!(score("DEV01.RD.%") > score("%.%.0001"));
+        assert!(score("%.%.0001") > score("DEV01.%.%"));
+        assert!(score("DEV01.RD.0001") > score("DEV01.RD.%"));
+        assert_eq!(score("..0001"), score("DEV01.RD.0001"));
+    }
+
+    #[test]
+    fn merge_ignores_wildcard_and_relative_overlay_levels() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        let overlay = LevelSpec::from_str("%.AA.").unwrap();
+        assert_eq!(ls.merge(&overlay), LevelSpec::from_shot("DEV01", "AA", "0001"));
+    }
+
+    #[test]
+    fn merge_ignores_pattern_overlay_levels() {
+        let ls = LevelSpec::from_sequence("DEV01", "RD");
+        let overlay = LevelSpec::from_shot("DEV01", "R%", "00?1");
+        assert_eq!(ls.merge(&overlay), LevelSpec::from_shot("DEV01", "RD", "00?1"));
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(ls.merge(&overlay), ls);
+    }
+
+    #[test]
+    fn merge_extends_shallower_levelspec() {
+        let ls = LevelSpec::from_show("DEV01");
+        let overlay = LevelSpec::from_str(".RD.0002").unwrap();
+        assert_eq!(ls.merge(&overlay), LevelSpec::from_shot("DEV01", "RD", "0002"));
+    }
+
+    #[test]
+    fn truncate_to_leaves_shallower_levelspec_unchanged() {
+        let ls = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(ls.truncate_to(LevelName::Shot), ls);
+        assert_eq!(ls.truncate_to(LevelName::Sequence), ls);
+    }
+
+    #[test]
+    fn can_get_str_tuple() {
+        let ls = LevelSpec::from_str(".RD.0001").unwrap();
+        assert_eq!(ls.as_str_tuple(), ("", Some("RD"), Some("0001")));
+    }
+
+    #[test]
+    fn can_try_from_vec() {
+        let levels = vec![LevelType::from("DEV01"), LevelType::from("RD")];
+        assert_eq!(LevelSpec::try_from(levels), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+    }
+
+    #[test]
+    fn try_from_vec_rejects_bad_lengths() {
+        assert_eq!(LevelSpec::try_from(Vec::new()), Err(LSE::NoLevels));
+        let levels = vec!["A", "B", "1", "2"].into_iter().map(LevelType::from).collect::<Vec<_>>();
+        assert_eq!(LevelSpec::try_from(levels), Err(LSE::TooManyLevels(4)));
+    }
+
+    #[test]
+    fn from_str_rejects_too_many_levels() {
+        assert_eq!(LevelSpec::from_str("A.B.C.D"), Err(LSE::TooManyLevels(4)));
+        assert_eq!(LevelSpec::from_str("DEV01.RD.0001.X.Y"), Err(LSE::TooManyLevels(5)));
+    }
+
+    #[test]
+    fn try_from_vec_rejects_bad_ordering() {
+        let levels = vec![LevelType::from("DEV01"), LevelType::Relative, LevelType::from("0001")];
+        assert_eq!(
+            LevelSpec::try_from(levels),
+            Err(LSE::LevelOrderError("relative sequence may not appear between show 'DEV01' and shot '0001'".to_string()))
+        );
+    }
+
+    #[test]
+    fn can_count_levels() {
+        assert_eq!(LevelSpec::from_str("DEV01").unwrap().levels(), 1);
+        assert_eq!(LevelSpec::from_str(".RD").unwrap().levels(), 2);
+        let ls = LevelSpec::from_str("%.%.%").unwrap();
+        assert_eq!(ls.levels(), 3);
+        assert!(ls.has_sequence() && ls.has_shot());
+    }
+
+    #[test]
+    fn patterns_are_not_concrete() {
+        let ls = LevelSpec::from_shot("DEV01", "RD%", "0001");
+        assert!(!ls.is_concrete());
+        assert_eq!(ls.wildcard_levels(), vec![LevelName::Sequence]);
+    }
+
+    #[test]
+    fn can_match_patterns() {
+        let pattern = LevelSpec::from_shot("DEV01", "R%", "00?1");
+        assert!(pattern.matches(&LevelSpec::from_shot("DEV01", "RD", "0011")));
+        assert!(!pattern.matches(&LevelSpec::from_shot("DEV01", "AA", "0011")));
+    }
+
+    #[test]
+    fn specificity_ranks_patterns_between_terms_and_wildcards() {
+        let score = |ls: LevelSpec| ls.specificity();
+        assert!(score(LevelSpec::from_shot("DEV01", "RD", "%")) > score(LevelSpec::from_shot("DEV01", "R%", "%")));
+        assert!(score(LevelSpec::from_shot("DEV01", "R%", "%")) > score(LevelSpec::from_shot("DEV01", "%", "%")));
+    }
+
+    #[test]
+    fn can_get_shot_number() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0120");
+        assert_eq!(ls.shot_number(), Some(120));
+        assert_eq!(ls.shot_padding(), Some(4));
+    }
+
+    #[test]
+    fn shot_number_is_none_for_non_numeric_shots() {
+        for spec in &["DEV01.RD", "DEV01.RD.%", "DEV01.RD.", "DEV01.ASSETDEV.FOO"] {
+            let ls = LevelSpec::from_str(spec).unwrap();
+            assert_eq!(ls.shot_number(), None);
+            assert_eq!(ls.shot_padding(), None);
+        }
+    }
+
+    #[test]
+    fn can_get_next_and_prev_shot() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0010");
+        assert_eq!(ls.next_shot(10), Ok(LevelSpec::from_shot("DEV01", "RD", "0020")));
+        assert_eq!(ls.prev_shot(5), Ok(LevelSpec::from_shot("DEV01", "RD", "0005")));
+    }
+
+    #[test]
+    fn offset_shot_grows_past_padding() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "99");
+        assert_eq!(ls.offset_shot(1), Ok(LevelSpec::from_shot("DEV01", "RD", "100")));
+    }
+
+    #[test]
+    fn offset_shot_cannot_go_negative() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0010");
+        assert_eq!(
+            ls.prev_shot(11),
+            Err(LSE::ShotError("offsetting 'DEV01.RD.0010' by -11 is out of range".to_string()))
+        );
+    }
+
+    #[test]
+    fn offset_shot_requires_numeric_shot() {
+        let ls = LevelSpec::from_str("DEV01.RD.%").unwrap();
+        assert_eq!(
+            ls.next_shot(1),
+            Err(LSE::ShotError("shot of 'DEV01.RD.%' is not numeric".to_string()))
+        );
+    }
+
+    #[test]
+    fn format_with_default_options_matches_display() {
+        let ls = LevelSpec::from_str(".RD.%").unwrap();
+        assert_eq!(ls.format_with(&FormatOptions::default()), ls.to_string());
+    }
+
+    #[test]
+    fn format_with_pads_only_numeric_shots() {
+        let options = FormatOptions::default().with_shot_padding(4);
+        assert_eq!(LevelSpec::from_shot("DEV01", "RD", "1").format_with(&options), "DEV01.RD.0001");
+        assert_eq!(LevelSpec::from_shot("DEV01", "RD", "%").format_with(&options), "DEV01.RD.%");
+        assert_eq!(LevelSpec::from_shot("DEV01", "RD", "12345").format_with(&options), "DEV01.RD.12345");
+    }
+
+    #[test]
+    fn can_determine_depth() {
+        let show = LevelSpec::from_show("DEV01");
+        assert!(show.is_show() && !show.is_sequence() && !show.is_shot());
+        let seq = LevelSpec::from_sequence("DEV01", "RD");
+        assert!(!seq.is_show() && seq.is_sequence() && !seq.is_shot());
+        let shot = LevelSpec::from_str("..%").unwrap();
+        assert!(!shot.is_show() && !shot.is_sequence() && shot.is_shot());
+    }
+
+    #[test]
+    fn can_detect_assetdev() {
+        assert!(LevelSpec::from_str("DEV01.ASSETDEV.FOO").unwrap().is_assetdev());
+        assert!(LevelSpec::from_str("DEV01.ASSETDEV").unwrap().is_assetdev());
+        assert!(!LevelSpec::from_str("DEV01.RD.0001").unwrap().is_assetdev());
+        assert!(!LevelSpec::from_str("DEV01").unwrap().is_assetdev());
+    }
+
+    #[cfg(feature = "case-insensitive")]
+    #[test]
+    fn can_detect_lowercase_assetdev() {
+        assert!(LevelSpec::from_str("dev01.assetdev.foo").unwrap().is_assetdev());
+    }
+
+    #[test]
+    fn can_classify_sequence() {
+        assert_eq!(LevelSpec::from_str("DEV01").unwrap().sequence_kind(), None);
+        assert_eq!(LevelSpec::from_str("DEV01.RD").unwrap().sequence_kind(), Some(SequenceKind::Standard));
+        assert_eq!(LevelSpec::from_str("DEV01.%").unwrap().sequence_kind(), Some(SequenceKind::Wildcard));
+        assert_eq!(LevelSpec::from_str("..0001").unwrap().sequence_kind(), Some(SequenceKind::Relative));
+    }
+
+    #[test]
+    fn can_report_relative_levels() {
+        let ls = LevelSpec::from_str(".RD.").unwrap();
+        assert!(ls.has_relative());
+        assert_eq!(ls.relative_levels(), vec![LevelName::Show, LevelName::Shot]);
+    }
+
+    #[test]
+    fn absolute_levelspec_has_no_relative_levels() {
+        let ls = LevelSpec::from_str("DEV01.%.0001").unwrap();
+        assert!(!ls.has_relative());
+        assert_eq!(ls.relative_levels(), Vec::new());
+    }
+
+    #[test]
+    fn can_report_wildcard_levels() {
+        let ls = LevelSpec::from_str("%.RD.%").unwrap();
+        assert_eq!(ls.wildcard_levels(), vec![LevelName::Show, LevelName::Shot]);
+        assert_eq!(LevelSpec::from_str("DEV01.RD").unwrap().wildcard_levels(), Vec::new());
+    }
+
+    #[test]
+    fn can_convert_to_vec_string() {
+        let ls = LevelSpec::from_str(".RD.%").unwrap();
+        assert_eq!(ls.to_vec_string(), vec!["".to_string(), "RD".to_string(), "%".to_string()]);
+    }
+
+    #[test]
+    fn can_convert_into_vec() {
+        let ls = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(ls.into_vec(), vec!["DEV01".to_string(), "RD".to_string()]);
+    }
+
+    #[test]
+    fn can_convert_to_sql_like() {
+        let ls = LevelSpec::from_shot("DEV01", "%", "00?0");
+        assert_eq!(ls.to_sql_like(), Ok("DEV01._%.00_0".to_string()));
+        assert_eq!(LevelSpec::from_sequence("DEV01", "RD").to_sql_like(), Ok("DEV01.RD".to_string()));
+        assert!(LevelSpec::from_str(".RD").unwrap().to_sql_like().is_err());
+    }
+
+    #[test]
+    fn sql_like_escapes_patterns() {
+        let ls = LevelSpec {
+            show: LevelType::Term("DEV_01".into()),
+            sequence: Some(LevelType::Pattern("R_\\%".to_string())),
+            shot: Some(LevelType::Pattern("0?%1".to_string())),
+        };
+        assert_eq!(ls.to_sql_like(), Ok("DEV\\_01.R\\_\\\\%.0_%1".to_string()));
+    }
+
+    #[test]
+    fn can_round_trip_through_bytes() {
+        for input in &["DEV01", "DEV01.RD", "DEV01.RD.0001", ".RD.%", "..", "DEV01.ASSETDEV.FOO1A"] {
+            let ls = LevelSpec::from_str(input).unwrap();
+            assert_eq!(LevelSpec::from_bytes(&ls.to_bytes().unwrap()), Ok(ls));
+        }
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_as_display_string_when_human_readable() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        let json = serde_json::to_string(&ls).unwrap();
+        assert_eq!(json, "\"DEV01.RD.0001\"");
+        assert_eq!(serde_json::from_str::<LevelSpec>(&json).unwrap(), ls);
+        assert!(serde_json::from_str::<LevelSpec>("\"DEV01.R-D\"").is_err());
+    }
+
+    #[test]
+    fn shell_arg_quotes_wildcards() {
+        assert_eq!(LevelSpec::from_str("DEV01..").unwrap().to_shell_arg(), "DEV01..");
+        assert_eq!(LevelSpec::from_str("%.%").unwrap().to_shell_arg(), "'%.%'");
+        assert_eq!(LevelSpec::from_shot("DEV01", "RD", "00?0").to_shell_arg(), "'DEV01.RD.00?0'");
+        assert_eq!(LevelSpec::from_show("IT'S").to_shell_arg(), "'IT'\\''S'");
+    }
+
+    #[test]
+    fn can_round_trip_through_env() {
+        let vars = EnvVars::new("LS_TEST_RT_SHOW", "LS_TEST_RT_SEQ", "LS_TEST_RT_SHOT");
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        ls.apply_to_env_with(&vars);
+        assert_eq!(LevelSpec::from_env_with(&vars), Ok(ls));
+    }
+
+    #[test]
+    fn apply_to_env_removes_missing_levels() {
+        let vars = EnvVars::new("LS_TEST_RM_SHOW", "LS_TEST_RM_SEQ", "LS_TEST_RM_SHOT");
+        LevelSpec::from_shot("DEV01", "RD", "0001").apply_to_env_with(&vars);
+        LevelSpec::from_sequence("DEV01", "AA").apply_to_env_with(&vars);
+        assert_eq!(LevelSpec::from_env_with(&vars), Ok(LevelSpec::from_sequence("DEV01", "AA")));
+    }
+
+    #[test]
+    fn from_env_requires_show() {
+        let vars = EnvVars::new("LS_TEST_NO_SHOW", "LS_TEST_NO_SEQ", "LS_TEST_NO_SHOT");
+        assert_eq!(
+            LevelSpec::from_env_with(&vars),
+            Err(LSE::EnvError("LS_TEST_NO_SHOW is not set".to_string()))
+        );
+    }
+
+    #[test]
+    fn from_env_reads_one_level_per_variable() {
+        let vars = EnvVars::new("LS_TEST_DOT_SHOW", "LS_TEST_DOT_SEQ", "LS_TEST_DOT_SHOT");
+        env::set_var("LS_TEST_DOT_SHOW", "DEV01.RD");
+        env::remove_var("LS_TEST_DOT_SEQ");
+        assert_eq!(
+            LevelSpec::from_env_with(&vars),
+            Err(LSE::EnvError("LS_TEST_DOT_SHOW holds more than one level: DEV01.RD".to_string()))
+        );
+        env::set_var("LS_TEST_DOT_SHOW", "DEV01");
+        env::set_var("LS_TEST_DOT_SEQ", "RD");
+        env::set_var("LS_TEST_DOT_SHOT", "0001.0002");
+        assert!(LevelSpec::from_env_with(&vars).is_err());
+        env::set_var("LS_TEST_DOT_SHOT", "00X1");
+        assert!(matches!(LevelSpec::from_env_with(&vars), Err(LSE::ParseError(_))));
+        env::set_var("LS_TEST_DOT_SHOT", "0001");
+        assert_eq!(LevelSpec::from_env_with(&vars), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
     }
 
 }
\ No newline at end of file
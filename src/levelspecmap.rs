@@ -0,0 +1,164 @@
+use crate::LevelSpec;
+use std::iter::FromIterator;
+
+/// A map keyed by LevelSpec, whose keys may contain wildcards and patterns.
+/// Looking up a concrete LevelSpec with `get` returns the value of the most
+/// specific matching key, as ranked by `LevelSpec::specificity`. This makes
+/// the map suited to per-shot overrides of show or sequence wide settings.
+/// 
+/// When several keys match with equal specificity, the earliest inserted wins.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelSpecMap};
+/// 
+/// let mut fps = LevelSpecMap::new();
+/// fps.insert(LevelSpec::new("DEV01.%.%").unwrap(), 24);
+/// fps.insert(LevelSpec::new("DEV01.RD.%").unwrap(), 48);
+/// fps.insert(LevelSpec::new("DEV01.RD.0003").unwrap(), 12);
+/// 
+/// assert_eq!(fps.get(&LevelSpec::from_shot("DEV01", "AA", "0001")), Some(&24));
+/// assert_eq!(fps.get(&LevelSpec::from_shot("DEV01", "RD", "0001")), Some(&48));
+/// assert_eq!(fps.get(&LevelSpec::from_shot("DEV01", "RD", "0003")), Some(&12));
+/// assert_eq!(fps.get(&LevelSpec::from_sequence("DEV01", "RD")), None);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LevelSpecMap<V> {
+    entries: Vec<(LevelSpec, V)>,
+}
+
+impl<V> LevelSpecMap<V> {
+    /// New up an empty LevelSpecMap
+    pub fn new() -> Self {
+        Self { entries: Vec::new() }
+    }
+
+    /// Insert a value for the supplied key.
+    /// 
+    /// # Returns
+    /// The previous value for an identical key, if there was one
+    pub fn insert(&mut self, key: LevelSpec, value: V) -> Option<V> {
+        match self.entries.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.entries.push((key, value));
+                None
+            }
+        }
+    }
+
+    /// Retrieve the value of the most specific key matching the supplied
+    /// LevelSpec. A key identical to the LevelSpec always matches, so 
+    /// wildcarded LevelSpecs may be used to retrieve wildcarded keys.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `levelspec` - The LevelSpec to look up, typically concrete
+    /// 
+    /// # Returns
+    /// The matching value, if any key matches
+    pub fn get(&self, levelspec: &LevelSpec) -> Option<&V> {
+        self.get_key_value(levelspec).map(|(_, value)| value)
+    }
+
+    /// Retrieve the most specific key matching the supplied LevelSpec, along
+    /// with its value. See `get`.
+    pub fn get_key_value(&self, levelspec: &LevelSpec) -> Option<(&LevelSpec, &V)> {
+        if let Some((key, value)) = self.entries.iter().find(|(k, _)| k == levelspec) {
+            return Some((key, value));
+        }
+        let mut best: Option<&(LevelSpec, V)> = None;
+        for entry in self.entries.iter().filter(|(key, _)| key.matches(levelspec)) {
+            match best {
+                Some((key, _)) if key.specificity() >= entry.0.specificity() => (),
+                _ => best = Some(entry),
+            }
+        }
+        best.map(|(key, value)| (key, value))
+    }
+
+    /// Retrieve the value of a key identical to the supplied LevelSpec,
+    /// without wildcard matching
+    pub fn get_exact(&self, key: &LevelSpec) -> Option<&V> {
+        self.entries.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+    }
+
+    /// Remove the supplied key, returning its value if present
+    pub fn remove(&mut self, key: &LevelSpec) -> Option<V> {
+        let idx = self.entries.iter().position(|(k, _)| k == key)?;
+        Some(self.entries.remove(idx).1)
+    }
+
+    /// Retrieve the number of keys in the map
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// Returns true if the map has no keys
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Iterate over the keys and values, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = (&LevelSpec, &V)> {
+        self.entries.iter().map(|(key, value)| (key, value))
+    }
+}
+
+impl<V> Default for LevelSpecMap<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V> FromIterator<(LevelSpec, V)> for LevelSpecMap<V> {
+    fn from_iter<I: IntoIterator<Item = (LevelSpec, V)>>(iter: I) -> Self {
+        let mut map = LevelSpecMap::new();
+        for (key, value) in iter {
+            map.insert(key, value);
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn ls(spec: &str) -> LevelSpec {
+        LevelSpec::from_str(spec).unwrap()
+    }
+
+    #[test]
+    fn prefers_later_terms_over_earlier_terms() {
+        let map = vec![(ls("DEV01.%.%"), "show"), (ls("%.RD.%"), "sequence"), (ls("%.%.0001"), "shot")]
+            .into_iter().collect::<LevelSpecMap<_>>();
+        assert_eq!(map.get(&ls("DEV01.RD.0001")), Some(&"shot"));
+        assert_eq!(map.get(&ls("DEV01.RD.0002")), Some(&"sequence"));
+        assert_eq!(map.get(&ls("DEV01.AA.0002")), Some(&"show"));
+        assert_eq!(map.get(&ls("DEV02.AA.0002")), None);
+    }
+
+    #[test]
+    fn insert_replaces_identical_keys() {
+        let mut map = LevelSpecMap::new();
+        assert_eq!(map.insert(ls("DEV01.%"), 1), None);
+        assert_eq!(map.insert(ls("DEV01.%"), 2), Some(1));
+        assert_eq!(map.len(), 1);
+        assert_eq!(map.get(&ls("DEV01.%")), Some(&2));
+        assert_eq!(map.get_exact(&ls("DEV01.RD")), None);
+        assert_eq!(map.remove(&ls("DEV01.%")), Some(2));
+        assert!(map.is_empty());
+    }
+
+    #[test]
+    fn earliest_key_wins_ties() {
+        let map = vec![
+            (LevelSpec::from_shot("DEV01", "R%", "%"), 1),
+            (LevelSpec::from_shot("DEV01", "%D", "%"), 2),
+        ].into_iter().collect::<LevelSpecMap<_>>();
+        assert_eq!(map.get(&ls("DEV01.RD.0001")), Some(&1));
+    }
+}
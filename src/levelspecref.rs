@@ -0,0 +1,158 @@
+use std::fmt;
+use crate::{LevelSpec, LevelSpecterError as LSE, LevelTypeRef, LevelTypeVec, levelparser::levelspec_ref_parser};
+
+/// Borrowed mirror of LevelSpec, whose levels are LevelTypeRefs pointing
+/// into the parsed input. Parsing into a LevelSpecRef performs no heap
+/// allocation for valid input, making it suitable for hot loops which
+/// inspect many levelspecs but keep few of them. Convert to a LevelSpec
+/// via `to_levelspec` to keep one.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, LevelSpecRef, LevelTypeRef};
+///
+/// let ls = LevelSpecRef::parse("DEV01.RD.0001").unwrap();
+/// assert_eq!(ls.sequence(), Some(LevelTypeRef::Term("RD")));
+/// assert_eq!(ls.to_levelspec(), LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub struct LevelSpecRef<'a> {
+    pub show: LevelTypeRef<'a>,
+    pub sequence: Option<LevelTypeRef<'a>>,
+    pub shot: Option<LevelTypeRef<'a>>,
+}
+
+impl<'a> LevelSpecRef<'a> {
+    /// Parse a levelspec without allocating. Accepts the same input as
+    /// `LevelSpec::from_str`.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The levelspec, which the result borrows from
+    ///
+    /// # Returns
+    /// A LevelSpecRef, or a ParseError if the input is not a legal levelspec
+    pub fn parse(input: &'a str) -> Result<Self, LSE> {
+        levelspec_ref_parser(input)
+    }
+
+    // New up from the strs matched by the parser. Empty strs are relative.
+    pub(crate) fn from_levels(show: &'a str, sequence: Option<&'a str>, shot: Option<&'a str>) -> Self {
+        Self {
+            show: LevelTypeRef::from(show),
+            sequence: sequence.map(LevelTypeRef::from),
+            shot: shot.map(LevelTypeRef::from),
+        }
+    }
+
+    /// Retrieve the show
+    pub fn show(&self) -> LevelTypeRef<'a> {
+        self.show
+    }
+
+    /// Retrieve the sequence, if any
+    pub fn sequence(&self) -> Option<LevelTypeRef<'a>> {
+        self.sequence
+    }
+
+    /// Retrieve the shot, if any
+    pub fn shot(&self) -> Option<LevelTypeRef<'a>> {
+        self.shot
+    }
+
+    /// Retrieve the number of levels, from 1 (show) to 3 (shot)
+    pub fn levels(&self) -> usize {
+        1 + self.sequence.is_some() as usize + self.shot.is_some() as usize
+    }
+
+    /// Iterate over the levels, from the show down
+    pub fn iter(&self) -> impl Iterator<Item = LevelTypeRef<'a>> {
+        std::iter::once(self.show).chain(self.sequence).chain(self.shot)
+    }
+
+    /// Returns true if no level is a wildcard or pattern. See `LevelSpec::is_concrete`
+    pub fn is_concrete(&self) -> bool {
+        self.iter().all(|level| !level.is_wildcard() && !matches!(level, LevelTypeRef::Pattern(_)))
+    }
+
+    /// Returns true if any level is relative
+    pub fn has_relative(&self) -> bool {
+        self.iter().any(|level| level.is_relative())
+    }
+
+    /// Convert to an owned LevelSpec, allocating for each term
+    pub fn to_levelspec(&self) -> LevelSpec {
+        LevelSpec {
+            show: self.show.to_level_type(),
+            sequence: self.sequence.map(|level| level.to_level_type()),
+            shot: self.shot.map(|level| level.to_level_type()),
+        }
+    }
+
+    /// Convert to the levels returned by `levelspec_parser`
+    pub fn to_level_types(&self) -> LevelTypeVec {
+        self.iter().map(|level| level.to_level_type()).collect()
+    }
+}
+
+impl<'a> From<LevelSpecRef<'a>> for LevelSpec {
+    fn from(input: LevelSpecRef<'a>) -> Self {
+        input.to_levelspec()
+    }
+}
+
+impl<'a> From<&'a LevelSpec> for LevelSpecRef<'a> {
+    fn from(input: &'a LevelSpec) -> Self {
+        Self {
+            show: input.show.as_level_ref(),
+            sequence: input.sequence.as_ref().map(|level| level.as_level_ref()),
+            shot: input.shot.as_ref().map(|level| level.as_level_ref()),
+        }
+    }
+}
+
+impl<'a> PartialEq<LevelSpec> for LevelSpecRef<'a> {
+    fn eq(&self, other: &LevelSpec) -> bool {
+        *self == LevelSpecRef::from(other)
+    }
+}
+
+impl<'a> fmt::Display for LevelSpecRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.show)?;
+        for level in self.iter().skip(1) {
+            write!(f, ".{}", level)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn parses_like_levelspec() {
+        for input in &["DEV01", "DEV01.RD", "DEV01.RD.0001", "DEV01.ASSETDEV.FOO1A", "%.%.%", ".RD.", "..0001", "DEV01..", "...", "."] {
+            let ls = LevelSpecRef::parse(input).unwrap();
+            assert_eq!(ls, LevelSpec::from_str(input).unwrap());
+            assert_eq!(ls.to_string(), LevelSpec::from_str(input).unwrap().to_string());
+        }
+        assert!(LevelSpecRef::parse("bad_line").is_err());
+    }
+
+    #[test]
+    fn borrows_from_input() {
+        let input = String::from("DEV01.RD.0001");
+        let ls = LevelSpecRef::parse(&input).unwrap();
+        match ls.show() {
+            LevelTypeRef::Term(show) => assert_eq!(show.as_ptr(), input.as_ptr()),
+            other => panic!("unexpected show {:?}", other),
+        }
+        assert!(ls.is_concrete());
+        assert!(!ls.has_relative());
+        assert_eq!(ls.levels(), 3);
+    }
+}
@@ -0,0 +1,426 @@
+use crate::{LevelSpec, LevelSpecterError as LSE, NaturalOrd, binary};
+#[cfg(feature = "csv")]
+use crate::LineError;
+use std::cmp::Ordering;
+use std::iter::FromIterator;
+#[cfg(feature = "csv")]
+use std::str::FromStr;
+
+/// Columns included when exporting a LevelSpecSet. The levelspec itself is
+/// always included.
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Default)]
+pub struct ExportOptions {
+    /// Include the number of levels
+    pub depth: bool,
+    /// Include whether the LevelSpec is concrete
+    pub concrete: bool,
+}
+
+impl ExportOptions {
+    /// Set whether the depth is included and return self
+    pub fn with_depth(mut self, depth: bool) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Set whether concreteness is included and return self
+    pub fn with_concrete(mut self, concrete: bool) -> Self {
+        self.concrete = concrete;
+        self
+    }
+}
+
+// A single exported row
+#[cfg(any(feature = "csv", feature = "json"))]
+#[cfg_attr(feature = "json", derive(serde::Serialize))]
+struct ExportRow {
+    levelspec: String,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    depth: Option<usize>,
+    #[cfg_attr(feature = "json", serde(skip_serializing_if = "Option::is_none"))]
+    concrete: Option<bool>,
+}
+
+/// A set of LevelSpecs, kept in the natural order of `NaturalOrd`, so that
+/// iteration and every export is the same however the set was built, and
+/// generated manifests diff cleanly.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelSpecSet};
+/// 
+/// let mut set = LevelSpecSet::new();
+/// assert!(set.insert(LevelSpec::from_shot("DEV01", "RD", "0010")));
+/// assert!(set.insert(LevelSpec::from_shot("DEV01", "RD", "0002")));
+/// assert!(!set.insert(LevelSpec::from_shot("DEV01", "RD", "0010")));
+/// assert_eq!(set.len(), 2);
+/// assert_eq!(set.iter().next(), Some(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LevelSpecSet {
+    // sorted by NaturalOrd, without duplicates
+    specs: Vec<LevelSpec>,
+}
+
+impl LevelSpecSet {
+    /// New up an empty LevelSpecSet
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a LevelSpec at its place in the natural order
+    /// 
+    /// # Returns
+    /// true if the LevelSpec was not already present
+    pub fn insert(&mut self, levelspec: LevelSpec) -> bool {
+        match self.position(&levelspec) {
+            Ok(_) => false,
+            Err(idx) => {
+                self.specs.insert(idx, levelspec);
+                true
+            }
+        }
+    }
+
+    /// Remove a LevelSpec
+    /// 
+    /// # Returns
+    /// true if the LevelSpec was present
+    pub fn remove(&mut self, levelspec: &LevelSpec) -> bool {
+        match self.position(levelspec) {
+            Ok(idx) => {
+                self.specs.remove(idx);
+                true
+            },
+            Err(_) => false,
+        }
+    }
+
+    /// Returns true if the set contains the LevelSpec
+    pub fn contains(&self, levelspec: &LevelSpec) -> bool {
+        self.position(levelspec).is_ok()
+    }
+
+    // locate a LevelSpec, or the index at which it belongs. NaturalOrd only
+    // finds distinct LevelSpecs equal, so each LevelSpec has one position.
+    fn position(&self, levelspec: &LevelSpec) -> Result<usize, usize> {
+        self.specs.binary_search_by(|member| natural(member, levelspec))
+    }
+
+    /// Retrieve the number of LevelSpecs in the set
+    pub fn len(&self) -> usize {
+        self.specs.len()
+    }
+
+    /// Returns true if the set is empty
+    pub fn is_empty(&self) -> bool {
+        self.specs.is_empty()
+    }
+
+    /// Iterate over the LevelSpecs in natural order
+    pub fn iter(&self) -> std::slice::Iter<'_, LevelSpec> {
+        self.specs.iter()
+    }
+
+    /// Retrieve the LevelSpecs as a slice, in natural order
+    pub fn as_slice(&self) -> &[LevelSpec] {
+        &self.specs
+    }
+
+    /// Encode as a versioned snapshot, in natural order. This is the layout
+    /// written by `binary::write_snapshot`.
+    /// 
+    /// # Returns
+    /// The encoded bytes, or a SerializeError if a level is longer than
+    /// `u16::MAX` bytes
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecSet};
+    /// 
+    /// let set = vec![LevelSpec::from_show("DEV01"), LevelSpec::from_sequence("DEV01", "RD")]
+    ///     .into_iter().collect::<LevelSpecSet>();
+    /// assert_eq!(LevelSpecSet::from_bytes(&set.to_bytes().unwrap()), Ok(set));
+    /// ```
+    pub fn to_bytes(&self) -> Result<Vec<u8>, LSE> {
+        let mut bytes = Vec::new();
+        binary::encode_snapshot(&self.specs, &mut bytes)?;
+        Ok(bytes)
+    }
+
+    /// New up a LevelSpecSet from the output of `to_bytes`, or any snapshot
+    /// 
+    /// # Parameters
+    /// 
+    /// * `bytes` - The encoded LevelSpecSet
+    /// 
+    /// # Returns
+    /// A LevelSpecSet, or a SerializeError if the bytes are truncated,
+    /// require a newer release to read, or hold invalid levels
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, LSE> {
+        binary::read_snapshot(bytes).map(Self::from_iter)
+    }
+
+    /// Render the set as one LevelSpec per line, in natural order, with each
+    /// line ending in a newline. Sets holding the same LevelSpecs always
+    /// render the same, making this suitable for manifests kept in version
+    /// control.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecSet};
+    /// 
+    /// let set = vec![LevelSpec::from_shot("DEV01", "RD", "0010"), LevelSpec::from_shot("DEV01", "RD", "0002")]
+    ///     .into_iter().collect::<LevelSpecSet>();
+    /// assert_eq!(set.canonical_string(), "DEV01.RD.0002\nDEV01.RD.0010\n");
+    /// ```
+    pub fn canonical_string(&self) -> String {
+        self.specs.iter().fold(String::new(), |mut out, levelspec| {
+            out.push_str(&levelspec.to_string());
+            out.push('\n');
+            out
+        })
+    }
+
+    /// Read LevelSpecs from a column of a CSV file with a header row, such as
+    /// an editorial turnover. Available with the `csv` feature.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `reader` - The source of the CSV data
+    /// * `column` - The header of the column holding the levelspecs
+    /// 
+    /// # Returns
+    /// A tuple of the set of LevelSpecs which parsed, and an error for each 
+    /// row which did not, carrying the row's line number. A CsvError is 
+    /// returned if the header cannot be read or lacks the column.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecSet};
+    /// 
+    /// let data = "shot,frames\nDEV01.RD.0001,100\nDEV01..0002,50\nDEV01.RD.0003,20\n";
+    /// let (set, errors) = LevelSpecSet::from_csv(data.as_bytes(), "shot").unwrap();
+    /// assert_eq!(set.len(), 2);
+    /// assert_eq!(errors[0].line, 3);
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn from_csv<R>(reader: R, column: &str) -> Result<(Self, Vec<LineError>), LSE>
+    where
+        R: std::io::Read
+    {
+        let mut reader = csv::Reader::from_reader(reader);
+        let headers = reader.headers()
+            .map_err(|e| LSE::CsvError(format!("Unable to read header: {}", e)))?;
+        let idx = headers.iter().position(|header| header.trim() == column)
+            .ok_or_else(|| LSE::CsvError(format!("No column named '{}'", column)))?;
+        let mut set = Self::new();
+        let mut errors = Vec::new();
+        for record in reader.records() {
+            let record = match record {
+                Ok(record) => record,
+                Err(e) => {
+                    let line = e.position().map_or(0, |pos| pos.line() as usize);
+                    errors.push(LineError { line, error: LSE::CsvError(e.to_string()) });
+                    continue;
+                }
+            };
+            let line = record.position().map_or(0, |pos| pos.line() as usize);
+            let parsed = record.get(idx)
+                .ok_or_else(|| LSE::CsvError(format!("Missing column '{}'", column)))
+                .and_then(|value| LevelSpec::from_str(value.trim()));
+            match parsed {
+                Ok(levelspec) => {set.insert(levelspec);},
+                Err(error) => errors.push(LineError { line, error }),
+            }
+        }
+        Ok((set, errors))
+    }
+}
+
+impl LevelSpecSet {
+    // the rows to export, in natural order
+    #[cfg(any(feature = "csv", feature = "json"))]
+    fn export_rows(&self, options: &ExportOptions) -> Vec<ExportRow> {
+        self.specs.iter().map(|levelspec| ExportRow {
+            levelspec: levelspec.to_string(),
+            depth: if options.depth {Some(levelspec.levels())} else {None},
+            concrete: if options.concrete {Some(levelspec.is_concrete())} else {None},
+        }).collect()
+    }
+
+    /// Write the set as CSV with a header row, sorted as per `sort_specs` so 
+    /// that output is stable. Available with the `csv` feature.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecSet, ExportOptions};
+    /// 
+    /// let set = vec![LevelSpec::from_shot("DEV01", "RD", "0010"), LevelSpec::new("DEV01.RD.%").unwrap()]
+    ///     .into_iter().collect::<LevelSpecSet>();
+    /// let csv = set.to_csv(&ExportOptions::default().with_concrete(true)).unwrap();
+    /// assert_eq!(csv, "levelspec,concrete\nDEV01.RD.%,false\nDEV01.RD.0010,true\n");
+    /// ```
+    #[cfg(feature = "csv")]
+    pub fn to_csv(&self, options: &ExportOptions) -> Result<String, LSE> {
+        let to_err = |e: csv::Error| LSE::CsvError(e.to_string());
+        let mut writer = csv::Writer::from_writer(Vec::new());
+        let mut header = vec!["levelspec"];
+        if options.depth {
+            header.push("depth");
+        }
+        if options.concrete {
+            header.push("concrete");
+        }
+        writer.write_record(&header).map_err(to_err)?;
+        for row in self.export_rows(options) {
+            let mut record = vec![row.levelspec];
+            record.extend(row.depth.map(|depth| depth.to_string()));
+            record.extend(row.concrete.map(|concrete| concrete.to_string()));
+            writer.write_record(&record).map_err(to_err)?;
+        }
+        let bytes = writer.into_inner().map_err(|e| LSE::CsvError(e.to_string()))?;
+        String::from_utf8(bytes).map_err(|e| LSE::CsvError(e.to_string()))
+    }
+
+    /// Write the set as a pretty printed JSON array of objects, sorted as per
+    /// `sort_specs` so that output is stable. Available with the `json` feature.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelSpec, LevelSpecSet, ExportOptions};
+    /// 
+    /// let set = vec![LevelSpec::from_show("DEV01")].into_iter().collect::<LevelSpecSet>();
+    /// let json = set.to_json(&ExportOptions::default().with_depth(true)).unwrap();
+    /// assert_eq!(json, "[\n  {\n    \"levelspec\": \"DEV01\",\n    \"depth\": 1\n  }\n]");
+    /// ```
+    #[cfg(feature = "json")]
+    pub fn to_json(&self, options: &ExportOptions) -> Result<String, LSE> {
+        serde_json::to_string_pretty(&self.export_rows(options))
+            .map_err(|e| LSE::SerializeError(format!("Unable to write JSON: {}", e)))
+    }
+}
+
+impl FromIterator<LevelSpec> for LevelSpecSet {
+    fn from_iter<I: IntoIterator<Item = LevelSpec>>(iter: I) -> Self {
+        let mut set = Self::new();
+        set.extend(iter);
+        set
+    }
+}
+
+impl Extend<LevelSpec> for LevelSpecSet {
+    // appending and sorting once is cheaper than inserting each in place
+    fn extend<I: IntoIterator<Item = LevelSpec>>(&mut self, iter: I) {
+        self.specs.extend(iter);
+        self.specs.sort_by(natural);
+        self.specs.dedup();
+    }
+}
+
+impl IntoIterator for LevelSpecSet {
+    type Item = LevelSpec;
+    type IntoIter = std::vec::IntoIter<LevelSpec>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.specs.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a LevelSpecSet {
+    type Item = &'a LevelSpec;
+    type IntoIter = std::slice::Iter<'a, LevelSpec>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.specs.iter()
+    }
+}
+
+fn natural(a: &LevelSpec, b: &LevelSpec) -> Ordering {
+    NaturalOrd::default().compare(a, b)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn remove_preserves_order() {
+        let mut set = vec![LevelSpec::from_show("C"), LevelSpec::from_show("A"), LevelSpec::from_show("B")]
+            .into_iter().collect::<LevelSpecSet>();
+        assert!(set.remove(&LevelSpec::from_show("B")));
+        assert!(!set.remove(&LevelSpec::from_show("B")));
+        assert_eq!(set.as_slice(), &[LevelSpec::from_show("A"), LevelSpec::from_show("C")]);
+    }
+
+    #[test]
+    fn order_is_independent_of_insertion() {
+        let specs = vec![
+            LevelSpec::from_shot("DEV01", "RD", "0010"),
+            LevelSpec::from_sequence("DEV01", "RD"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+            LevelSpec::from_shot("DEV01", "ASSETDEV", "FOO"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ];
+        let extended = specs.iter().cloned().collect::<LevelSpecSet>();
+        let mut inserted = LevelSpecSet::new();
+        for levelspec in specs.into_iter().rev() {
+            inserted.insert(levelspec);
+        }
+        assert_eq!(extended, inserted);
+        assert_eq!(inserted.to_bytes(), extended.to_bytes());
+        assert_eq!(
+            extended.canonical_string(),
+            "DEV01.ASSETDEV.FOO\nDEV01.RD\nDEV01.RD.0002\nDEV01.RD.0010\n"
+        );
+        assert!(extended.contains(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+        assert_eq!(LevelSpecSet::new().canonical_string(), "");
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_reports_row_errors() {
+        let data = "frames, shot \n100,DEV01.RD.0001\n50\n20, DEV01.RD.0001 \n10,bad\n";
+        let (set, errors) = LevelSpecSet::from_csv(data.as_bytes(), "shot").unwrap();
+        assert_eq!(set.as_slice(), &[LevelSpec::from_shot("DEV01", "RD", "0001")]);
+        assert_eq!(errors.iter().map(|e| e.line).collect::<Vec<_>>(), vec![3, 5]);
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_round_trips() {
+        let set = vec![LevelSpec::from_shot("DEV01", "RD", "0002"), LevelSpec::from_shot("DEV01", "AA", "0001")]
+            .into_iter().collect::<LevelSpecSet>();
+        let csv = set.to_csv(&ExportOptions::default().with_depth(true)).unwrap();
+        assert_eq!(csv, "levelspec,depth\nDEV01.AA.0001,3\nDEV01.RD.0002,3\n");
+        let (read, errors) = LevelSpecSet::from_csv(csv.as_bytes(), "levelspec").unwrap();
+        assert!(errors.is_empty());
+        assert_eq!(read.len(), 2);
+    }
+
+    #[cfg(feature = "json")]
+    #[test]
+    fn json_export_is_sorted() {
+        let set = vec![LevelSpec::from_shot("DEV01", "RD", "0010"), LevelSpec::from_shot("DEV01", "RD", "0002")]
+            .into_iter().collect::<LevelSpecSet>();
+        let json = set.to_json(&ExportOptions::default()).unwrap();
+        let rows: Vec<serde_json::Value> = serde_json::from_str(&json).unwrap();
+        assert_eq!(rows[0]["levelspec"], "DEV01.RD.0002");
+        assert!(rows[0].get("depth").is_none());
+    }
+
+    #[cfg(feature = "csv")]
+    #[test]
+    fn csv_requires_column() {
+        assert_eq!(
+            LevelSpecSet::from_csv("a,b\n1,2\n".as_bytes(), "shot").unwrap_err(),
+            LSE::CsvError("No column named 'shot'".to_string())
+        );
+    }
+}
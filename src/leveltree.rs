@@ -0,0 +1,340 @@
+use crate::{LevelSpec, LevelType};
+use std::collections::BTreeMap;
+use std::iter::FromIterator;
+
+// A node in the tree. Nodes are created for every prefix of an inserted
+// LevelSpec; `present` records whether the node itself was inserted.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+struct LevelNode {
+    present: bool,
+    children: BTreeMap<LevelType, LevelNode>,
+}
+
+impl LevelNode {
+    // visit every inserted spec at or below the node, in order
+    fn collect(&self, path: &mut Vec<LevelType>, specs: &mut Vec<LevelSpec>) {
+        if self.present {
+            specs.push(spec_from_path(path));
+        }
+        for (level, child) in &self.children {
+            path.push(level.clone());
+            child.collect(path, specs);
+            path.pop();
+        }
+    }
+}
+
+// detach the node at the end of `levels`, pruning implicit parents left empty
+fn remove_node(children: &mut BTreeMap<LevelType, LevelNode>, levels: &[&LevelType]) -> Option<LevelNode> {
+    let (level, rest) = levels.split_first()?;
+    if rest.is_empty() {
+        return children.remove(*level);
+    }
+    let node = children.get_mut(*level)?;
+    let removed = remove_node(&mut node.children, rest);
+    if !node.present && node.children.is_empty() {
+        children.remove(*level);
+    }
+    removed
+}
+
+// collect the nodes below `children` matching the remaining levels
+fn expand_level(children: &BTreeMap<LevelType, LevelNode>, levels: &[&LevelType], path: &mut Vec<LevelType>, specs: &mut Vec<LevelSpec>) {
+    let (level, rest) = match levels.split_first() {
+        Some(split) => split,
+        None => return,
+    };
+    let mut visit = |key: &LevelType, node: &LevelNode, specs: &mut Vec<LevelSpec>| {
+        path.push(key.clone());
+        if rest.is_empty() {
+            specs.push(spec_from_path(path));
+        } else {
+            expand_level(&node.children, rest, path, specs);
+        }
+        path.pop();
+    };
+    if level.is_term() && !cfg!(feature = "case-insensitive") {
+        if let Some((key, node)) = children.get_key_value(*level) {
+            visit(key, node, specs);
+        }
+    } else {
+        for (key, node) in children {
+            if level.matches(key) {
+                visit(key, node, specs);
+            }
+        }
+    }
+}
+
+// build a LevelSpec from a path of one to three levels
+fn spec_from_path(path: &[LevelType]) -> LevelSpec {
+    LevelSpec {
+        show: path[0].clone(),
+        sequence: path.get(1).cloned(),
+        shot: path.get(2).cloned(),
+    }
+}
+
+/// A set of LevelSpecs stored as a show → sequence → shot trie. Inserting
+/// or looking up a LevelSpec walks one node per level, and each node's 
+/// children are kept sorted, making the tree suited to browsers and pickers
+/// which present one level at a time.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelTree};
+/// 
+/// let mut tree = LevelTree::new();
+/// tree.insert(LevelSpec::from_shot("DEV01", "RD", "0002"));
+/// tree.insert(LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// tree.insert(LevelSpec::from_shot("DEV01", "AA", "0001"));
+/// 
+/// assert_eq!(tree.children(&LevelSpec::from_show("DEV01")), vec![
+///     LevelSpec::from_sequence("DEV01", "AA"),
+///     LevelSpec::from_sequence("DEV01", "RD"),
+/// ]);
+/// assert_eq!(tree.subtree(&LevelSpec::from_sequence("DEV01", "RD")).len(), 2);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct LevelTree {
+    shows: BTreeMap<LevelType, LevelNode>,
+    len: usize,
+}
+
+impl LevelTree {
+    /// New up an empty LevelTree
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a LevelSpec, creating nodes for its parent levels as needed.
+    /// 
+    /// # Returns
+    /// true if the LevelSpec was not already present
+    pub fn insert(&mut self, levelspec: LevelSpec) -> bool {
+        let mut levels = levelspec.to_vec_str().into_iter();
+        let show = levels.next().unwrap().clone();
+        let mut node = self.shows.entry(show).or_default();
+        for level in levels {
+            node = node.children.entry(level.clone()).or_default();
+        }
+        let inserted = !node.present;
+        node.present = true;
+        if inserted {
+            self.len += 1;
+        }
+        inserted
+    }
+
+    /// Returns true if the LevelSpec has been inserted. Parent levels created
+    /// implicitly by inserting their children are not considered present.
+    pub fn contains(&self, levelspec: &LevelSpec) -> bool {
+        self.node(levelspec).map(|node| node.present).unwrap_or(false)
+    }
+
+    /// Remove the node for the LevelSpec along with everything beneath it.
+    /// Implicit parents left without children are removed as well.
+    /// 
+    /// # Returns
+    /// The inserted LevelSpecs which were removed, in depth first order
+    pub fn remove_subtree(&mut self, levelspec: &LevelSpec) -> Vec<LevelSpec> {
+        let levels = levelspec.to_vec_str();
+        let mut removed = Vec::new();
+        if let Some(node) = remove_node(&mut self.shows, &levels) {
+            let mut path = levels.into_iter().cloned().collect();
+            node.collect(&mut path, &mut removed);
+        }
+        self.len -= removed.len();
+        removed
+    }
+
+    /// Returns true if the tree has a node for the LevelSpec, either because
+    /// it was inserted or because one of its descendants was
+    pub fn has_node(&self, levelspec: &LevelSpec) -> bool {
+        self.node(levelspec).is_some()
+    }
+
+    /// Retrieve the nodes matching the supplied pattern, in order. Matching
+    /// follows `LevelSpec::matches`, so only nodes at the pattern's depth are
+    /// returned. Terms in the pattern are looked up directly, while wildcard 
+    /// and pattern levels are tested against each child.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `pattern` - The LevelSpec to expand, eg `DEV01.%.0001`
+    /// 
+    /// # Returns
+    /// A Vec of matching LevelSpecs, including nodes created implicitly
+    pub fn expand(&self, pattern: &LevelSpec) -> Vec<LevelSpec> {
+        let levels = pattern.to_vec_str();
+        let mut specs = Vec::new();
+        expand_level(&self.shows, &levels, &mut Vec::new(), &mut specs);
+        specs
+    }
+
+    /// Retrieve the number of LevelSpecs inserted
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no LevelSpecs have been inserted
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Retrieve the shows in the tree, in order
+    pub fn shows(&self) -> Vec<LevelSpec> {
+        self.shows.keys().map(|show| LevelSpec { show: show.clone(), sequence: None, shot: None }).collect()
+    }
+
+    /// Retrieve the immediate children of the supplied node, in order. This
+    /// includes children created implicitly by inserting their descendants.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `parent` - The LevelSpec of the node whose children we want
+    /// 
+    /// # Returns
+    /// A Vec of child LevelSpecs, one level deeper than `parent`. The Vec is 
+    /// empty if the node has no children or does not exist.
+    pub fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+        let node = match self.node(parent) {
+            Some(node) => node,
+            None => return Vec::new(),
+        };
+        let path = parent.to_vec_str().into_iter().cloned().collect::<Vec<_>>();
+        node.children.keys().map(|level| {
+            let mut child = path.clone();
+            child.push(level.clone());
+            spec_from_path(&child)
+        }).collect()
+    }
+
+    /// Retrieve the inserted LevelSpecs at or below the supplied node, in 
+    /// depth first order
+    pub fn subtree(&self, root: &LevelSpec) -> Vec<LevelSpec> {
+        let mut specs = Vec::new();
+        if let Some(node) = self.node(root) {
+            let mut path = root.to_vec_str().into_iter().cloned().collect();
+            node.collect(&mut path, &mut specs);
+        }
+        specs
+    }
+
+    /// Retrieve every inserted LevelSpec, in depth first order
+    pub fn to_vec(&self) -> Vec<LevelSpec> {
+        let mut specs = Vec::with_capacity(self.len);
+        for (show, node) in &self.shows {
+            node.collect(&mut vec![show.clone()], &mut specs);
+        }
+        specs
+    }
+
+    // walk to the node of the supplied LevelSpec
+    fn node(&self, levelspec: &LevelSpec) -> Option<&LevelNode> {
+        let mut levels = levelspec.to_vec_str().into_iter();
+        let mut node = self.shows.get(levels.next()?)?;
+        for level in levels {
+            node = node.children.get(level)?;
+        }
+        Some(node)
+    }
+}
+
+impl FromIterator<LevelSpec> for LevelTree {
+    fn from_iter<I: IntoIterator<Item = LevelSpec>>(iter: I) -> Self {
+        let mut tree = LevelTree::new();
+        tree.extend(iter);
+        tree
+    }
+}
+
+impl Extend<LevelSpec> for LevelTree {
+    fn extend<I: IntoIterator<Item = LevelSpec>>(&mut self, iter: I) {
+        for levelspec in iter {
+            self.insert(levelspec);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> LevelTree {
+        vec![
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_sequence("DEV01", "AA"),
+            LevelSpec::from_show("TEST"),
+        ].into_iter().collect()
+    }
+
+    #[test]
+    fn insert_reports_new_specs() {
+        let mut tree = tree();
+        assert_eq!(tree.len(), 4);
+        assert!(!tree.insert(LevelSpec::from_show("TEST")));
+        assert!(tree.insert(LevelSpec::from_show("DEV01")));
+        assert_eq!(tree.len(), 5);
+    }
+
+    #[test]
+    fn implicit_parents_are_not_present() {
+        let tree = tree();
+        assert!(tree.contains(&LevelSpec::from_shot("DEV01", "RD", "0001")));
+        assert!(!tree.contains(&LevelSpec::from_sequence("DEV01", "RD")));
+        assert!(!tree.contains(&LevelSpec::from_show("NOPE")));
+    }
+
+    #[test]
+    fn enumerates_children_in_order() {
+        let tree = tree();
+        assert_eq!(tree.shows(), vec![LevelSpec::from_show("DEV01"), LevelSpec::from_show("TEST")]);
+        assert_eq!(tree.children(&LevelSpec::from_sequence("DEV01", "RD")), vec![
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ]);
+        assert!(tree.children(&LevelSpec::from_show("TEST")).is_empty());
+        assert!(tree.children(&LevelSpec::from_show("NOPE")).is_empty());
+    }
+
+    #[test]
+    fn expands_patterns_against_nodes() {
+        let tree = tree();
+        assert_eq!(tree.expand(&LevelSpec::new("DEV01.%").unwrap()), vec![
+            LevelSpec::from_sequence("DEV01", "AA"),
+            LevelSpec::from_sequence("DEV01", "RD"),
+        ]);
+        assert_eq!(tree.expand(&LevelSpec::new("%.RD.%").unwrap()).len(), 2);
+        assert_eq!(tree.expand(&LevelSpec::new("DEV01.RD.0001").unwrap()).len(), 1);
+        assert!(tree.expand(&LevelSpec::new("DEV01.RD.0003").unwrap()).is_empty());
+        assert!(tree.has_node(&LevelSpec::from_sequence("DEV01", "RD")));
+    }
+
+    #[test]
+    fn removes_subtrees_and_empty_parents() {
+        let mut tree = tree();
+        assert_eq!(tree.remove_subtree(&LevelSpec::from_sequence("DEV01", "RD")), vec![
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ]);
+        assert_eq!(tree.len(), 2);
+        assert!(tree.has_node(&LevelSpec::from_show("DEV01")));
+        tree.remove_subtree(&LevelSpec::from_sequence("DEV01", "AA"));
+        assert!(!tree.has_node(&LevelSpec::from_show("DEV01")));
+        assert!(tree.remove_subtree(&LevelSpec::from_show("NOPE")).is_empty());
+    }
+
+    #[test]
+    fn iterates_subtrees_depth_first() {
+        let tree = tree();
+        assert_eq!(tree.subtree(&LevelSpec::from_show("DEV01")), vec![
+            LevelSpec::from_sequence("DEV01", "AA"),
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ]);
+        assert_eq!(tree.to_vec().len(), 4);
+    }
+}
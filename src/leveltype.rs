@@ -1,15 +1,73 @@
 use std::fmt;
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::str::FromStr;
+use crate::{LevelSpecterError, LevelTypeRef, ParseFailure};
 
 /// Enum which models types of entries available in 
-/// the LevelSpec
-#[derive(Debug, PartialEq, Eq, Clone)]
+/// the LevelSpec. Terms are stored as a `Cow<'static, str>`, so that
+/// terms built from static strings do not allocate.
+/// 
+/// Patterns are names containing embedded wildcards: `%` matches any
+/// run of characters, and `?` matches a single character (eg `RD%`).
+/// 
+/// LevelTypes are ordered Relative < Wildcard < Pattern < Term. Terms are
+/// ordered naturally, comparing runs of digits by their value, so that `2`
+/// sorts before `10`. Patterns are ordered by their string value.
+#[derive(Debug, PartialEq, Eq, Clone, Hash)]
 pub enum LevelType {
-    Term(String),
+    Term(Cow<'static, str>),
+    Pattern(String),
     Wildcard,
     Relative
 }
 
+/// Classification of a LevelType. Distinguishes the ASSETDEV special
+/// term from other terms, so that callers need not compare strings.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum LevelKind {
+    /// A regular, named term
+    Term,
+    /// The ASSETDEV special term
+    AssetDev,
+    Pattern,
+    Wildcard,
+    Relative,
+}
+
+// returns true if the input contains embedded wildcard characters
+fn is_pattern_str(input: &str) -> bool {
+    input.contains(&['%', '?'][..])
+}
+
 impl LevelType {
+    /// New up a Term from a static str without allocating. As this is a 
+    /// const fn, it may be used to build constants. Unlike `from_static`, 
+    /// the input is not checked for wildcard or relative values.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelType;
+    /// 
+    /// const SHOW: LevelType = LevelType::static_term("DEV01");
+    /// assert_eq!(SHOW, LevelType::from("DEV01"));
+    /// ```
+    pub const fn static_term(input: &'static str) -> Self {
+        LevelType::Term(Cow::Borrowed(input))
+    }
+
+    /// New up a LevelType from a static str. Terms borrow the input
+    /// rather than allocating.
+    pub fn from_static(input: &'static str) -> Self {
+        match input {
+            "%" => LevelType::Wildcard,
+            "" => LevelType::Relative,
+            _ if is_pattern_str(input) => LevelType::Pattern(input.to_string()),
+            _ =>  LevelType::Term(Cow::Borrowed(input))
+        }
+    }
+
     /// Wildcard leveltype implies that the LevelSpec
     /// is not concrete.
     pub fn is_wildcard(&self) -> bool {
@@ -36,14 +94,108 @@ impl LevelType {
         }
     }
 
+    /// Pattern leveltype implies that the LevelSpec is not concrete
+    pub fn is_pattern(&self) -> bool {
+        matches!(self, LevelType::Pattern(_))
+    }
+
+    /// Returns true if the supplied concrete name matches the LevelType.
+    /// Terms match equal names, ignoring case in case-insensitive builds.
+    /// Wildcards match any name, and Patterns match names fitting the pattern.
+    /// Relative LevelTypes match nothing.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelType;
+    /// 
+    /// let pattern = LevelType::from("RD%");
+    /// assert!(pattern.matches_str("RD"));
+    /// assert!(pattern.matches_str("RD01"));
+    /// assert!(!pattern.matches_str("AA01"));
+    /// assert!(LevelType::from("00?1").matches_str("0011"));
+    /// ```
+    pub fn matches_str(&self, concrete: &str) -> bool {
+        self.as_level_ref().matches_str(concrete)
+    }
+
+    /// Borrow the LevelType as a LevelTypeRef
+    pub fn as_level_ref(&self) -> LevelTypeRef<'_> {
+        LevelTypeRef::from(self)
+    }
+
+    /// Returns true if the supplied concrete LevelType matches self, treating
+    /// self as the pattern. Only terms are concrete, so wildcards, patterns, and
+    /// relative levels are never matched; resolve relative levels via 
+    /// `LevelSpec::rel_to_abs` first. Otherwise, the semantics are those of
+    /// `matches_str`.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::LevelType;
+    /// 
+    /// assert!(LevelType::Wildcard.matches(&LevelType::from("RD")));
+    /// assert!(LevelType::from("R%").matches(&LevelType::from("RD")));
+    /// assert!(!LevelType::from("RD").matches(&LevelType::Wildcard));
+    /// assert!(!LevelType::Relative.matches(&LevelType::from("RD")));
+    /// ```
+    pub fn matches(&self, concrete: &LevelType) -> bool {
+        match concrete {
+            LevelType::Term(val) => self.matches_str(val),
+            _ => false,
+        }
+    }
+
+    /// Classify the LevelType. Case insensitive builds recognize ASSETDEV 
+    /// regardless of case.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{LevelType, LevelKind};
+    /// 
+    /// assert_eq!(LevelType::from("ASSETDEV").kind(), LevelKind::AssetDev);
+    /// assert_eq!(LevelType::from("RD").kind(), LevelKind::Term);
+    /// ```
+    pub fn kind(&self) -> LevelKind {
+        self.as_level_ref().kind()
+    }
+
+    /// Returns true if the LevelType is the ASSETDEV special term
+    pub fn is_assetdev(&self) -> bool {
+        self.kind() == LevelKind::AssetDev
+    }
+
+    // rank of the variant, used for ordering
+    fn rank(&self) -> u8 {
+        match self {
+            LevelType::Relative => 0,
+            LevelType::Wildcard => 1,
+            LevelType::Pattern(_) => 2,
+            LevelType::Term(_) => 3,
+        }
+    }
+
     /// Convert to a str
     pub fn to_str(&self) -> &str {
         match *self {
             LevelType::Term(ref val) => val,
+            LevelType::Pattern(ref val) => val,
             LevelType::Wildcard => "%",
             LevelType::Relative => "",
         }
     }
+
+    /// Consume the LevelType, converting it to a String in display form
+    pub fn into_string(self) -> String {
+        match self {
+            LevelType::Term(val) => val.into_owned(),
+            LevelType::Pattern(val) => val,
+            LevelType::Wildcard => "%".to_string(),
+            LevelType::Relative => String::new(),
+        }
+    }
 }
 
 impl From<&str> for LevelType {
@@ -51,17 +203,270 @@ impl From<&str> for LevelType {
         match input {
             "%" => LevelType::Wildcard,
             "" => LevelType::Relative,
-            _ =>  LevelType::Term(input.to_owned())
+            _ if is_pattern_str(input) => LevelType::Pattern(input.to_owned()),
+            _ =>  LevelType::Term(Cow::Owned(input.to_owned()))
+        }
+    }
+}
+
+impl From<String> for LevelType {
+    fn from(input: String) -> Self {
+        match input.as_str() {
+            "%" => LevelType::Wildcard,
+            "" => LevelType::Relative,
+            _ if is_pattern_str(&input) => LevelType::Pattern(input),
+            _ =>  LevelType::Term(Cow::Owned(input))
+        }
+    }
+}
+
+impl FromStr for LevelType {
+    type Err = LevelSpecterError;
+
+    /// Parse a single level, validating it. Unlike `From<&str>`, which accepts
+    /// anything, names may only contain alphanumeric characters and the pattern
+    /// characters `%` and `?`. An empty string is a relative level.
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        if let Some(c) = input.chars().find(|c| !(c.is_ascii_alphanumeric() || *c == '%' || *c == '?')) {
+            return Err(LevelSpecterError::ParseError(ParseFailure::Message(
+                format!("Unable to parse level '{}': invalid character '{}'", input, c)
+            )));
+        }
+        Ok(LevelType::from(input))
+    }
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for LevelType {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.to_str())
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for LevelType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let input = String::deserialize(deserializer)?;
+        LevelType::from_str(&input).map_err(serde::de::Error::custom)
+    }
+}
+
+impl Ord for LevelType {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match (self, other) {
+            (LevelType::Term(a), LevelType::Term(b)) => natural_cmp(a, b),
+            (LevelType::Pattern(a), LevelType::Pattern(b)) => a.cmp(b),
+            _ => self.rank().cmp(&other.rank()),
         }
     }
 }
 
+impl PartialOrd for LevelType {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl fmt::Display for LevelType {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
        match &self {
            &LevelType::Term(d) => write!(f, "{}", d),
+           &LevelType::Pattern(d) => write!(f, "{}", d),
            &LevelType::Wildcard => write!(f, "%"),
            &LevelType::Relative => write!(f, ""),
        }
     }
 }
+
+// compare strings, treating each run of digits as a number. Runs of equal
+// value, such as `01` and `1`, fall back to comparing the strings, so that
+// only equal strings compare equal.
+pub(crate) fn natural_cmp(a: &str, b: &str) -> Ordering {
+    let (mut x, mut y) = (a, b);
+    loop {
+        let (run_x, run_y) = (leading_run(x), leading_run(y));
+        if run_x.is_empty() || run_y.is_empty() {
+            return run_x.cmp(run_y).then_with(|| a.cmp(b));
+        }
+        let both_digits = run_x.as_bytes()[0].is_ascii_digit() && run_y.as_bytes()[0].is_ascii_digit();
+        let ordering = if both_digits {
+            let (num_x, num_y) = (run_x.trim_start_matches('0'), run_y.trim_start_matches('0'));
+            num_x.len().cmp(&num_y.len()).then_with(|| num_x.cmp(num_y))
+        } else {
+            run_x.cmp(run_y)
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+        x = &x[run_x.len()..];
+        y = &y[run_y.len()..];
+    }
+}
+
+// the leading run of digits, or of non digits
+fn leading_run(input: &str) -> &str {
+    let digits = input.starts_with(|c: char| c.is_ascii_digit());
+    let end = input.find(|c: char| c.is_ascii_digit() != digits).unwrap_or(input.len());
+    &input[..end]
+}
+
+// compare characters, ignoring ascii case in case-insensitive builds
+pub(crate) fn eq_char(a: char, b: char) -> bool {
+    if cfg!(feature = "case-insensitive") {a.eq_ignore_ascii_case(&b)} else {a == b}
+}
+
+pub(crate) fn eq_chars(a: &str, b: &str) -> bool {
+    if cfg!(feature = "case-insensitive") {a.eq_ignore_ascii_case(b)} else {a == b}
+}
+
+// match concrete against a pattern, where `%` matches any run of chars and
+// `?` matches a single char. Backtracks to the most recent `%` on mismatch.
+pub(crate) fn glob_matches(pattern: &[char], concrete: &[char]) -> bool {
+    let (mut p, mut c) = (0, 0);
+    let mut backtrack: Option<(usize, usize)> = None;
+    while c < concrete.len() {
+        if p < pattern.len() && pattern[p] == '%' {
+            backtrack = Some((p, c));
+            p += 1;
+        } else if p < pattern.len() && (pattern[p] == '?' || eq_char(pattern[p], concrete[c])) {
+            p += 1;
+            c += 1;
+        } else if let Some((bp, bc)) = backtrack {
+            p = bp + 1;
+            c = bc + 1;
+            backtrack = Some((bp, bc + 1));
+        } else {
+            return false;
+        }
+    }
+    pattern[p..].iter().all(|ch| *ch == '%')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_static_does_not_allocate_terms() {
+        let level = LevelType::from_static("DEV01");
+        assert!(matches!(level, LevelType::Term(Cow::Borrowed("DEV01"))));
+    }
+
+    #[test]
+    fn from_static_recognizes_wildcard_and_relative() {
+        assert_eq!(LevelType::from_static("%"), LevelType::Wildcard);
+        assert_eq!(LevelType::from_static(""), LevelType::Relative);
+    }
+
+    #[test]
+    fn orders_terms_naturally() {
+        assert!(LevelType::from("2") < LevelType::from("10"));
+        assert!(LevelType::from("RD2") < LevelType::from("RD10"));
+        assert!(LevelType::from("RD10") < LevelType::from("RE1"));
+        assert!(LevelType::from("01") < LevelType::from("1"));
+        assert!(LevelType::from("A1") < LevelType::from("AB"));
+        assert_eq!(LevelType::from("0010").cmp(&LevelType::from("0010")), Ordering::Equal);
+    }
+
+    #[test]
+    fn orders_relative_wildcard_term() {
+        let mut levels = vec![
+            LevelType::from("RD"),
+            LevelType::Wildcard,
+            LevelType::from("AA"),
+            LevelType::Relative,
+        ];
+        levels.sort();
+        assert_eq!(levels, vec![
+            LevelType::Relative,
+            LevelType::Wildcard,
+            LevelType::from("AA"),
+            LevelType::from("RD"),
+        ]);
+    }
+
+    #[test]
+    fn borrowed_and_owned_terms_hash_equally() {
+        use std::collections::HashSet;
+        let mut set = HashSet::new();
+        set.insert(LevelType::from_static("DEV01"));
+        assert!(set.contains(&LevelType::from("DEV01")));
+    }
+
+    #[test]
+    fn from_creates_patterns() {
+        assert_eq!(LevelType::from("RD%"), LevelType::Pattern("RD%".to_string()));
+        assert_eq!(LevelType::from("00?1"), LevelType::Pattern("00?1".to_string()));
+        assert_eq!(LevelType::from_static("%01"), LevelType::Pattern("%01".to_string()));
+        assert_eq!(LevelType::from("RD%").to_string(), "RD%");
+    }
+
+    #[test]
+    fn pattern_matches_embedded_wildcards() {
+        let pattern = LevelType::from("A%B%C");
+        assert!(pattern.matches_str("ABC"));
+        assert!(pattern.matches_str("AXXBYYC"));
+        assert!(!pattern.matches_str("AXXBYY"));
+        assert!(LevelType::from("%01").matches_str("DEV01"));
+        assert!(!LevelType::from("?01").matches_str("DEV01"));
+    }
+
+    #[test]
+    fn term_wildcard_and_relative_matching() {
+        assert!(LevelType::from("RD").matches_str("RD"));
+        assert!(!LevelType::from("RD").matches_str("RD01"));
+        assert!(LevelType::Wildcard.matches_str("RD"));
+        assert!(!LevelType::Relative.matches_str("RD"));
+    }
+
+    #[test]
+    fn only_terms_are_matched() {
+        for pattern in &[LevelType::Wildcard, LevelType::from("R%"), LevelType::from("RD")] {
+            assert!(pattern.matches(&LevelType::from("RD")));
+            assert!(!pattern.matches(&LevelType::Wildcard));
+            assert!(!pattern.matches(&LevelType::from("R%")));
+            assert!(!pattern.matches(&LevelType::Relative));
+        }
+        assert!(!LevelType::Relative.matches(&LevelType::from("RD")));
+    }
+
+    #[test]
+    fn from_str_validates_levels() {
+        assert_eq!(LevelType::from_str("RD"), Ok(LevelType::from("RD")));
+        assert_eq!(LevelType::from_str("R%"), Ok(LevelType::from("R%")));
+        assert_eq!(LevelType::from_str(""), Ok(LevelType::Relative));
+        assert!(LevelType::from_str("DEV01.RD").is_err());
+        assert!(LevelType::from_str("R D").is_err());
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn serializes_as_display_string() {
+        let levels = vec![LevelType::from("RD"), LevelType::Wildcard, LevelType::from("A%")];
+        let json = serde_json::to_string(&levels).unwrap();
+        assert_eq!(json, r#"["RD","%","A%"]"#);
+        assert_eq!(serde_json::from_str::<Vec<LevelType>>(&json).unwrap(), levels);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn deserialize_rejects_invalid_levels() {
+        assert!(serde_json::from_str::<LevelType>(r#""DEV01.RD""#).is_err());
+    }
+
+    #[test]
+    fn can_classify_levels() {
+        assert_eq!(LevelType::from("ASSETDEV").kind(), LevelKind::AssetDev);
+        assert_eq!(LevelType::from("ASSETDEV1").kind(), LevelKind::Term);
+        assert_eq!(LevelType::from("ASSET%").kind(), LevelKind::Pattern);
+        assert_eq!(LevelType::Wildcard.kind(), LevelKind::Wildcard);
+        assert_eq!(LevelType::Relative.kind(), LevelKind::Relative);
+        assert_eq!(LevelType::from("assetdev").is_assetdev(), cfg!(feature = "case-insensitive"));
+    }
+
+    #[test]
+    fn can_convert_from_string() {
+        assert_eq!(LevelType::from("RD".to_string()), LevelType::from("RD"));
+        assert_eq!(LevelType::from("%".to_string()), LevelType::Wildcard);
+    }
+}
@@ -0,0 +1,178 @@
+use std::fmt;
+use std::borrow::Cow;
+use crate::{LevelType, LevelKind, levelspec::ASSETDEV};
+use crate::leveltype::{eq_chars, glob_matches};
+
+/// Borrowed mirror of LevelType, whose Term and Pattern hold a `&str`
+/// rather than owning their data. Useful for parsing and matching 
+/// without allocating.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelType, LevelTypeRef};
+/// 
+/// let level = LevelType::from("RD");
+/// let level_ref = level.as_level_ref();
+/// assert_eq!(level_ref, LevelTypeRef::Term("RD"));
+/// assert_eq!(level_ref.to_level_type(), level);
+/// ```
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Hash)]
+pub enum LevelTypeRef<'a> {
+    Relative,
+    Wildcard,
+    Pattern(&'a str),
+    Term(&'a str),
+}
+
+impl<'a> LevelTypeRef<'a> {
+    pub fn is_wildcard(&self) -> bool {
+        *self == LevelTypeRef::Wildcard
+    }
+
+    pub fn is_relative(&self) -> bool {
+        *self == LevelTypeRef::Relative
+    }
+
+    pub fn is_term(&self) -> bool {
+        matches!(self, LevelTypeRef::Term(_))
+    }
+
+    pub fn is_pattern(&self) -> bool {
+        matches!(self, LevelTypeRef::Pattern(_))
+    }
+
+    /// Classify the LevelTypeRef. See `LevelType::kind`
+    pub fn kind(&self) -> LevelKind {
+        match self {
+            LevelTypeRef::Term(val) if eq_chars(val, ASSETDEV) => LevelKind::AssetDev,
+            LevelTypeRef::Term(_) => LevelKind::Term,
+            LevelTypeRef::Pattern(_) => LevelKind::Pattern,
+            LevelTypeRef::Wildcard => LevelKind::Wildcard,
+            LevelTypeRef::Relative => LevelKind::Relative,
+        }
+    }
+
+    /// Returns true if the supplied concrete name matches the LevelTypeRef.
+    /// See `LevelType::matches_str`
+    pub fn matches_str(&self, concrete: &str) -> bool {
+        match self {
+            LevelTypeRef::Term(val) => eq_chars(val, concrete),
+            LevelTypeRef::Pattern(pattern) => {
+                let pattern = pattern.chars().collect::<Vec<_>>();
+                let concrete = concrete.chars().collect::<Vec<_>>();
+                glob_matches(&pattern, &concrete)
+            },
+            LevelTypeRef::Wildcard => !concrete.is_empty(),
+            LevelTypeRef::Relative => false,
+        }
+    }
+
+    /// Returns true if the supplied concrete LevelTypeRef matches self.
+    /// See `LevelType::matches`
+    pub fn matches(&self, concrete: &LevelTypeRef) -> bool {
+        match concrete {
+            LevelTypeRef::Term(val) => self.matches_str(val),
+            _ => false,
+        }
+    }
+
+    /// Convert to a str, with the lifetime of the borrowed data
+    pub fn to_str(&self) -> &'a str {
+        match *self {
+            LevelTypeRef::Term(val) => val,
+            LevelTypeRef::Pattern(val) => val,
+            LevelTypeRef::Wildcard => "%",
+            LevelTypeRef::Relative => "",
+        }
+    }
+
+    /// Convert to an owned LevelType, allocating for terms and patterns
+    pub fn to_level_type(&self) -> LevelType {
+        match *self {
+            LevelTypeRef::Term(val) => LevelType::Term(Cow::Owned(val.to_string())),
+            LevelTypeRef::Pattern(val) => LevelType::Pattern(val.to_string()),
+            LevelTypeRef::Wildcard => LevelType::Wildcard,
+            LevelTypeRef::Relative => LevelType::Relative,
+        }
+    }
+}
+
+impl<'a> From<&'a str> for LevelTypeRef<'a> {
+    fn from(input: &'a str) -> Self {
+        match input {
+            "%" => LevelTypeRef::Wildcard,
+            "" => LevelTypeRef::Relative,
+            _ if input.contains(&['%', '?'][..]) => LevelTypeRef::Pattern(input),
+            _ => LevelTypeRef::Term(input),
+        }
+    }
+}
+
+impl<'a> From<&'a LevelType> for LevelTypeRef<'a> {
+    fn from(input: &'a LevelType) -> Self {
+        match input {
+            LevelType::Term(val) => LevelTypeRef::Term(val),
+            LevelType::Pattern(val) => LevelTypeRef::Pattern(val),
+            LevelType::Wildcard => LevelTypeRef::Wildcard,
+            LevelType::Relative => LevelTypeRef::Relative,
+        }
+    }
+}
+
+impl<'a> From<LevelTypeRef<'a>> for LevelType {
+    fn from(input: LevelTypeRef<'a>) -> Self {
+        input.to_level_type()
+    }
+}
+
+impl<'a> PartialEq<LevelType> for LevelTypeRef<'a> {
+    fn eq(&self, other: &LevelType) -> bool {
+        *self == LevelTypeRef::from(other)
+    }
+}
+
+impl<'a> PartialEq<LevelTypeRef<'a>> for LevelType {
+    fn eq(&self, other: &LevelTypeRef<'a>) -> bool {
+        LevelTypeRef::from(self) == *other
+    }
+}
+
+impl<'a> fmt::Display for LevelTypeRef<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.to_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str_mirrors_level_type() {
+        for input in &["DEV01", "RD%", "%", ""] {
+            assert_eq!(LevelTypeRef::from(*input), LevelType::from(*input));
+        }
+    }
+
+    #[test]
+    fn round_trips_through_level_type() {
+        let level = LevelType::from("R?");
+        assert_eq!(LevelType::from(level.as_level_ref()), level);
+    }
+
+    #[test]
+    fn orders_like_level_type() {
+        let mut refs = vec![LevelTypeRef::Term("RD"), LevelTypeRef::Pattern("R%"), LevelTypeRef::Wildcard, LevelTypeRef::Relative];
+        let mut owned = refs.iter().map(LevelTypeRef::to_level_type).collect::<Vec<_>>();
+        refs.sort();
+        owned.sort();
+        assert_eq!(refs.iter().map(LevelTypeRef::to_level_type).collect::<Vec<_>>(), owned);
+    }
+
+    #[test]
+    fn matches_without_owning() {
+        assert!(LevelTypeRef::from("R%").matches(&LevelTypeRef::from("RD")));
+        assert!(!LevelTypeRef::from("R%").matches(&LevelTypeRef::Wildcard));
+    }
+}
@@ -1,21 +1,176 @@
  
 pub mod levelparser;
-pub use levelparser::{levelspec_parser, LevelTypeVec};
+pub use levelparser::{levelspec_parser, levelspec_ref_parser, is_valid, LevelTypeVec};
 
 pub mod leveltype;
-pub use leveltype::LevelType;
+pub use leveltype::{LevelType, LevelKind};
+
+pub mod leveltyperef;
+pub use leveltyperef::LevelTypeRef;
 
 pub mod levelspec;
-pub use levelspec::{LevelSpec, LevelName};
+pub use levelspec::{LevelSpec, LevelName, SequenceKind, SeparatedDisplay, LevelSpecComponents};
+
+pub mod levelspecref;
+pub use levelspecref::LevelSpecRef;
+
+pub mod pathtemplate;
+pub use pathtemplate::{PathTemplate, PathSchema};
+
+pub mod jobroot;
+pub use jobroot::JobRoot;
+
+pub mod reserved;
+pub use reserved::ReservedSequences;
+
+pub mod envvars;
+pub use envvars::EnvVars;
+
+pub mod formatoptions;
+pub use formatoptions::{FormatOptions, Case};
+
+pub mod formattemplate;
+pub use formattemplate::FormatTemplate;
+
+pub mod levelcontext;
+pub use levelcontext::LevelContext;
+
+pub mod aliases;
+pub use aliases::AliasTable;
+
+pub mod showregistry;
+pub use showregistry::{ShowRegistry, LongDisplay};
+
+pub mod normalize;
+pub use normalize::{NormalizeRules, NormalizeChange, NormalizeAction};
+
+pub mod schema;
+pub use schema::{LevelSchema, LevelRule, Charset, ValidationError};
+
+pub mod leveltree;
+pub use leveltree::LevelTree;
+
+pub mod shotindex;
+pub use shotindex::ShotIndex;
+
+pub mod resolver;
+pub use resolver::{LevelResolver, EnvResolver, MapResolver};
+#[cfg(feature = "async")]
+pub use resolver::AsyncLevelResolver;
+
+#[cfg(feature = "watch")]
+pub mod watcher;
+#[cfg(feature = "watch")]
+pub use watcher::{LiveShotIndex, IndexEvent};
+
+pub mod levelspecset;
+pub use levelspecset::{LevelSpecSet, ExportOptions};
+
+pub mod levelspecmap;
+pub use levelspecmap::LevelSpecMap;
+
+pub mod query;
+pub use query::Query;
+
+pub mod matcherset;
+pub use matcherset::MatcherSet;
+
+pub mod shotrange;
+pub use shotrange::ShotRange;
+
+pub mod diff;
+pub use diff::{diff_lists, LevelSpecDiff};
+
+pub mod naturalord;
+pub use naturalord::{NaturalOrd, AssetDevPosition, sort_specs, sort_specs_by};
+
+pub mod rules;
+pub use rules::{Rules, Violation};
+
+pub mod interner;
+pub use interner::{Interner, InternedLevelSpec};
+
+pub mod bulk;
+pub use bulk::{LineError, parse_many};
+#[cfg(feature = "rayon")]
+pub use bulk::parse_many_parallel;
+#[cfg(feature = "async")]
+pub use bulk::parse_stream;
+
+pub mod conflicts;
+pub use conflicts::{find_conflicts, Conflict, ConflictKind};
+
+pub mod fuzzy;
+pub use fuzzy::closest_match;
+
+pub mod parseoptions;
+pub use parseoptions::{ParseOptions, Strictness, is_valid_with};
+
+pub mod parselimits;
+pub use parselimits::ParseLimits;
+
+pub mod diagnostic;
+pub use diagnostic::{diagnose, ParseDiagnostic};
+
+pub mod binary;
+pub use binary::{BINARY_VERSION, BINARY_MAGIC, write_snapshot, read_snapshot};
+
+pub mod uri;
+pub use uri::URI_SCHEME;
+
+pub mod grammar;
+pub use grammar::levelspec_regex;
+#[cfg(feature = "json")]
+pub use grammar::levelspec_json_schema;
+
+#[cfg(feature = "config")]
+pub mod config;
+
+#[cfg(feature = "diesel")]
+pub mod sqlmatch;
+#[cfg(feature = "diesel")]
+pub use sqlmatch::matches_spec;
+
+#[cfg(feature = "ffi")]
+pub mod ffi;
 
 pub mod errors;
-pub use errors::LevelSpecterError;
+pub use errors::{LevelSpecterError, ParseFailure};
 
 pub mod prelude {
     pub use super::LevelSpecterError;
     pub use super::levelparser::levelspec_parser;
-    pub use super::LevelType;
+    pub use super::{LevelType, LevelKind};
+    pub use super::LevelTypeRef;
     pub use super::LevelSpec;
+    pub use super::LevelSpecRef;
     pub use super::LevelName;
+    pub use super::SequenceKind;
+    pub use super::{PathTemplate, PathSchema};
+    pub use super::EnvVars;
+    pub use super::JobRoot;
+    pub use super::ReservedSequences;
+    pub use super::LevelContext;
+    pub use super::{NormalizeRules, AliasTable};
+    pub use super::ShowRegistry;
+    pub use super::LevelSchema;
+    pub use super::LevelTree;
+    pub use super::ShotIndex;
+    pub use super::{LevelResolver, EnvResolver, MapResolver};
+    pub use super::LevelSpecMap;
+    pub use super::LevelSpecSet;
+    pub use super::Query;
+    pub use super::MatcherSet;
+    pub use super::ShotRange;
+    pub use super::{diff_lists, LevelSpecDiff};
+    pub use super::{NaturalOrd, sort_specs};
+    pub use super::Rules;
+    pub use super::{ParseOptions, Strictness};
+    pub use super::{diagnose, ParseDiagnostic};
+    pub use super::Interner;
+    pub use super::closest_match;
+    pub use super::find_conflicts;
+    pub use super::{FormatOptions, Case};
+    pub use super::FormatTemplate;
     pub use std::str::FromStr;
 }
\ No newline at end of file
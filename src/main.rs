@@ -1,14 +1,27 @@
-use levelspecter::{LevelSpec, LevelSpecterError};
-use std::env;
+use clap::{CommandFactory, FromArgMatches};
 
-fn main() -> Result<(), LevelSpecterError> {
-    let args = env::args();
-    if args.len() < 2 {
-        eprintln!("levelspecter <levelspec>");
-        std::process::exit(1);
-    }
-    let args = args.collect::<Vec<_>>();
-    let levelspec = LevelSpec::new(&args[1])?;
-    println!("{:?}", levelspec);
-    Ok(())
+mod cli;
+use cli::exitcode;
+
+fn main() {
+    let parsed = cli::Cli::command()
+        .try_get_matches()
+        .and_then(|matches| cli::Cli::from_arg_matches(&matches).map(|cli| (cli, matches)));
+    let (cli, matches) = match parsed {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            // help and version requests are printed to stdout and succeed
+            let code = if e.use_stderr() { exitcode::USAGE } else { exitcode::SUCCESS };
+            let _ = e.print();
+            std::process::exit(code);
+        }
+    };
+    let cli = match cli::configure(cli, &matches) {
+        Ok(cli) => cli,
+        Err(e) => {
+            eprintln!("{}", e);
+            std::process::exit(exitcode::for_error(&e));
+        }
+    };
+    std::process::exit(cli::run(cli));
 }
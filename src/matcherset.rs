@@ -0,0 +1,203 @@
+use crate::{LevelSpec, LevelType};
+use std::collections::HashMap;
+
+// A node in the compiled trie. Terms are indexed by key for constant time
+// lookup, while wildcards and patterns are branches tried for every input.
+#[derive(Debug, Clone, Default)]
+struct MatcherNode {
+    terms: HashMap<String, MatcherNode>,
+    wildcard: Option<Box<MatcherNode>>,
+    patterns: Vec<(LevelType, MatcherNode)>,
+    // indices of the patterns ending at this node
+    accepts: Vec<usize>,
+}
+
+impl MatcherNode {
+    fn child(&mut self, level: &LevelType) -> &mut MatcherNode {
+        match level {
+            LevelType::Term(term) => self.terms.entry(term_key(term)).or_default(),
+            LevelType::Wildcard => self.wildcard.get_or_insert_with(Default::default),
+            _ => {
+                let idx = match self.patterns.iter().position(|(pattern, _)| pattern == level) {
+                    Some(idx) => idx,
+                    None => {
+                        self.patterns.push((level.clone(), MatcherNode::default()));
+                        self.patterns.len() - 1
+                    }
+                };
+                &mut self.patterns[idx].1
+            }
+        }
+    }
+
+    // collect the patterns matching the remaining levels
+    fn collect(&self, levels: &[&str], matches: &mut Vec<usize>) {
+        let (level, rest) = match levels.split_first() {
+            Some(split) => split,
+            None => {
+                matches.extend(&self.accepts);
+                return;
+            }
+        };
+        if let Some(node) = self.terms.get(&term_key(level)) {
+            node.collect(rest, matches);
+        }
+        if let Some(ref node) = self.wildcard {
+            node.collect(rest, matches);
+        }
+        for (pattern, node) in &self.patterns {
+            if pattern.matches_str(level) {
+                node.collect(rest, matches);
+            }
+        }
+    }
+}
+
+// key used to index terms, folding case in case-insensitive builds
+fn term_key(term: &str) -> String {
+    if cfg!(feature = "case-insensitive") {
+        term.to_ascii_uppercase()
+    } else {
+        term.to_string()
+    }
+}
+
+/// A set of LevelSpec patterns compiled into a trie, answering which of the
+/// patterns match a concrete LevelSpec. Matching walks the trie once, rather
+/// than testing each pattern in turn, so the cost depends on the number of
+/// wildcard and pattern branches rather than the number of patterns.
+/// 
+/// Matching follows `LevelSpec::matches`: patterns only match LevelSpecs
+/// of the same depth, and patterns with relative levels never match.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, MatcherSet};
+/// 
+/// let set = MatcherSet::new(vec![
+///     LevelSpec::new("DEV01.%.%").unwrap(),
+///     LevelSpec::new("DEV01.RD.%").unwrap(),
+///     LevelSpec::new("DEV02.%.%").unwrap(),
+/// ]);
+/// assert_eq!(set.matches(&LevelSpec::from_shot("DEV01", "RD", "0001")), vec![0, 1]);
+/// assert!(!set.is_match(&LevelSpec::from_shot("DEV03", "RD", "0001")));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct MatcherSet {
+    root: MatcherNode,
+    patterns: Vec<LevelSpec>,
+}
+
+impl MatcherSet {
+    /// New up a MatcherSet from the supplied patterns. Patterns are 
+    /// identified by their index in the input.
+    pub fn new<I>(patterns: I) -> Self 
+    where
+        I: IntoIterator<Item = LevelSpec>
+    {
+        let mut set = Self::default();
+        for pattern in patterns {
+            set.add(pattern);
+        }
+        set
+    }
+
+    /// Add a pattern to the set
+    /// 
+    /// # Returns
+    /// The index identifying the pattern
+    pub fn add(&mut self, pattern: LevelSpec) -> usize {
+        let idx = self.patterns.len();
+        let levels = pattern.to_vec_str();
+        if !levels.iter().any(|level| level.is_relative()) {
+            let mut node = &mut self.root;
+            for level in levels {
+                node = node.child(level);
+            }
+            node.accepts.push(idx);
+        }
+        self.patterns.push(pattern);
+        idx
+    }
+
+    /// Retrieve the indices of the patterns matching the supplied concrete 
+    /// LevelSpec, in ascending order
+    pub fn matches(&self, levelspec: &LevelSpec) -> Vec<usize> {
+        let levels = levelspec.to_vec_str();
+        if !levels.iter().all(|level| level.is_term()) {
+            return Vec::new();
+        }
+        let levels = levels.iter().map(|level| level.to_str()).collect::<Vec<_>>();
+        let mut matches = Vec::new();
+        self.root.collect(&levels, &mut matches);
+        matches.sort_unstable();
+        matches
+    }
+
+    /// Retrieve the patterns matching the supplied concrete LevelSpec
+    pub fn matching_patterns(&self, levelspec: &LevelSpec) -> Vec<&LevelSpec> {
+        self.matches(levelspec).into_iter().map(|idx| &self.patterns[idx]).collect()
+    }
+
+    /// Returns true if any pattern matches the supplied concrete LevelSpec
+    pub fn is_match(&self, levelspec: &LevelSpec) -> bool {
+        !self.matches(levelspec).is_empty()
+    }
+
+    /// Retrieve the pattern with the supplied index
+    pub fn pattern(&self, idx: usize) -> Option<&LevelSpec> {
+        self.patterns.get(idx)
+    }
+
+    /// Retrieve the number of patterns in the set
+    pub fn len(&self) -> usize {
+        self.patterns.len()
+    }
+
+    /// Returns true if the set has no patterns
+    pub fn is_empty(&self) -> bool {
+        self.patterns.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn ls(spec: &str) -> LevelSpec {
+        LevelSpec::from_str(spec).unwrap()
+    }
+
+    #[test]
+    fn agrees_with_levelspec_matches() {
+        let patterns = vec![
+            ls("DEV01.%.%"), ls("%.RD.%"), ls("%.%.0001"), ls("DEV01.RD.0001"), ls("DEV01.%"),
+            ls("DEV01"), ls("..0001"), LevelSpec::from_shot("DEV01", "R%", "00?1"), ls("%.%.%"),
+        ];
+        let set = MatcherSet::new(patterns.clone());
+        for concrete in &["DEV01.RD.0001", "DEV01.RD.0011", "DEV01.AA.0002", "DEV02.RD.0001", "DEV01.RD", "DEV01"] {
+            let concrete = ls(concrete);
+            let expected = patterns.iter().enumerate()
+                .filter(|(_, pattern)| pattern.matches(&concrete))
+                .map(|(idx, _)| idx)
+                .collect::<Vec<_>>();
+            assert_eq!(set.matches(&concrete), expected, "matching {}", concrete);
+        }
+    }
+
+    #[test]
+    fn duplicate_patterns_are_reported_separately() {
+        let set = MatcherSet::new(vec![ls("DEV01.%"), ls("DEV01.%")]);
+        assert_eq!(set.matches(&ls("DEV01.RD")), vec![0, 1]);
+        assert_eq!(set.matching_patterns(&ls("DEV01.RD")), vec![&ls("DEV01.%"), &ls("DEV01.%")]);
+    }
+
+    #[test]
+    fn non_concrete_input_matches_nothing() {
+        let set = MatcherSet::new(vec![ls("%.%.%")]);
+        assert!(!set.is_match(&ls("DEV01.%.0001")));
+        assert!(set.is_match(&ls("DEV01.RD.0001")));
+    }
+}
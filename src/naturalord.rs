@@ -0,0 +1,158 @@
+use crate::{LevelSpec, LevelType};
+use std::cmp::Ordering;
+
+/// Where the ASSETDEV sequence sorts relative to other sequences
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum AssetDevPosition {
+    /// Before all other sequences
+    First,
+    /// Alphabetically, like any other sequence
+    Alphabetical,
+    /// After all other sequences
+    Last,
+}
+
+/// Comparator ordering LevelSpecs the way artists expect: shows and sequences
+/// alphabetically, and shots numerically, so that `0002` sorts before `0010`.
+/// Shorter LevelSpecs sort before their children, and numeric shots sort 
+/// before non-numeric (eg ASSETDEV) shots. Wildcard and relative levels sort
+/// before terms, as in the Ord implementation of LevelType.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, NaturalOrd, AssetDevPosition};
+/// 
+/// let mut specs = vec![
+///     LevelSpec::from_shot("DEV01", "RD", "10"),
+///     LevelSpec::from_sequence("DEV01", "ASSETDEV"),
+///     LevelSpec::from_shot("DEV01", "RD", "2"),
+/// ];
+/// specs.sort_by(|a, b| NaturalOrd::new().with_assetdev(AssetDevPosition::Last).compare(a, b));
+/// assert_eq!(specs, vec![
+///     LevelSpec::from_shot("DEV01", "RD", "2"),
+///     LevelSpec::from_shot("DEV01", "RD", "10"),
+///     LevelSpec::from_sequence("DEV01", "ASSETDEV"),
+/// ]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct NaturalOrd {
+    pub assetdev: AssetDevPosition,
+}
+
+impl NaturalOrd {
+    /// New up a NaturalOrd which sorts ASSETDEV first
+    pub fn new() -> Self {
+        Self { assetdev: AssetDevPosition::First }
+    }
+
+    /// Set the position of the ASSETDEV sequence and return self
+    pub fn with_assetdev(mut self, assetdev: AssetDevPosition) -> Self {
+        self.assetdev = assetdev;
+        self
+    }
+
+    /// Compare two LevelSpecs
+    pub fn compare(&self, a: &LevelSpec, b: &LevelSpec) -> Ordering {
+        a.show.cmp(&b.show)
+            .then_with(|| compare_option(&a.sequence, &b.sequence, |x, y| self.compare_sequence(x, y)))
+            .then_with(|| compare_option(&a.shot, &b.shot, compare_shot))
+    }
+
+    fn compare_sequence(&self, a: &LevelType, b: &LevelType) -> Ordering {
+        let rank = |level: &LevelType| match self.assetdev {
+            AssetDevPosition::First if level.is_assetdev() => 0,
+            AssetDevPosition::Last if level.is_assetdev() => 2,
+            _ => 1,
+        };
+        rank(a).cmp(&rank(b)).then_with(|| a.cmp(b))
+    }
+}
+
+impl Default for NaturalOrd {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// missing levels sort before present ones
+fn compare_option<F>(a: &Option<LevelType>, b: &Option<LevelType>, compare: F) -> Ordering
+where
+    F: Fn(&LevelType, &LevelType) -> Ordering
+{
+    match (a, b) {
+        (Some(a), Some(b)) => compare(a, b),
+        _ => a.is_some().cmp(&b.is_some()),
+    }
+}
+
+// numeric shots sort by value, ahead of non-numeric shots
+fn compare_shot(a: &LevelType, b: &LevelType) -> Ordering {
+    let number = |level: &LevelType| match level {
+        LevelType::Term(val) => val.parse::<u64>().ok(),
+        _ => None,
+    };
+    match (number(a), number(b)) {
+        (Some(x), Some(y)) => x.cmp(&y).then_with(|| a.cmp(b)),
+        (Some(_), None) if b.is_term() => Ordering::Less,
+        (None, Some(_)) if a.is_term() => Ordering::Greater,
+        _ => a.cmp(b),
+    }
+}
+
+/// Sort LevelSpecs in place using the default NaturalOrd
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, sort_specs};
+/// 
+/// let mut specs = vec![LevelSpec::from_shot("DEV01", "RD", "0010"), LevelSpec::from_shot("DEV01", "RD", "0002")];
+/// sort_specs(&mut specs);
+/// assert_eq!(specs[0], LevelSpec::from_shot("DEV01", "RD", "0002"));
+/// ```
+pub fn sort_specs(specs: &mut [LevelSpec]) {
+    sort_specs_by(specs, &NaturalOrd::default())
+}
+
+/// Sort LevelSpecs in place using the supplied NaturalOrd
+pub fn sort_specs_by(specs: &mut [LevelSpec], order: &NaturalOrd) {
+    specs.sort_by(|a, b| order.compare(a, b))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    fn specs(input: &[&str]) -> Vec<LevelSpec> {
+        input.iter().map(|spec| LevelSpec::from_str(spec).unwrap()).collect()
+    }
+
+    #[test]
+    fn sorts_shots_numerically() {
+        let mut list = specs(&["DEV01.RD.10", "DEV01.RD.0002", "DEV01.RD", "DEV01.AA.0100", "DEV01"]);
+        sort_specs(&mut list);
+        assert_eq!(list, specs(&["DEV01", "DEV01.AA.0100", "DEV01.RD", "DEV01.RD.0002", "DEV01.RD.10"]));
+    }
+
+    #[test]
+    fn assetdev_position_is_configurable() {
+        let input = specs(&["DEV01.ZZ", "DEV01.ASSETDEV", "DEV01.AA"]);
+        let sorted = |position| {
+            let mut list = input.clone();
+            sort_specs_by(&mut list, &NaturalOrd::new().with_assetdev(position));
+            list
+        };
+        assert_eq!(sorted(AssetDevPosition::First), specs(&["DEV01.ASSETDEV", "DEV01.AA", "DEV01.ZZ"]));
+        assert_eq!(sorted(AssetDevPosition::Alphabetical), specs(&["DEV01.AA", "DEV01.ASSETDEV", "DEV01.ZZ"]));
+        assert_eq!(sorted(AssetDevPosition::Last), specs(&["DEV01.AA", "DEV01.ZZ", "DEV01.ASSETDEV"]));
+    }
+
+    #[test]
+    fn numeric_shots_precede_named_shots() {
+        let mut list = specs(&["DEV01.ASSETDEV.FOO", "DEV01.ASSETDEV.%", "DEV01.ASSETDEV.0001"]);
+        sort_specs(&mut list);
+        assert_eq!(list, specs(&["DEV01.ASSETDEV.%", "DEV01.ASSETDEV.0001", "DEV01.ASSETDEV.FOO"]));
+    }
+}
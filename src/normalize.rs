@@ -0,0 +1,224 @@
+use crate::{LevelSpec, LevelName, LevelType, Case, AliasTable, aliases, levelspec::ASSETDEV};
+
+/// The kind of change made to a level during normalization
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum NormalizeAction {
+    /// An alias was replaced by its canonical name
+    Alias,
+    /// A keyword was replaced by its canonical spelling
+    Keyword,
+    /// The case of a term was converted
+    Case,
+    /// A numeric shot was zero padded
+    Padding,
+}
+
+/// A single change made to a level during normalization
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NormalizeChange {
+    pub level: LevelName,
+    pub action: NormalizeAction,
+    pub from: String,
+    pub to: String,
+}
+
+/// Site rules applied by `LevelSpec::normalize`. Rules are applied to each
+/// term in the following order: alias substitution, keyword canonicalization,
+/// case conversion, and shot padding. Keywords keep their canonical spelling
+/// regardless of the case conversion.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, NormalizeRules, Case};
+///
+/// let rules = NormalizeRules::default()
+///     .with_case(Case::Upper)
+///     .with_shot_padding(4)
+///     .with_sequence_alias("RND", "RD");
+/// let ls = LevelSpec::from_shot("DEV01", "RND", "1");
+/// let (normalized, changes) = ls.normalize(&rules);
+/// assert_eq!(normalized, LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// assert_eq!(changes.len(), 2);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct NormalizeRules {
+    /// Case conversion applied to terms
+    pub case: Case,
+    /// Minimum width numeric shots are zero padded to
+    pub shot_padding: Option<usize>,
+    /// Show and sequence aliases, replaced by their canonical names
+    pub aliases: AliasTable,
+    /// Canonical spellings of keywords, matched case insensitively
+    pub keywords: Vec<String>,
+}
+
+impl NormalizeRules {
+    /// Set the case conversion and return self
+    pub fn with_case(mut self, case: Case) -> Self {
+        self.case = case;
+        self
+    }
+
+    /// Set the minimum width of numeric shots and return self
+    pub fn with_shot_padding(mut self, padding: usize) -> Self {
+        self.shot_padding = Some(padding);
+        self
+    }
+
+    /// Set the alias table and return self
+    pub fn with_aliases(mut self, aliases: AliasTable) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Add a show alias and return self
+    pub fn with_show_alias<I>(mut self, alias: I, canonical: I) -> Self
+    where
+        I: Into<String>
+    {
+        aliases::insert(&mut self.aliases.shows, alias.into(), canonical.into());
+        self
+    }
+
+    /// Add a sequence alias and return self
+    pub fn with_sequence_alias<I>(mut self, alias: I, canonical: I) -> Self
+    where
+        I: Into<String>
+    {
+        aliases::insert(&mut self.aliases.sequences, alias.into(), canonical.into());
+        self
+    }
+
+    /// Add a keyword and return self
+    pub fn with_keyword<I>(mut self, keyword: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.keywords.push(keyword.into());
+        self
+    }
+
+    /// Apply the rules to the supplied LevelSpec.
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to normalize
+    ///
+    /// # Returns
+    /// A tuple of the normalized LevelSpec and the changes made, in order
+    pub fn apply(&self, levelspec: &LevelSpec) -> (LevelSpec, Vec<NormalizeChange>) {
+        let mut changes = Vec::new();
+        let mut return_value = levelspec.clone();
+
+        self.normalize_level(LevelName::Show, &mut return_value.show, &mut changes);
+        if let Some(ref mut sequence) = return_value.sequence {
+            self.normalize_level(LevelName::Sequence, sequence, &mut changes);
+        }
+        if let Some(ref mut shot) = return_value.shot {
+            self.normalize_level(LevelName::Shot, shot, &mut changes);
+        }
+        (return_value, changes)
+    }
+
+    // normalize a single level in place, recording each change
+    fn normalize_level(&self, level: LevelName, level_type: &mut LevelType, changes: &mut Vec<NormalizeChange>) {
+        let term = match level_type {
+            LevelType::Term(ref mut term) => term.to_mut(),
+            _ => return,
+        };
+        let mut record = |action: NormalizeAction, term: &mut String, to: String| {
+            if *term != to {
+                changes.push(NormalizeChange { level, action, from: term.clone(), to: to.clone() });
+                *term = to;
+            }
+        };
+
+        let canonical = match level {
+            LevelName::Show => self.aliases.show(term),
+            LevelName::Sequence => self.aliases.sequence(term),
+            LevelName::Shot => None,
+        };
+        if let Some(canonical) = canonical {
+            record(NormalizeAction::Alias, term, canonical.to_string());
+        }
+
+        if let Some(keyword) = self.keywords.iter().find(|kw| kw.eq_ignore_ascii_case(term)) {
+            record(NormalizeAction::Keyword, term, keyword.clone());
+            return;
+        }
+
+        let converted = self.case.apply(term);
+        record(NormalizeAction::Case, term, converted);
+
+        if let Some(padding) = self.shot_padding {
+            if level == LevelName::Shot && !term.is_empty() && term.chars().all(|c| c.is_ascii_digit()) {
+                let padded = format!("{:0>width$}", term, width = padding);
+                record(NormalizeAction::Padding, term, padded);
+            }
+        }
+    }
+}
+
+impl Default for NormalizeRules {
+    fn default() -> Self {
+        Self {
+            case: Case::Preserve,
+            shot_padding: None,
+            aliases: AliasTable::default(),
+            keywords: vec![ASSETDEV.to_string()],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn default_rules_make_no_changes() {
+        let ls = LevelSpec::from_shot("DEV01", "RD", "1");
+        assert_eq!(ls.normalize(&NormalizeRules::default()), (ls, Vec::new()));
+    }
+
+    #[test]
+    fn reports_each_change() {
+        let rules = NormalizeRules::default()
+            .with_case(Case::Upper)
+            .with_shot_padding(4)
+            .with_show_alias("DEVTEST", "DEV01");
+        let ls = LevelSpec {
+            show: LevelType::from("devtest"),
+            sequence: Some(LevelType::from("rd")),
+            shot: Some(LevelType::from("10"))
+        };
+        let (normalized, changes) = ls.normalize(&rules);
+        assert_eq!(normalized, LevelSpec::from_shot("DEV01", "RD", "0010"));
+        assert_eq!(changes, vec![
+            NormalizeChange { level: LevelName::Show, action: NormalizeAction::Alias, from: "devtest".to_string(), to: "DEV01".to_string() },
+            NormalizeChange { level: LevelName::Sequence, action: NormalizeAction::Case, from: "rd".to_string(), to: "RD".to_string() },
+            NormalizeChange { level: LevelName::Shot, action: NormalizeAction::Padding, from: "10".to_string(), to: "0010".to_string() },
+        ]);
+    }
+
+    #[test]
+    fn keywords_keep_canonical_spelling() {
+        let rules = NormalizeRules::default().with_case(Case::Lower);
+        let ls = LevelSpec {
+            show: LevelType::from("DEV01"),
+            sequence: Some(LevelType::from("assetDev")),
+            shot: None
+        };
+        let (normalized, _) = ls.normalize(&rules);
+        assert_eq!(normalized.sequence(), Some(&LevelType::from("ASSETDEV")));
+        assert_eq!(normalized.show(), &LevelType::from("dev01"));
+    }
+
+    #[test]
+    fn leaves_wildcards_and_relative_levels_alone() {
+        let rules = NormalizeRules::default().with_case(Case::Lower).with_shot_padding(4);
+        let ls = LevelSpec::from_str(".%.%").unwrap();
+        assert_eq!(ls.normalize(&rules), (ls, Vec::new()));
+    }
+}
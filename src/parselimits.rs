@@ -0,0 +1,119 @@
+use crate::{LevelSpec, LevelSpecterError as LSE, ParseFailure, levelspec_ref_parser};
+
+/// Longest input accepted by the default ParseLimits, in bytes
+pub const DEFAULT_MAX_LEN: usize = 256;
+
+/// Bounds on untrusted input, such as a levelspec taken from a web request.
+/// Input beyond the bounds is rejected before it is parsed, so the work done
+/// for any input is bounded, and parsing never panics. The default limits
+/// accept input of up to `DEFAULT_MAX_LEN` bytes and three levels.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, ParseLimits, LevelSpecterError};
+///
+/// let limits = ParseLimits::default().with_max_levels(2);
+/// assert_eq!(LevelSpec::parse_with_limits("DEV01.RD", &limits), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+/// assert_eq!(LevelSpec::parse_with_limits("DEV01.RD.0001", &limits), Err(LevelSpecterError::TooManyLevels(3)));
+/// assert!(LevelSpec::parse_with_limits(&"A".repeat(1000), &limits).is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct ParseLimits {
+    /// Longest input, in bytes, which is parsed
+    pub max_len: usize,
+    /// Most levels the input may have. Values above three have no effect, as
+    /// no levelspec is deeper than a shot.
+    pub max_levels: usize,
+}
+
+impl ParseLimits {
+    /// Set the longest input, in bytes, which is parsed and return self
+    pub fn with_max_len(mut self, max_len: usize) -> Self {
+        self.max_len = max_len;
+        self
+    }
+
+    /// Set the most levels the input may have and return self
+    pub fn with_max_levels(mut self, max_levels: usize) -> Self {
+        self.max_levels = max_levels;
+        self
+    }
+
+    /// Parse the input into a LevelSpec, enforcing the limits. Any input may
+    /// be supplied; none causes a panic.
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The untrusted str to parse
+    ///
+    /// # Returns
+    /// A LevelSpec, a ParseError if the input is malformed or too long, or
+    /// TooManyLevels if it has more levels than permitted
+    pub fn parse(&self, input: &str) -> Result<LevelSpec, LSE> {
+        if input.len() > self.max_len {
+            return Err(LSE::ParseError(ParseFailure::TooLong { len: input.len(), max_len: self.max_len }));
+        }
+        let levelspec = levelspec_ref_parser(input)?;
+        if levelspec.levels() > self.max_levels {
+            return Err(LSE::TooManyLevels(levelspec.levels()));
+        }
+        Ok(levelspec.to_levelspec())
+    }
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_len: DEFAULT_MAX_LEN,
+            max_levels: 3,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn rejects_long_input_before_parsing() {
+        let limits = ParseLimits::default().with_max_len(8);
+        assert_eq!(limits.parse("DEV01.RD"), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+        assert_eq!(
+            limits.parse("DEV01.RD.0001"),
+            Err(LSE::ParseError(ParseFailure::TooLong { len: 13, max_len: 8 }))
+        );
+    }
+
+    #[test]
+    fn counts_relative_levels() {
+        let limits = ParseLimits::default().with_max_levels(1);
+        assert!(limits.parse(".").is_ok());
+        assert_eq!(limits.parse(".."), Err(LSE::TooManyLevels(2)));
+        assert_eq!(ParseLimits::default().parse("A.B.C.D"), Err(LSE::TooManyLevels(4)));
+    }
+
+    #[test]
+    fn agrees_with_from_str_on_arbitrary_input() {
+        // a small xorshift generator, so that the inputs are reproducible
+        let mut state = 0x2545_f491_4f6c_dd1du64;
+        let mut next = move || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            state
+        };
+        let alphabet = ['A', 'z', '0', '9', '.', '%', '?', '_', ' ', 'é', '\u{0}', '\u{1F600}'];
+        for _ in 0..10_000 {
+            let len = (next() % 12) as usize;
+            let input = (0..len).map(|_| alphabet[(next() % alphabet.len() as u64) as usize]).collect::<String>();
+            let result = ParseLimits::default().parse(&input);
+            assert_eq!(result, LevelSpec::from_str(&input), "{:?}", input);
+            // entirely relative levels render one period short
+            if let Some(levelspec) = result.ok().filter(|levelspec| !levelspec.to_vec_str().iter().all(|level| level.is_relative())) {
+                assert!(levelspec.to_string_checked().is_ok(), "{:?}", input);
+            }
+        }
+    }
+}
@@ -0,0 +1,393 @@
+use crate::{AliasTable, LevelSpec, LevelSpecRef, LevelTypeRef, LevelSpecterError as LSE, ParseDiagnostic, ParseFailure, ReservedSequences, diagnostic, levelparser::levelparser_with};
+
+// Longest input which `is_valid` uppercases on the stack rather than the heap
+const UPPERCASE_BUFFER_LEN: usize = 64;
+
+// The first option a parsed levelspec violates
+enum Rejection {
+    Wildcards,
+    Relative,
+    ShotPadding(usize),
+}
+
+/// How forgiving ParseOptions are, so that call sites state their intent
+/// rather than a set of toggles. See `ParseOptions::strictness`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Strictness {
+    /// Parse exactly as `LevelSpec::new` does, without trimming or case
+    /// coercion. The default.
+    Strict,
+    /// Trim surrounding whitespace and uppercase input before parsing, as
+    /// suits hand typed input
+    Lenient,
+    /// Options which match neither preset
+    Custom(ParseOptions),
+}
+
+/// Options controlling how input is parsed into a LevelSpec. Start from a
+/// `Strictness` preset via `strict`, `lenient`, or `From<Strictness>`, and
+/// adjust individual toggles with the `with_` methods. The default options
+/// are strict, parsing exactly as `LevelSpec::new` does.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, ParseOptions};
+/// 
+/// let options = ParseOptions::lenient();
+/// assert_eq!(options.parse(" dev01.rd.0001\n"), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+/// 
+/// let concrete = ParseOptions::strict().with_wildcards(false);
+/// assert!(concrete.parse("DEV01.%").is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ParseOptions {
+    /// Trim surrounding whitespace from the input before parsing
+    pub trim_whitespace: bool,
+    /// Convert the input to uppercase before parsing
+    pub uppercase: bool,
+    /// Permit wildcard levels
+    pub allow_wildcards: bool,
+    /// Permit relative levels
+    pub allow_relative: bool,
+    /// Minimum number of digits numeric shots must have
+    pub shot_padding: Option<usize>,
+    /// Aliases replaced by their canonical names once input is parsed
+    pub aliases: AliasTable,
+    /// Sequences whose shots are named rather than numbered
+    pub reserved_sequences: ReservedSequences,
+}
+
+impl ParseOptions {
+    /// New up the Strict preset, which parses exactly as `LevelSpec::new` does
+    pub fn strict() -> Self {
+        Self {
+            trim_whitespace: false,
+            uppercase: false,
+            allow_wildcards: true,
+            allow_relative: true,
+            shot_padding: None,
+            aliases: AliasTable::default(),
+            reserved_sequences: ReservedSequences::default(),
+        }
+    }
+
+    /// New up the Lenient preset, which trims and uppercases input
+    pub fn lenient() -> Self {
+        Self::strict().with_trim_whitespace(true).with_uppercase(true)
+    }
+
+    /// Retrieve the preset matching these options, or Custom if they have
+    /// been adjusted from both. Aliases and reserved sequences are site data
+    /// rather than a measure of strictness, so they are ignored when matching
+    /// presets.
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::{ParseOptions, Strictness};
+    /// 
+    /// assert_eq!(ParseOptions::default().strictness(), Strictness::Strict);
+    /// assert_eq!(ParseOptions::strict().with_uppercase(true).with_trim_whitespace(true).strictness(), Strictness::Lenient);
+    /// let concrete = ParseOptions::strict().with_wildcards(false);
+    /// assert_eq!(concrete.strictness(), Strictness::Custom(concrete));
+    /// ```
+    pub fn strictness(&self) -> Strictness {
+        let toggles = Self {
+            aliases: AliasTable::default(),
+            reserved_sequences: ReservedSequences::default(),
+            ..self.clone()
+        };
+        if toggles == Self::strict() {
+            Strictness::Strict
+        } else if toggles == Self::lenient() {
+            Strictness::Lenient
+        } else {
+            Strictness::Custom(self.clone())
+        }
+    }
+
+    /// Set whether surrounding whitespace is trimmed and return self
+    pub fn with_trim_whitespace(mut self, trim_whitespace: bool) -> Self {
+        self.trim_whitespace = trim_whitespace;
+        self
+    }
+
+    /// Set whether input is converted to uppercase and return self
+    pub fn with_uppercase(mut self, uppercase: bool) -> Self {
+        self.uppercase = uppercase;
+        self
+    }
+
+    /// Set whether wildcard levels are permitted and return self
+    pub fn with_wildcards(mut self, allow_wildcards: bool) -> Self {
+        self.allow_wildcards = allow_wildcards;
+        self
+    }
+
+    /// Set whether relative levels are permitted and return self
+    pub fn with_relative(mut self, allow_relative: bool) -> Self {
+        self.allow_relative = allow_relative;
+        self
+    }
+
+    /// Set the minimum number of digits in numeric shots and return self
+    pub fn with_shot_padding(mut self, shot_padding: usize) -> Self {
+        self.shot_padding = Some(shot_padding);
+        self
+    }
+
+    /// Set the aliases resolved once input is parsed and return self
+    pub fn with_aliases(mut self, aliases: AliasTable) -> Self {
+        self.aliases = aliases;
+        self
+    }
+
+    /// Set the sequences whose shots are named rather than numbered and
+    /// return self. See `ReservedSequences`.
+    pub fn with_reserved_sequences(mut self, reserved_sequences: ReservedSequences) -> Self {
+        self.reserved_sequences = reserved_sequences;
+        self
+    }
+
+    /// Parse the input into a LevelSpec, applying the options
+    /// 
+    /// # Parameters
+    /// 
+    /// * `input` - The str to parse
+    /// 
+    /// # Returns
+    /// A LevelSpec, or a ParseError if the input is malformed or violates the options
+    pub fn parse(&self, input: &str) -> Result<LevelSpec, LSE> {
+        let input = if self.trim_whitespace {input.trim()} else {input};
+        let levelspec = if self.uppercase {
+            self.parse_levels(&input.to_uppercase())?
+        } else {
+            self.parse_levels(input)?
+        };
+        match self.rejection(&LevelSpecRef::from(&levelspec)) {
+            Some(Rejection::Wildcards) => {
+                return Err(LSE::ParseError(ParseFailure::Message(format!("Wildcards are not permitted in {}", input))));
+            },
+            Some(Rejection::Relative) => {
+                return Err(LSE::ParseError(ParseFailure::Message(format!("Relative levels are not permitted in {}", input))));
+            },
+            Some(Rejection::ShotPadding(padding)) => {
+                return Err(LSE::ParseError(ParseFailure::Message(
+                    format!("Shot in {} must have at least {} digits", input, padding)
+                )));
+            },
+            None => (),
+        }
+        if self.aliases.is_empty() {
+            Ok(levelspec)
+        } else {
+            Ok(levelspec.resolve_aliases(&self.aliases))
+        }
+    }
+
+    /// Returns true if `parse` would accept the input. Short inputs are
+    /// checked without allocating, even when the options uppercase them,
+    /// which suits validating form fields as they are edited.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `input` - The str to check
+    /// 
+    /// # Returns
+    /// true if the input is a levelspec permitted by the options
+    /// 
+    /// # Example
+    /// 
+    /// ```
+    /// use levelspecter::ParseOptions;
+    /// 
+    /// let options = ParseOptions::default().with_uppercase(true).with_relative(false);
+    /// assert!(options.is_valid("dev01.rd.0001"));
+    /// assert!(!options.is_valid(".rd.0001"));
+    /// ```
+    pub fn is_valid(&self, input: &str) -> bool {
+        let input = if self.trim_whitespace {input.trim()} else {input};
+        if !self.uppercase || (input.is_ascii() && !input.bytes().any(|c| c.is_ascii_lowercase())) {
+            return self.accepts(input);
+        }
+        if !input.is_ascii() || input.len() > UPPERCASE_BUFFER_LEN {
+            return self.parse(input).is_ok();
+        }
+        let mut buffer = [0u8; UPPERCASE_BUFFER_LEN];
+        let buffer = &mut buffer[..input.len()];
+        buffer.copy_from_slice(input.as_bytes());
+        buffer.make_ascii_uppercase();
+        std::str::from_utf8(buffer).is_ok_and(|input| self.accepts(input))
+    }
+
+    // parse the input as is, and check it against the options
+    fn accepts(&self, input: &str) -> bool {
+        levelparser_with(input, Some(&self.reserved_sequences)).is_ok_and(|levelspec| self.rejection(&levelspec).is_none())
+    }
+
+    // parse the input as is, honouring the reserved sequences
+    fn parse_levels(&self, input: &str) -> Result<LevelSpec, LSE> {
+        levelparser_with(input, Some(&self.reserved_sequences)).map(|levels| levels.to_levelspec())
+    }
+
+    // find the first option which the parsed levelspec violates
+    fn rejection(&self, levelspec: &LevelSpecRef) -> Option<Rejection> {
+        if !self.allow_wildcards && levelspec.iter().any(|level| level.is_wildcard()) {
+            return Some(Rejection::Wildcards);
+        }
+        if !self.allow_relative && levelspec.has_relative() {
+            return Some(Rejection::Relative);
+        }
+        match (self.shot_padding, levelspec.shot()) {
+            (Some(padding), Some(LevelTypeRef::Term(shot)))
+                if shot.bytes().all(|c| c.is_ascii_digit()) && shot.len() < padding => {
+                Some(Rejection::ShotPadding(padding))
+            },
+            _ => None,
+        }
+    }
+}
+
+/// Returns true if the input is a levelspec permitted by the options. See
+/// `ParseOptions::is_valid`.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{is_valid_with, ParseOptions};
+/// 
+/// let options = ParseOptions::strict().with_wildcards(false);
+/// assert!(is_valid_with("DEV01.RD", &options));
+/// assert!(!is_valid_with("DEV01.%", &options));
+/// ```
+pub fn is_valid_with(input: &str, options: &ParseOptions) -> bool {
+    options.is_valid(input)
+}
+
+impl ParseOptions {
+    /// Explain why the input fails to parse with these options, locating the
+    /// offending character in the trimmed, case converted input.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `input` - The str which failed to parse
+    /// 
+    /// # Returns
+    /// A ParseDiagnostic, or None if the input parses
+    pub fn diagnose(&self, input: &str) -> Option<ParseDiagnostic> {
+        if self.is_valid(input) {
+            return None;
+        }
+        let input = if self.trim_whitespace {input.trim()} else {input};
+        let input = if self.uppercase {input.to_uppercase()} else {input.to_string()};
+        let (offset, message) = match self.parse_levels(&input) {
+            Err(_) => diagnostic::locate(&input, &self.reserved_sequences),
+            Ok(levelspec) => self.locate_violation(&input, &levelspec),
+        };
+        Some(ParseDiagnostic::new(&input, offset, message))
+    }
+
+    // locate the first level of a parsed LevelSpec which the options reject
+    fn locate_violation(&self, input: &str, levelspec: &LevelSpec) -> (usize, String) {
+        let mut offset = 0;
+        for (idx, level) in levelspec.to_vec_str().into_iter().enumerate() {
+            if !self.allow_wildcards && level.is_wildcard() {
+                return (offset, "wildcards are not permitted".to_string());
+            }
+            if !self.allow_relative && level.is_relative() {
+                return (offset, "relative levels are not permitted".to_string());
+            }
+            if let (2, Some(padding)) = (idx, self.shot_padding) {
+                return (offset, format!("shots must have at least {} digits", padding));
+            }
+            offset += level.to_str().len() + 1;
+        }
+        (input.len().min(offset), "unable to parse levelspec".to_string())
+    }
+}
+
+impl Default for ParseOptions {
+    fn default() -> Self {
+        Self::strict()
+    }
+}
+
+impl From<Strictness> for ParseOptions {
+    fn from(strictness: Strictness) -> Self {
+        match strictness {
+            Strictness::Strict => Self::strict(),
+            Strictness::Lenient => Self::lenient(),
+            Strictness::Custom(options) => options,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_options_match_new() {
+        for input in &["DEV01.RD.0001", "DEV01.%.%", "..0001", "dev01", " DEV01"] {
+            assert_eq!(ParseOptions::default().parse(input), LevelSpec::new(input));
+        }
+    }
+
+    #[test]
+    fn presets_round_trip_through_strictness() {
+        for options in &[ParseOptions::strict(), ParseOptions::lenient(), ParseOptions::lenient().with_shot_padding(4)] {
+            assert_eq!(&ParseOptions::from(options.strictness()), options);
+        }
+        assert_eq!(ParseOptions::lenient().parse(" dev01 "), Ok(LevelSpec::from_show("DEV01")));
+        assert!(ParseOptions::strict().parse(" DEV01").is_err());
+    }
+
+    #[test]
+    fn resolves_aliases() {
+        let options = ParseOptions::lenient().with_aliases(AliasTable::default().with_sequence_alias("RND", "RD"));
+        assert_eq!(options.parse("dev01.rnd.0001"), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+        assert_eq!(options.strictness(), Strictness::Lenient);
+    }
+
+    #[test]
+    fn rejects_relative_levels() {
+        let options = ParseOptions::default().with_relative(false);
+        assert!(options.parse(".RD.0001").is_err());
+        assert!(options.parse("DEV01.RD.0001").is_ok());
+    }
+
+    #[test]
+    fn diagnoses_option_violations() {
+        let options = ParseOptions::default().with_trim_whitespace(true).with_wildcards(false).with_shot_padding(4);
+        let diagnostic = options.diagnose("  DEV01.%.0001").unwrap();
+        assert_eq!((diagnostic.input.as_str(), diagnostic.column), ("DEV01.%.0001", 6));
+        assert_eq!(options.diagnose("DEV01.RD.01").unwrap().column, 9);
+        assert_eq!(options.diagnose("DEV01.RD.0001"), None);
+    }
+
+    #[test]
+    fn is_valid_agrees_with_parse() {
+        let long = format!("dev01.{}.0001", "r".repeat(UPPERCASE_BUFFER_LEN));
+        let inputs = ["DEV01.RD.0001", "dev01.rd.0001", " dev01.%.01 ", ".RD.0001", "DEV01..0001", "bad_line", "", long.as_str()];
+        let options = [
+            ParseOptions::default(),
+            ParseOptions::lenient(),
+            ParseOptions::default().with_uppercase(true).with_wildcards(false).with_relative(false),
+            ParseOptions::default().with_uppercase(true).with_trim_whitespace(true).with_shot_padding(4),
+        ];
+        for options in options.iter() {
+            for input in inputs.iter() {
+                assert_eq!(options.is_valid(input), options.parse(input).is_ok(), "{:?} {:?}", options, input);
+            }
+        }
+    }
+
+    #[test]
+    fn enforces_shot_padding() {
+        let options = ParseOptions::default().with_shot_padding(4);
+        assert!(options.parse("DEV01.RD.0001").is_ok());
+        assert!(options.parse("DEV01.RD.01").is_err());
+        assert!(options.parse("DEV01.ASSETDEV.FOO").is_ok());
+    }
+}
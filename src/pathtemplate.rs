@@ -0,0 +1,601 @@
+use crate::{LevelSpecterError as LSE, LevelSpec, LevelName, LevelType};
+use std::path::{Path, PathBuf};
+use std::collections::BTreeMap;
+use std::str::FromStr;
+use std::fmt;
+
+/// A single piece of a parsed template segment
+#[derive(Debug, PartialEq, Eq, Clone)]
+enum Token {
+    Literal(String),
+    Root,
+    Level(LevelName),
+    Custom(String),
+}
+
+/// Template describing how a LevelSpec maps onto the filesystem. Templates are
+/// strings such as `{root}/{show}/{sequence}/{shot}`, made up of `/` separated
+/// segments containing literal text and the `{root}`, `{show}`, `{sequence}`, and
+/// `{shot}` tokens. A template is parsed once and may be reused to render any
+/// number of LevelSpecs, or to match paths back to LevelSpecs.
+/// 
+/// Templates built with `with_custom_tokens` may also contain site specific 
+/// tokens, such as `{task}`, whose values are set with `with_value`.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct PathTemplate {
+    template: String,
+    root: Option<PathBuf>,
+    values: BTreeMap<String, String>,
+    segments: Vec<Vec<Token>>,
+}
+
+impl PathTemplate {
+    /// New up a PathTemplate from a str or string.
+    ///
+    /// # Parameters
+    ///
+    /// * `template` - The template string, eg `{root}/{show}/{sequence}/{shot}`
+    ///
+    /// # Returns
+    /// A PathTemplate instance or error if the template is malformed
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use levelspecter::{LevelSpec, PathTemplate};
+    /// use std::path::PathBuf;
+    ///
+    /// let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap().with_root("/jobs");
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(ls.to_path(&template), Ok(PathBuf::from("/jobs/DEV01/RD/0001")));
+    /// ```
+    pub fn new<I>(template: I) -> Result<PathTemplate, LSE>
+    where
+        I: AsRef<str>
+    {
+        Self::with_custom_tokens(template, Vec::<String>::new())
+    }
+
+    /// New up a PathTemplate which may contain the supplied custom tokens, in
+    /// addition to the standard ones.
+    ///
+    /// # Parameters
+    ///
+    /// * `template` - The template string, eg `{root}/{show}/{sequence}/{shot}/{task}`
+    /// * `tokens` - The names of the custom tokens, eg `task`
+    ///
+    /// # Returns
+    /// A PathTemplate instance or error if the template is malformed, or if a
+    /// custom token name is invalid or shadows a standard token
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use levelspecter::{LevelSpec, PathTemplate};
+    /// use std::path::PathBuf;
+    ///
+    /// let template = PathTemplate::with_custom_tokens("/jobs/{show}/{sequence}/{shot}/{task}", vec!["task"])
+    ///     .unwrap()
+    ///     .with_value("task", "comp");
+    /// let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+    /// assert_eq!(ls.to_path(&template), Ok(PathBuf::from("/jobs/DEV01/RD/0001/comp")));
+    /// ```
+    pub fn with_custom_tokens<I, T, S>(template: I, tokens: T) -> Result<PathTemplate, LSE>
+    where
+        I: AsRef<str>,
+        T: IntoIterator<Item = S>,
+        S: Into<String>
+    {
+        let template = template.as_ref();
+        let mut custom = Vec::new();
+        for token in tokens {
+            let token = token.into();
+            if token.is_empty() || !token.chars().all(|c| c.is_ascii_alphanumeric() || c == '_') {
+                return Err(LSE::PathTemplateError(format!("Invalid custom token name '{}'", token)));
+            }
+            if STANDARD_TOKENS.contains(&token.as_str()) {
+                return Err(LSE::PathTemplateError(format!("Custom token '{{{}}}' shadows a standard token", token)));
+            }
+            custom.push(token);
+        }
+        let mut segments = Vec::new();
+        // a leading separator is preserved as an empty segment so that
+        // absolute templates render absolute paths
+        for segment in template.split('/') {
+            segments.push(parse_segment(segment, template, &custom)?);
+        }
+        Ok(PathTemplate {
+            template: template.to_string(),
+            root: None,
+            values: BTreeMap::new(),
+            segments,
+        })
+    }
+
+    /// Set the value substituted for the `{root}` token and return self.
+    pub fn with_root<P>(mut self, root: P) -> Self
+    where
+        P: AsRef<Path>
+    {
+        self.root = Some(root.as_ref().to_path_buf());
+        self
+    }
+
+    /// Set the value substituted for the `{root}` token
+    pub fn set_root<P>(&mut self, root: P)
+    where
+        P: AsRef<Path>
+    {
+        self.root = Some(root.as_ref().to_path_buf());
+    }
+
+    /// Retrieve the root, if one has been set
+    pub fn root(&self) -> Option<&Path> {
+        self.root.as_deref()
+    }
+
+    /// Set the value substituted for a custom token and return self
+    pub fn with_value<I>(mut self, token: I, value: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.set_value(token, value);
+        self
+    }
+
+    /// Set the value substituted for a custom token
+    pub fn set_value<I>(&mut self, token: I, value: I)
+    where
+        I: Into<String>
+    {
+        self.values.insert(token.into(), value.into());
+    }
+
+    /// Retrieve the value of a custom token, if one has been set
+    pub fn value(&self, token: &str) -> Option<&str> {
+        self.values.get(token).map(|val| val.as_str())
+    }
+
+    /// Retrieve the template string the PathTemplate was parsed from
+    pub fn template(&self) -> &str {
+        &self.template
+    }
+
+    /// Render the supplied LevelSpec as a path. Rendering stops at the first
+    /// segment which references a level the LevelSpec does not have, or a 
+    /// custom token without a value, so a sequence renders as the sequence
+    /// directory. Levels render in their
+    /// display form; resolve relative levels with `rel_to_abs` first.
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to render
+    ///
+    /// # Returns
+    /// The rendered PathBuf, or a PathTemplateError if the template contains
+    /// `{root}` but no root has been set
+    pub fn render(&self, levelspec: &LevelSpec) -> Result<PathBuf, LSE> {
+        if self.root.is_none() && self.segments.iter().flatten().any(|token| *token == Token::Root) {
+            return Err(LSE::PathTemplateError(
+                format!("Template '{}' contains {{root}} but no root has been set", self.template)));
+        }
+        Ok(self.render_unchecked(levelspec))
+    }
+
+    // render the LevelSpec, rendering an unset root as empty. Callers which
+    // have not set the root go through `render`.
+    pub(crate) fn render_unchecked(&self, levelspec: &LevelSpec) -> PathBuf {
+        let mut path = String::new();
+        for (idx, segment) in self.segments.iter().enumerate() {
+            let mut rendered = String::new();
+            for token in segment {
+                match token {
+                    Token::Literal(val) => rendered.push_str(val),
+                    Token::Root => {
+                        if let Some(ref root) = self.root {
+                            rendered.push_str(&root.to_string_lossy())
+                        }
+                    },
+                    Token::Level(level) => {
+                        match level_value(levelspec, *level) {
+                            Some(val) => rendered.push_str(&val),
+                            None => return PathBuf::from(path),
+                        }
+                    },
+                    Token::Custom(name) => {
+                        match self.values.get(name) {
+                            Some(val) => rendered.push_str(val),
+                            None => return PathBuf::from(path),
+                        }
+                    }
+                }
+            }
+            if idx > 0 {
+                path.push('/');
+            }
+            path.push_str(&rendered);
+        }
+        PathBuf::from(path)
+    }
+
+    /// Match a path against the template, recovering the LevelSpec it was
+    /// rendered from along with the values of any custom tokens. As with 
+    /// rendering, the path may stop short of the full template, so a sequence
+    /// directory matches as a sequence. The `{root}` token matches the root if
+    /// one has been set, and any leading directories otherwise.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path to match
+    ///
+    /// # Returns
+    /// A tuple of the LevelSpec and a map of custom token values, or a 
+    /// PathTemplateError if the path does not match. Levels are validated as
+    /// by `LevelSpec::new`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use levelspecter::{LevelSpec, PathTemplate};
+    ///
+    /// let template = PathTemplate::with_custom_tokens("{root}/{show}/{sequence}/{shot}/{task}", vec!["task"])
+    ///     .unwrap()
+    ///     .with_root("/jobs");
+    /// let (ls, values) = template.match_path("/jobs/DEV01/RD/0001/comp").unwrap();
+    /// assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+    /// assert_eq!(values["task"], "comp");
+    /// ```
+    pub fn match_path<P>(&self, path: P) -> Result<(LevelSpec, BTreeMap<String, String>), LSE>
+    where
+        P: AsRef<Path>
+    {
+        let path = path.as_ref().to_string_lossy();
+        let trimmed = if path.len() > 1 {path.trim_end_matches('/')} else {&path};
+        let root = self.root.as_ref().map(|root| root.to_string_lossy().into_owned());
+        // try the full template first, then successively shorter prefixes
+        for count in (1..=self.segments.len()).rev() {
+            let mut pattern = Vec::new();
+            for (idx, segment) in self.segments[..count].iter().enumerate() {
+                if idx > 0 {
+                    pattern.push(Token::Literal("/".to_string()));
+                }
+                for token in segment {
+                    match (token, &root) {
+                        (Token::Root, Some(root)) => pattern.push(Token::Literal(root.clone())),
+                        _ => pattern.push(token.clone()),
+                    }
+                }
+            }
+            let mut captures = BTreeMap::new();
+            if match_tokens(&pattern, trimmed, &mut captures) {
+                return self.levelspec_from_captures(captures, &path);
+            }
+        }
+        Err(LSE::PathTemplateError(format!("Path '{}' does not match template '{}'", path, self.template)))
+    }
+
+    // build the LevelSpec from the captured token values, returning the custom
+    // values. Each level is validated on its own, so that a directory name
+    // containing a period cannot supply further levels.
+    fn levelspec_from_captures(&self, mut captures: BTreeMap<String, String>, path: &str)
+    -> Result<(LevelSpec, BTreeMap<String, String>), LSE> {
+        let mut levels = Vec::new();
+        for name in &STANDARD_TOKENS[1..] {
+            match captures.remove(*name) {
+                Some(val) if val.contains('.') => return Err(LSE::PathTemplateError(format!(
+                    "Path '{}' has a {} containing '.': '{}'", path, name, val))),
+                Some(val) => levels.push(LevelType::from_str(&val)?),
+                None => break,
+            }
+        }
+        let mut levels = levels.into_iter();
+        let show = levels.next()
+            .ok_or_else(|| LSE::PathTemplateError(format!("Path '{}' does not contain a show", path)))?;
+        captures.remove(STANDARD_TOKENS[0]);
+        let levelspec = LevelSpec { show, sequence: levels.next(), shot: levels.next() }.validated()?;
+        Ok((levelspec, captures))
+    }
+}
+
+// The tokens available in every template
+const STANDARD_TOKENS: [&str; 4] = ["root", "show", "sequence", "shot"];
+
+// returns the name a token is captured under, if it captures
+fn capture_name(token: &Token) -> Option<&str> {
+    match token {
+        Token::Literal(_) => None,
+        Token::Root => Some(STANDARD_TOKENS[0]),
+        Token::Level(LevelName::Show) => Some(STANDARD_TOKENS[1]),
+        Token::Level(LevelName::Sequence) => Some(STANDARD_TOKENS[2]),
+        Token::Level(LevelName::Shot) => Some(STANDARD_TOKENS[3]),
+        Token::Custom(name) => Some(name),
+    }
+}
+
+// match the input against the tokens, backtracking over the length of each
+// capture. Captures may not be empty, and only an unset root may span
+// directories. A token appearing more than once must capture the same value.
+fn match_tokens(tokens: &[Token], input: &str, captures: &mut BTreeMap<String, String>) -> bool {
+    let (token, rest) = match tokens.split_first() {
+        Some(split) => split,
+        None => return input.is_empty(),
+    };
+    let name = match capture_name(token) {
+        Some(name) => name,
+        None => {
+            let literal = if let Token::Literal(val) = token {val.as_str()} else {""};
+            return input.starts_with(literal) && match_tokens(rest, &input[literal.len()..], captures);
+        }
+    };
+    if let Some(existing) = captures.get(name).cloned() {
+        return input.starts_with(existing.as_str()) && match_tokens(rest, &input[existing.len()..], captures);
+    }
+    let spans_directories = *token == Token::Root;
+    let ends = input.char_indices().map(|(idx, _)| idx).skip(1).chain(std::iter::once(input.len()));
+    for end in ends {
+        if !spans_directories && input[..end].contains('/') {
+            break;
+        }
+        captures.insert(name.to_string(), input[..end].to_string());
+        if match_tokens(rest, &input[end..], captures) {
+            return true;
+        }
+        captures.remove(name);
+    }
+    false
+}
+
+/// An ordered list of PathTemplates describing the filesystem layouts in
+/// use at a site, used to work out which LevelSpec a directory belongs to.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, PathTemplate, PathSchema};
+///
+/// let schema = PathSchema::new()
+///     .with_template(PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap());
+/// let ls = schema.levelspec_for("/jobs/DEV01/RD/0001/comp/work").unwrap();
+/// assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct PathSchema {
+    templates: Vec<PathTemplate>,
+}
+
+impl PathSchema {
+    /// New up an empty PathSchema
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a template and return self. Templates are tried in the order added.
+    pub fn with_template(mut self, template: PathTemplate) -> Self {
+        self.templates.push(template);
+        self
+    }
+
+    /// Add a template. Templates are tried in the order added.
+    pub fn add_template(&mut self, template: PathTemplate) {
+        self.templates.push(template);
+    }
+
+    /// Retrieve the templates
+    pub fn templates(&self) -> &[PathTemplate] {
+        &self.templates
+    }
+
+    /// Find the LevelSpec enclosing the supplied path. The path and then each 
+    /// of its ancestors are matched against the templates, so directories 
+    /// below a shot resolve to the shot.
+    ///
+    /// # Parameters
+    ///
+    /// * `path` - The path to inspect
+    ///
+    /// # Returns
+    /// The LevelSpec of the deepest matching directory, or a PathTemplateError
+    /// if neither the path nor its ancestors match a template
+    pub fn levelspec_for<P>(&self, path: P) -> Result<LevelSpec, LSE>
+    where
+        P: AsRef<Path>
+    {
+        let path = path.as_ref();
+        for dir in path.ancestors() {
+            for template in &self.templates {
+                if let Ok((levelspec, _)) = template.match_path(dir) {
+                    return Ok(levelspec);
+                }
+            }
+        }
+        Err(LSE::PathTemplateError(format!("No template matches '{}' or its parents", path.display())))
+    }
+}
+
+impl FromStr for PathTemplate {
+    type Err = LSE;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PathTemplate::new(s)
+    }
+}
+
+impl fmt::Display for PathTemplate {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.template)
+    }
+}
+
+// retrieve the display form of the requested level, if present
+fn level_value(levelspec: &LevelSpec, level: LevelName) -> Option<String> {
+    match level {
+        LevelName::Show => Some(levelspec.show().to_string()),
+        LevelName::Sequence => levelspec.sequence().map(|s| s.to_string()),
+        LevelName::Shot => levelspec.shot().map(|s| s.to_string()),
+    }
+}
+
+// split a single path segment into literal and token pieces
+fn parse_segment(segment: &str, template: &str, custom: &[String]) -> Result<Vec<Token>, LSE> {
+    let mut tokens = Vec::new();
+    let mut rest = segment;
+    while let Some(start) = rest.find('{') {
+        if start > 0 {
+            tokens.push(Token::Literal(rest[..start].to_string()));
+        }
+        let end = rest[start..].find('}').ok_or_else(|| LSE::PathTemplateError(
+            format!("Unterminated token in template '{}'", template)))? + start;
+        let token = match &rest[start + 1..end] {
+            "root" => Token::Root,
+            "show" => Token::Level(LevelName::Show),
+            "sequence" => Token::Level(LevelName::Sequence),
+            "shot" => Token::Level(LevelName::Shot),
+            other if custom.iter().any(|name| name == other) => Token::Custom(other.to_string()),
+            other => return Err(LSE::PathTemplateError(
+                format!("Unknown token '{{{}}}' in template '{}'", other, template))),
+        };
+        tokens.push(token);
+        rest = &rest[end + 1..];
+    }
+    if rest.contains('}') {
+        return Err(LSE::PathTemplateError(format!("Unmatched '}}' in template '{}'", template)));
+    }
+    if !rest.is_empty() {
+        tokens.push(Token::Literal(rest.to_string()));
+    }
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_render_shot() {
+        let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap().with_root("/jobs");
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(template.render(&ls), Ok(PathBuf::from("/jobs/DEV01/RD/0001")));
+    }
+
+    #[test]
+    fn can_render_sequence() {
+        let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+        let ls = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(template.render(&ls), Ok(PathBuf::from("/jobs/DEV01/RD")));
+    }
+
+    #[test]
+    fn can_render_mixed_segment() {
+        let template = PathTemplate::new("/jobs/{show}/shots/{sequence}_{shot}").unwrap();
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(template.render(&ls), Ok(PathBuf::from("/jobs/DEV01/shots/RD_0001")));
+    }
+
+    #[test]
+    fn cannot_render_unset_root() {
+        let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap();
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(
+            template.render(&ls),
+            Err(LSE::PathTemplateError(
+                "Template '{root}/{show}/{sequence}/{shot}' contains {root} but no root has been set".to_string()))
+        );
+        assert_eq!(template.with_root("/jobs").render(&ls), Ok(PathBuf::from("/jobs/DEV01/RD/0001")));
+    }
+
+    #[test]
+    fn cannot_parse_unknown_token() {
+        let template = PathTemplate::new("/jobs/{foo}/{show}");
+        assert_eq!(
+            template,
+            Err(LSE::PathTemplateError("Unknown token '{foo}' in template '/jobs/{foo}/{show}'".to_string()))
+        );
+    }
+
+    #[test]
+    fn cannot_parse_unterminated_token() {
+        let template = PathTemplate::new("/jobs/{show");
+        assert!(template.is_err());
+    }
+
+    #[test]
+    fn cannot_shadow_standard_tokens() {
+        assert_eq!(
+            PathTemplate::with_custom_tokens("/jobs/{show}", vec!["show"]),
+            Err(LSE::PathTemplateError("Custom token '{show}' shadows a standard token".to_string()))
+        );
+        assert!(PathTemplate::with_custom_tokens("/jobs/{show}", vec!["a b"]).is_err());
+    }
+
+    #[test]
+    fn custom_tokens_without_values_truncate() {
+        let template = PathTemplate::with_custom_tokens("/jobs/{show}/{sequence}/{shot}/{task}", vec!["task"]).unwrap();
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        assert_eq!(template.render(&ls), Ok(PathBuf::from("/jobs/DEV01/RD/0001")));
+    }
+
+    #[test]
+    fn can_match_truncated_paths() {
+        let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+        assert_eq!(template.match_path("/jobs/DEV01/RD/").unwrap().0, LevelSpec::from_sequence("DEV01", "RD"));
+        assert_eq!(template.match_path("/jobs/DEV01").unwrap().0, LevelSpec::from_show("DEV01"));
+        assert!(template.match_path("/jobs").is_err());
+        assert!(template.match_path("/other/DEV01/RD").is_err());
+    }
+
+    #[test]
+    fn can_match_mixed_segments() {
+        let template = PathTemplate::new("/jobs/{show}/shots/{sequence}_{shot}").unwrap();
+        let (ls, values) = template.match_path("/jobs/DEV01/shots/RD_0001").unwrap();
+        assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert!(values.is_empty());
+    }
+
+    #[test]
+    fn unset_root_matches_leading_directories() {
+        let template = PathTemplate::new("{root}/{show}/{sequence}").unwrap();
+        assert_eq!(template.match_path("/mnt/jobs/DEV01/RD").unwrap().0, LevelSpec::from_sequence("DEV01", "RD"));
+        let template = template.with_root("/jobs");
+        assert!(template.match_path("/mnt/jobs/DEV01/RD").is_err());
+    }
+
+    #[test]
+    fn match_validates_levels() {
+        let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+        assert!(template.match_path("/jobs/DEV01/RD/notashot").is_err());
+    }
+
+    #[test]
+    fn match_rejects_periods_within_a_level() {
+        let template = PathTemplate::new("/jobs/{show}").unwrap();
+        assert_eq!(
+            template.match_path("/jobs/DEV01.RD"),
+            Err(LSE::PathTemplateError("Path '/jobs/DEV01.RD' has a show containing '.': 'DEV01.RD'".to_string()))
+        );
+        let template = PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap();
+        assert!(template.match_path("/jobs/DEV01/RD.0001").is_err());
+    }
+
+    #[test]
+    fn schema_finds_enclosing_levelspec() {
+        let schema = PathSchema::new()
+            .with_template(PathTemplate::new("/jobs/{show}/{sequence}/{shot}").unwrap())
+            .with_template(PathTemplate::new("/work/{show}/{sequence}").unwrap());
+        assert_eq!(schema.levelspec_for("/jobs/DEV01/RD/0001/comp").unwrap(), LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert_eq!(schema.levelspec_for("/work/DEV01/RD/tmp").unwrap(), LevelSpec::from_sequence("DEV01", "RD"));
+        assert_eq!(schema.levelspec_for("/jobs/DEV01/not_a_seq").unwrap(), LevelSpec::from_show("DEV01"));
+        assert!(schema.levelspec_for("/home/someone").is_err());
+    }
+
+    #[test]
+    fn render_and_match_round_trip() {
+        let template = PathTemplate::with_custom_tokens("{root}/{show}/{sequence}/{shot}/{task}", vec!["task"])
+            .unwrap()
+            .with_root("/jobs")
+            .with_value("task", "anim");
+        let ls = LevelSpec::from_shot("DEV01", "RD", "0001");
+        let (matched, values) = template.match_path(template.render(&ls).unwrap()).unwrap();
+        assert_eq!(matched, ls);
+        assert_eq!(values.get("task").map(String::as_str), template.value("task"));
+    }
+}
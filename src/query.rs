@@ -0,0 +1,230 @@
+use crate::{LevelSpec, LevelSpecterError as LSE};
+use std::fmt;
+use std::ops::Not;
+use std::str::FromStr;
+
+/// A boolean expression over LevelSpec patterns. A LevelSpec satisfies a
+/// `Spec` term when the term's pattern matches it (see `LevelSpec::matches`).
+/// 
+/// Queries may be built programmatically or parsed from strings combining
+/// levelspecs with `AND`, `OR`, `NOT`, and parentheses. Keywords are case 
+/// insensitive, and bind in the order NOT, AND, OR.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, Query};
+/// use std::str::FromStr;
+/// 
+/// let query = Query::from_str("DEV01.RD.% AND NOT DEV01.RD.0003").unwrap();
+/// assert!(query.evaluate(&LevelSpec::from_shot("DEV01", "RD", "0001")));
+/// assert!(!query.evaluate(&LevelSpec::from_shot("DEV01", "RD", "0003")));
+/// 
+/// let built = Query::spec(LevelSpec::new("DEV01.RD.%").unwrap())
+///     .and(!Query::spec(LevelSpec::new("DEV01.RD.0003").unwrap()));
+/// assert_eq!(query, built);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Query {
+    Spec(LevelSpec),
+    Not(Box<Query>),
+    And(Box<Query>, Box<Query>),
+    Or(Box<Query>, Box<Query>),
+}
+
+impl Query {
+    /// New up a query matched by the supplied LevelSpec pattern
+    pub fn spec(levelspec: LevelSpec) -> Self {
+        Query::Spec(levelspec)
+    }
+
+    /// Combine with another query, requiring both to be satisfied
+    pub fn and(self, other: Query) -> Self {
+        Query::And(Box::new(self), Box::new(other))
+    }
+
+    /// Combine with another query, requiring either to be satisfied
+    pub fn or(self, other: Query) -> Self {
+        Query::Or(Box::new(self), Box::new(other))
+    }
+
+    /// Returns true if the supplied LevelSpec satisfies the query
+    pub fn evaluate(&self, levelspec: &LevelSpec) -> bool {
+        match self {
+            Query::Spec(pattern) => pattern.matches(levelspec),
+            Query::Not(query) => !query.evaluate(levelspec),
+            Query::And(left, right) => left.evaluate(levelspec) && right.evaluate(levelspec),
+            Query::Or(left, right) => left.evaluate(levelspec) || right.evaluate(levelspec),
+        }
+    }
+}
+
+impl Not for Query {
+    type Output = Query;
+
+    fn not(self) -> Self::Output {
+        Query::Not(Box::new(self))
+    }
+}
+
+impl FromStr for Query {
+    type Err = LSE;
+
+    fn from_str(input: &str) -> Result<Self, Self::Err> {
+        let tokens = tokenize(input);
+        let mut parser = QueryParser { tokens: &tokens, pos: 0 };
+        let query = parser.parse_or()?;
+        match parser.peek() {
+            None => Ok(query),
+            Some(token) => Err(LSE::QueryError(format!("Unexpected '{}' in query '{}'", token, input))),
+        }
+    }
+}
+
+// split the input into parentheses and whitespace separated words
+fn tokenize(input: &str) -> Vec<&str> {
+    let mut tokens = Vec::new();
+    let mut start = None;
+    for (idx, c) in input.char_indices() {
+        if c.is_whitespace() || c == '(' || c == ')' {
+            if let Some(begin) = start.take() {
+                tokens.push(&input[begin..idx]);
+            }
+            if !c.is_whitespace() {
+                tokens.push(&input[idx..idx + 1]);
+            }
+        } else if start.is_none() {
+            start = Some(idx);
+        }
+    }
+    if let Some(begin) = start {
+        tokens.push(&input[begin..]);
+    }
+    tokens
+}
+
+// recursive descent parser over the tokens, one method per precedence level
+struct QueryParser<'a> {
+    tokens: &'a [&'a str],
+    pos: usize,
+}
+
+impl<'a> QueryParser<'a> {
+    fn peek(&self) -> Option<&'a str> {
+        self.tokens.get(self.pos).copied()
+    }
+
+    // consume the next token if it is the supplied keyword
+    fn keyword(&mut self, keyword: &str) -> bool {
+        match self.peek() {
+            Some(token) if token.eq_ignore_ascii_case(keyword) => {
+                self.pos += 1;
+                true
+            },
+            _ => false,
+        }
+    }
+
+    fn parse_or(&mut self) -> Result<Query, LSE> {
+        let mut query = self.parse_and()?;
+        while self.keyword("OR") {
+            query = query.or(self.parse_and()?);
+        }
+        Ok(query)
+    }
+
+    fn parse_and(&mut self) -> Result<Query, LSE> {
+        let mut query = self.parse_not()?;
+        while self.keyword("AND") {
+            query = query.and(self.parse_not()?);
+        }
+        Ok(query)
+    }
+
+    fn parse_not(&mut self) -> Result<Query, LSE> {
+        if self.keyword("NOT") {
+            return Ok(!self.parse_not()?);
+        }
+        self.parse_atom()
+    }
+
+    fn parse_atom(&mut self) -> Result<Query, LSE> {
+        let token = self.peek().ok_or_else(|| LSE::QueryError("Unexpected end of query".to_string()))?;
+        self.pos += 1;
+        if token == "(" {
+            let query = self.parse_or()?;
+            if !self.keyword(")") {
+                return Err(LSE::QueryError("Unmatched '(' in query".to_string()));
+            }
+            return Ok(query);
+        }
+        if token == ")" || ["AND", "OR"].iter().any(|kw| token.eq_ignore_ascii_case(kw)) {
+            return Err(LSE::QueryError(format!("Expected levelspec, found '{}'", token)));
+        }
+        LevelSpec::from_str(token).map(Query::Spec)
+    }
+}
+
+impl fmt::Display for Query {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Query::Spec(levelspec) => write!(f, "{}", levelspec),
+            Query::Not(query) => write!(f, "NOT {}", Parens(query)),
+            Query::And(left, right) => write!(f, "{} AND {}", Parens(left), Parens(right)),
+            Query::Or(left, right) => write!(f, "{} OR {}", Parens(left), Parens(right)),
+        }
+    }
+}
+
+// display compound queries within parentheses
+struct Parens<'a>(&'a Query);
+
+impl<'a> fmt::Display for Parens<'a> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self.0 {
+            Query::Spec(_) | Query::Not(_) => write!(f, "{}", self.0),
+            query => write!(f, "({})", query),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ls(spec: &str) -> LevelSpec {
+        LevelSpec::from_str(spec).unwrap()
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let query = Query::from_str("DEV01.RD.% OR DEV01.AA.% and not DEV01.AA.0001").unwrap();
+        assert_eq!(query, Query::spec(ls("DEV01.RD.%")).or(Query::spec(ls("DEV01.AA.%")).and(!Query::spec(ls("DEV01.AA.0001")))));
+        assert!(query.evaluate(&ls("DEV01.AA.0002")));
+        assert!(!query.evaluate(&ls("DEV01.AA.0001")));
+    }
+
+    #[test]
+    fn parentheses_group() {
+        let query = Query::from_str("NOT (DEV01.RD.% OR DEV01.AA.%)").unwrap();
+        assert!(!query.evaluate(&ls("DEV01.RD.0001")));
+        assert!(query.evaluate(&ls("DEV01.BB.0001")));
+    }
+
+    #[test]
+    fn display_round_trips() {
+        let query = Query::from_str("NOT (DEV01.RD.% OR DEV01.AA.%) AND %.%.0001").unwrap();
+        assert_eq!(query.to_string(), "NOT (DEV01.RD.% OR DEV01.AA.%) AND %.%.0001");
+        assert_eq!(Query::from_str(&query.to_string()).unwrap(), query);
+    }
+
+    #[test]
+    fn reports_malformed_queries() {
+        assert!(Query::from_str("").is_err());
+        assert!(Query::from_str("DEV01.RD.% AND").is_err());
+        assert!(Query::from_str("(DEV01.RD.%").is_err());
+        assert!(Query::from_str("DEV01.RD.%)").is_err());
+        assert!(Query::from_str("DEV01.RD.% DEV01.AA.%").is_err());
+        assert!(Query::from_str("DEV01..RD.%").is_err());
+    }
+}
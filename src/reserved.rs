@@ -0,0 +1,150 @@
+use crate::{PathTemplate, aliases};
+use std::collections::HashMap;
+
+/// Sequences which a site treats specially, such as `COMMON` or `EDIT`.
+/// Like ASSETDEV, a reserved sequence holds named shots rather than numbered
+/// ones, so `DEV01.COMMON.LIGHTRIG` parses and `DEV01.COMMON.0001` does not.
+/// `LevelSpec::sequence_kind_with` reports them as `SequenceKind::Reserved`,
+/// and a reserved sequence may supply its own PathTemplate, which
+/// `LevelSpec::to_path_with` and `JobRoot::path_for` render with in place of
+/// the usual layout.
+///
+/// ReservedSequences are carried by `ParseOptions`, `LevelSchema`, and
+/// `JobRoot`, much as aliases are carried by ParseOptions. `LevelSpec::new`
+/// reserves nothing. Names are matched exactly, or regardless of case in
+/// case insensitive builds.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{JobRoot, LevelSpec, ParseOptions, ReservedSequences, PathTemplate, SequenceKind};
+/// use std::path::PathBuf;
+///
+/// let reserved = ReservedSequences::default()
+///     .with_sequence("EDIT")
+///     .with_template("COMMON", PathTemplate::new("{root}/{show}/common/{shot}").unwrap());
+/// let options = ParseOptions::strict().with_reserved_sequences(reserved.clone());
+/// let ls = options.parse("DEV01.COMMON.LIGHTRIG").unwrap();
+/// assert_eq!(ls.sequence_kind_with(&reserved), Some(SequenceKind::Reserved));
+/// let root = JobRoot::new("/jobs").with_reserved_sequences(reserved);
+/// assert_eq!(root.path_for(&ls), PathBuf::from("/jobs/DEV01/common/LIGHTRIG"));
+/// assert!(options.parse("DEV01.EDIT.0001").is_err());
+/// assert!(LevelSpec::new("DEV01.EDIT.0001").is_ok());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ReservedSequences {
+    /// Map of reserved sequence name to the PathTemplate its paths are
+    /// rendered with, if it differs from the usual layout
+    pub sequences: HashMap<String, Option<PathTemplate>>,
+}
+
+impl ReservedSequences {
+    /// Reserve a sequence, which renders with the usual layout, and return self
+    pub fn with_sequence<I>(mut self, name: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.insert(name.into(), None);
+        self
+    }
+
+    /// Reserve a sequence whose paths are rendered with the supplied
+    /// PathTemplate, and return self
+    pub fn with_template<I>(mut self, name: I, template: PathTemplate) -> Self
+    where
+        I: Into<String>
+    {
+        self.insert(name.into(), Some(template));
+        self
+    }
+
+    /// Returns true if no sequence is reserved
+    pub fn is_empty(&self) -> bool {
+        self.sequences.is_empty()
+    }
+
+    /// Returns true if the sequence is reserved
+    pub fn contains(&self, sequence: &str) -> bool {
+        self.lookup(sequence).is_some()
+    }
+
+    /// Retrieve the PathTemplate of a reserved sequence, if it has one
+    pub fn template(&self, sequence: &str) -> Option<&PathTemplate> {
+        self.lookup(sequence).and_then(Option::as_ref)
+    }
+
+    // reserve a sequence, replacing any differing only in case in case
+    // insensitive builds, where the two would name the same sequence
+    pub(crate) fn insert(&mut self, name: String, template: Option<PathTemplate>) {
+        if cfg!(feature = "case-insensitive") {
+            aliases::insert(&mut self.sequences, name, template);
+        } else {
+            self.sequences.insert(name, template);
+        }
+    }
+
+    // find a reserved sequence's template slot, preferring an exact match
+    fn lookup(&self, sequence: &str) -> Option<&Option<PathTemplate>> {
+        if cfg!(feature = "case-insensitive") {
+            aliases::lookup(&self.sequences, sequence)
+        } else {
+            self.sequences.get(sequence)
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::{LevelSpec, SequenceKind, JobRoot, LevelSchema, ParseOptions};
+    use std::path::PathBuf;
+    use std::str::FromStr;
+
+    // The reserved sequences shared by tests across the crate
+    pub(crate) fn reserved() -> ReservedSequences {
+        ReservedSequences::default()
+            .with_sequence("EDIT")
+            .with_template("COMMON", PathTemplate::new("{root}/{show}/common/{shot}").unwrap())
+    }
+
+    #[test]
+    fn reserved_sequences_hold_named_shots() {
+        let options = ParseOptions::strict().with_reserved_sequences(reserved());
+        assert!(options.parse("DEV01.COMMON.LIGHTRIG").is_ok());
+        assert!(options.parse("DEV01.EDIT.%").is_ok());
+        assert!(options.parse("DEV01.COMMON.0001").is_err());
+        assert!(!options.is_valid("DEV01.COMMON.0001"));
+        assert!(options.parse("DEV01.COMMONS.0001").is_ok());
+        assert!(LevelSpec::from_str("DEV01.COMMON.0001").is_ok());
+        let ls = options.parse("DEV01.EDIT").unwrap();
+        assert_eq!(ls.sequence_kind_with(&reserved()), Some(SequenceKind::Reserved));
+        assert_eq!(ls.sequence_kind(), Some(SequenceKind::Standard));
+        let ls = options.parse("EDIT.RD").unwrap();
+        assert_eq!(ls.sequence_kind_with(&reserved()), Some(SequenceKind::Standard));
+        let schema = LevelSchema::default().with_reserved_sequences(reserved());
+        assert!(LevelSpec::from_shot("DEV01", "EDIT", "CUT").validate(&schema).is_ok());
+        assert!(LevelSpec::from_shot("DEV01", "EDIT", "CUT").validate(&LevelSchema::default()).is_err());
+    }
+
+    #[test]
+    fn reserved_templates_replace_the_layout() {
+        let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap().with_root("/mnt/jobs");
+        let ls = LevelSpec::from_shot("DEV01", "COMMON", "LIGHTRIG");
+        assert_eq!(ls.to_path_with(&template, &reserved()), Ok(PathBuf::from("/mnt/jobs/DEV01/common/LIGHTRIG")));
+        assert_eq!(ls.to_path(&template), Ok(PathBuf::from("/mnt/jobs/DEV01/COMMON/LIGHTRIG")));
+        let root = JobRoot::new("/jobs").with_reserved_sequences(reserved());
+        assert_eq!(root.path_for(&ls), PathBuf::from("/jobs/DEV01/common/LIGHTRIG"));
+        assert_eq!(JobRoot::new("/jobs").path_for(&ls), PathBuf::from("/jobs/DEV01/COMMON/LIGHTRIG"));
+        let ls = LevelSpec::from_shot("DEV01", "EDIT", "CUT");
+        assert_eq!(ls.to_path_with(&template, &reserved()), Ok(PathBuf::from("/mnt/jobs/DEV01/EDIT/CUT")));
+    }
+
+    #[cfg(feature = "case-insensitive")]
+    #[test]
+    fn lookups_ignoring_case_are_deterministic() {
+        let template = PathTemplate::new("{root}/{show}/common/{shot}").unwrap();
+        let reserved = ReservedSequences::default().with_sequence("COMMON").with_template("common", template.clone());
+        assert_eq!(reserved.sequences.len(), 1);
+        assert_eq!(reserved.template("Common"), Some(&template));
+    }
+}
@@ -0,0 +1,473 @@
+use crate::{LevelSpec, LevelName, LevelContext, LevelTree, ShotIndex, EnvVars};
+use std::collections::HashMap;
+use std::env;
+#[cfg(feature = "async")]
+use async_trait::async_trait;
+#[cfg(feature = "async")]
+use futures::future::join_all;
+
+/// A source of truth for levels, such as a production database. Resolvers
+/// supply absolute values for relative levels, and enumerate the levels 
+/// which exist so that wildcards may be expanded.
+/// 
+/// Only `resolve` is required. Resolvers which cannot enumerate levels 
+/// may rely on the default `shows` and `children`, which find nothing.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelName, LevelResolver};
+/// 
+/// struct Studio;
+/// 
+/// impl LevelResolver for Studio {
+///     fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+///         match level {
+///             LevelName::Show => Some("DEV01".to_string()),
+///             _ => None,
+///         }
+///     }
+/// 
+///     fn shows(&self) -> Vec<LevelSpec> {
+///         vec![LevelSpec::from_show("DEV01")]
+///     }
+/// 
+///     fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+///         match parent.levels() {
+///             1 => vec![LevelSpec::from_sequence("DEV01", "AA"), LevelSpec::from_sequence("DEV01", "RD")],
+///             _ => Vec::new(),
+///         }
+///     }
+/// }
+/// 
+/// let ls = LevelSpec::new(".RD").unwrap();
+/// assert_eq!(ls.rel_to_abs_with(&Studio), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+/// let pattern = LevelSpec::new(".%").unwrap();
+/// assert_eq!(pattern.expand_with(&Studio).unwrap().len(), 2);
+/// ```
+pub trait LevelResolver {
+    /// Retrieve the absolute value of a relative level
+    /// 
+    /// # Parameters
+    /// 
+    /// * `level` - The relative level to resolve
+    /// * `levelspec` - The LevelSpec being resolved
+    /// 
+    /// # Returns
+    /// The value of the level, or None if it cannot be resolved
+    fn resolve(&self, level: LevelName, levelspec: &LevelSpec) -> Option<String>;
+
+    /// Retrieve the shows which exist, in order
+    fn shows(&self) -> Vec<LevelSpec> {
+        Vec::new()
+    }
+
+    /// Retrieve the immediate children of the supplied LevelSpec, in order
+    fn children(&self, _parent: &LevelSpec) -> Vec<LevelSpec> {
+        Vec::new()
+    }
+}
+
+impl LevelResolver for LevelContext {
+    fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+        self.get(level).map(|val| val.to_string())
+    }
+}
+
+impl LevelResolver for LevelTree {
+    fn resolve(&self, _level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+        None
+    }
+
+    fn shows(&self) -> Vec<LevelSpec> {
+        LevelTree::shows(self)
+    }
+
+    fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+        LevelTree::children(self, parent)
+    }
+}
+
+impl LevelResolver for ShotIndex {
+    fn resolve(&self, _level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+        None
+    }
+
+    fn shows(&self) -> Vec<LevelSpec> {
+        ShotIndex::shows(self)
+    }
+
+    fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+        ShotIndex::children(self, parent)
+    }
+}
+
+/// A LevelResolver which reads relative levels from environment variables
+/// at the time they are resolved, so changes to the environment are seen.
+/// Unset and empty variables do not resolve.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, EnvVars, EnvResolver};
+/// 
+/// std::env::set_var("EXAMPLE_SHOW", "DEV01");
+/// let resolver = EnvResolver::new(EnvVars::new("EXAMPLE_SHOW", "EXAMPLE_SEQUENCE", "EXAMPLE_SHOT"));
+/// let ls = LevelSpec::new(".RD").unwrap();
+/// assert_eq!(ls.rel_to_abs_with(&resolver), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct EnvResolver {
+    vars: EnvVars,
+}
+
+impl EnvResolver {
+    /// New up an EnvResolver reading the supplied variables
+    pub fn new(vars: EnvVars) -> Self {
+        Self { vars }
+    }
+
+    /// Retrieve the names of the variables read by the resolver
+    pub fn vars(&self) -> &EnvVars {
+        &self.vars
+    }
+}
+
+impl LevelResolver for EnvResolver {
+    fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+        env::var(self.vars.var(level)).ok().filter(|val| !val.is_empty())
+    }
+}
+
+/// A LevelResolver backed by in-memory maps, which is convenient for tests.
+/// Inserting a LevelSpec makes its parents exist as well.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelName, MapResolver};
+/// 
+/// let resolver = MapResolver::new()
+///     .with_value(LevelName::Show, "DEV01")
+///     .with_levelspec(LevelSpec::from_shot("DEV01", "RD", "0001"))
+///     .with_levelspec(LevelSpec::from_shot("DEV01", "RD", "0002"));
+/// let ls = LevelSpec::new(".RD.%").unwrap();
+/// assert_eq!(ls.expand_with(&resolver).unwrap().len(), 2);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct MapResolver {
+    values: HashMap<LevelName, String>,
+    shows: Vec<LevelSpec>,
+    children: HashMap<LevelSpec, Vec<LevelSpec>>,
+}
+
+impl MapResolver {
+    /// New up an empty MapResolver
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the value a relative level resolves to and return self
+    pub fn with_value<I>(mut self, level: LevelName, value: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.set_value(level, value);
+        self
+    }
+
+    /// Set the value a relative level resolves to
+    pub fn set_value<I>(&mut self, level: LevelName, value: I)
+    where
+        I: Into<String>
+    {
+        self.values.insert(level, value.into());
+    }
+
+    /// Insert a LevelSpec and return self
+    pub fn with_levelspec(mut self, levelspec: LevelSpec) -> Self {
+        self.insert(levelspec);
+        self
+    }
+
+    /// Insert a LevelSpec, along with its parents. Children are enumerated
+    /// in insertion order. LevelSpecs which are not concrete, or which have 
+    /// relative levels, are skipped.
+    /// 
+    /// # Returns
+    /// true if the LevelSpec was inserted, false if it was skipped
+    pub fn insert(&mut self, levelspec: LevelSpec) -> bool {
+        if !levelspec.is_concrete() || levelspec.has_relative() {
+            return false;
+        }
+        let show = levelspec.truncate_to(LevelName::Show);
+        if !self.shows.contains(&show) {
+            self.shows.push(show.clone());
+        }
+        let mut parent = show;
+        for level in &[LevelName::Sequence, LevelName::Shot][..levelspec.levels() - 1] {
+            let child = levelspec.truncate_to(*level);
+            let children = self.children.entry(parent).or_default();
+            if !children.contains(&child) {
+                children.push(child.clone());
+            }
+            parent = child;
+        }
+        true
+    }
+}
+
+impl LevelResolver for MapResolver {
+    fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+        self.values.get(&level).cloned()
+    }
+
+    fn shows(&self) -> Vec<LevelSpec> {
+        self.shows.clone()
+    }
+
+    fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+        self.children.get(parent).cloned().unwrap_or_default()
+    }
+}
+
+// Walk the resolver's levels, one depth at a time, keeping the candidates 
+// which match the corresponding level of the absolute pattern
+pub(crate) fn expand<R>(resolver: &R, pattern: &LevelSpec) -> Vec<LevelSpec>
+where
+    R: LevelResolver + ?Sized
+{
+    let levels = pattern.to_vec_str();
+    let mut candidates = resolver.shows();
+    for (depth, level) in levels.iter().enumerate() {
+        let matching = candidates.into_iter()
+            .filter(|candidate| matches!(candidate.to_vec_str().get(depth), Some(found) if level.matches(found)))
+            .collect::<Vec<_>>();
+        if depth + 1 == levels.len() {
+            return matching;
+        }
+        candidates = matching.iter().flat_map(|parent| resolver.children(parent)).collect();
+    }
+    Vec::new()
+}
+
+/// The async counterpart of LevelResolver, for sources of truth such as
+/// web services, which would otherwise block the caller.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelName, AsyncLevelResolver};
+/// use async_trait::async_trait;
+/// use futures::executor::block_on;
+/// 
+/// struct Service;
+/// 
+/// #[async_trait]
+/// impl AsyncLevelResolver for Service {
+///     async fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+///         match level {
+///             LevelName::Show => Some("DEV01".to_string()),
+///             _ => None,
+///         }
+///     }
+/// }
+/// 
+/// let ls = LevelSpec::new(".RD").unwrap();
+/// assert_eq!(block_on(ls.rel_to_abs_async(&Service)), Ok(LevelSpec::from_sequence("DEV01", "RD")));
+/// ```
+#[cfg(feature = "async")]
+#[async_trait]
+pub trait AsyncLevelResolver: Sync {
+    /// Retrieve the absolute value of a relative level
+    /// 
+    /// # Parameters
+    /// 
+    /// * `level` - The relative level to resolve
+    /// * `levelspec` - The LevelSpec being resolved
+    /// 
+    /// # Returns
+    /// The value of the level, or None if it cannot be resolved
+    async fn resolve(&self, level: LevelName, levelspec: &LevelSpec) -> Option<String>;
+
+    /// Retrieve the shows which exist, in order
+    async fn shows(&self) -> Vec<LevelSpec> {
+        Vec::new()
+    }
+
+    /// Retrieve the immediate children of the supplied LevelSpec, in order
+    async fn children(&self, _parent: &LevelSpec) -> Vec<LevelSpec> {
+        Vec::new()
+    }
+}
+
+// Resolve each relative level of the LevelSpec in turn, returning the values
+// by level
+#[cfg(feature = "async")]
+pub(crate) async fn resolve_async<R>(resolver: &R, levelspec: &LevelSpec) -> Vec<(LevelName, Option<String>)>
+where
+    R: AsyncLevelResolver + ?Sized
+{
+    let mut values = Vec::new();
+    for level in levelspec.relative_levels() {
+        values.push((level, resolver.resolve(level, levelspec).await));
+    }
+    values
+}
+
+// The async counterpart of `expand`. The children of each depth's matching
+// candidates are requested concurrently.
+#[cfg(feature = "async")]
+pub(crate) async fn expand_async<R>(resolver: &R, pattern: &LevelSpec) -> Vec<LevelSpec>
+where
+    R: AsyncLevelResolver + ?Sized
+{
+    let levels = pattern.to_vec_str();
+    let mut candidates = resolver.shows().await;
+    for (depth, level) in levels.iter().enumerate() {
+        let matching = candidates.into_iter()
+            .filter(|candidate| matches!(candidate.to_vec_str().get(depth), Some(found) if level.matches(found)))
+            .collect::<Vec<_>>();
+        if depth + 1 == levels.len() {
+            return matching;
+        }
+        candidates = join_all(matching.iter().map(|parent| resolver.children(parent))).await
+            .into_iter()
+            .flatten()
+            .collect();
+    }
+    Vec::new()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tree() -> LevelTree {
+        vec![
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+            LevelSpec::from_shot("DEV01", "AA", "0001"),
+            LevelSpec::from_sequence("TEST", "RD"),
+        ].into_iter().collect()
+    }
+
+    #[test]
+    fn expansion_agrees_with_tree() {
+        let tree = tree();
+        for pattern in &["%", "DEV01.%", "%.RD", "%.%.0001", "DEV01.RD.%", "DEV01.RD.0003"] {
+            let pattern = LevelSpec::new(*pattern).unwrap();
+            assert_eq!(pattern.expand_with(&tree), Ok(tree.expand(&pattern)));
+        }
+    }
+
+    #[test]
+    fn expansion_resolves_relative_levels_first() {
+        struct Resolver(LevelTree);
+        impl LevelResolver for Resolver {
+            fn resolve(&self, _level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+                Some("DEV01".to_string())
+            }
+            fn shows(&self) -> Vec<LevelSpec> {
+                self.0.shows()
+            }
+            fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+                self.0.children(parent)
+            }
+        }
+        let pattern = LevelSpec::new(".%").unwrap();
+        assert_eq!(pattern.expand_with(&Resolver(tree())), Ok(vec![
+            LevelSpec::from_sequence("DEV01", "AA"),
+            LevelSpec::from_sequence("DEV01", "RD"),
+        ]));
+    }
+
+    #[test]
+    fn expansion_fails_when_relative_levels_are_unresolved() {
+        let pattern = LevelSpec::new(".%").unwrap();
+        assert!(pattern.expand_with(&tree()).is_err());
+    }
+
+    #[test]
+    fn resolvers_without_enumeration_expand_to_nothing() {
+        let context = LevelContext::new(Some("DEV01"), None, None);
+        assert_eq!(LevelSpec::new("DEV01.%").unwrap().expand_with(&context), Ok(Vec::new()));
+    }
+
+    #[test]
+    fn env_resolver_reads_variables_when_resolving() {
+        let resolver = EnvResolver::new(EnvVars::new("RESOLVER_TEST_SHOW", "RESOLVER_TEST_SEQUENCE", "RESOLVER_TEST_SHOT"));
+        let ls = LevelSpec::new("..0001").unwrap();
+        env::set_var("RESOLVER_TEST_SHOW", "DEV01");
+        env::set_var("RESOLVER_TEST_SEQUENCE", "");
+        assert!(ls.rel_to_abs_with(&resolver).is_err());
+        env::set_var("RESOLVER_TEST_SEQUENCE", "RD");
+        assert_eq!(ls.rel_to_abs_with(&resolver), Ok(LevelSpec::from_shot("DEV01", "RD", "0001")));
+    }
+
+    #[test]
+    fn map_resolver_implies_parents() {
+        let resolver = MapResolver::new()
+            .with_levelspec(LevelSpec::from_shot("DEV01", "RD", "0002"))
+            .with_levelspec(LevelSpec::from_shot("DEV01", "RD", "0001"))
+            .with_levelspec(LevelSpec::from_show("TEST"));
+        assert_eq!(resolver.shows(), vec![LevelSpec::from_show("DEV01"), LevelSpec::from_show("TEST")]);
+        assert_eq!(resolver.children(&LevelSpec::from_show("DEV01")), vec![LevelSpec::from_sequence("DEV01", "RD")]);
+        assert_eq!(resolver.children(&LevelSpec::from_sequence("DEV01", "RD")), vec![
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+        ]);
+        assert!(resolver.children(&LevelSpec::from_show("TEST")).is_empty());
+    }
+
+    #[test]
+    fn map_resolver_skips_patterns() {
+        let mut resolver = MapResolver::new();
+        assert!(!resolver.insert(LevelSpec::new("DEV01.%").unwrap()));
+        assert!(!resolver.insert(LevelSpec::new(".RD").unwrap()));
+        assert!(resolver.shows().is_empty());
+    }
+
+    #[cfg(feature = "async")]
+    mod async_resolver {
+        use super::*;
+        use futures::executor::block_on;
+
+        struct Service(LevelTree);
+
+        #[async_trait]
+        impl AsyncLevelResolver for Service {
+            async fn resolve(&self, level: LevelName, _levelspec: &LevelSpec) -> Option<String> {
+                match level {
+                    LevelName::Show => Some("DEV01".to_string()),
+                    _ => None,
+                }
+            }
+            async fn shows(&self) -> Vec<LevelSpec> {
+                self.0.shows()
+            }
+            async fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+                self.0.children(parent)
+            }
+        }
+
+        #[test]
+        fn expansion_agrees_with_sync_expansion() {
+            let service = Service(tree());
+            for pattern in &["%", "DEV01.%", "%.RD", "%.%.0001", ".RD.%", "DEV01.RD.0003"] {
+                let pattern = LevelSpec::new(*pattern).unwrap();
+                let resolved = pattern.rel_to_abs(|level| block_on(service.resolve(level, &pattern))).unwrap();
+                assert_eq!(block_on(pattern.expand_async(&service)), Ok(service.0.expand(&resolved)));
+            }
+        }
+
+        #[test]
+        fn rel_to_abs_fails_when_resolver_lacks_level() {
+            let ls = LevelSpec::new("..0001").unwrap();
+            assert_eq!(
+                block_on(ls.rel_to_abs_async(&Service(tree()))),
+                Err(crate::LevelSpecterError::RelToAbsError("Unable to retrieve Sequence in rel_to_abs".to_string()))
+            );
+        }
+    }
+}
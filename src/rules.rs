@@ -0,0 +1,205 @@
+use crate::{LevelSpec, LevelName, LevelType, LevelSpecterError as LSE, schema};
+use failure::Fail;
+use regex::Regex;
+
+/// A single constraint on the value of a level
+#[derive(Debug, Clone)]
+enum Constraint {
+    Reserved(String),
+    BannedSubstring(String),
+    NumericRange { min: u64, max: u64 },
+    Regex(Regex),
+}
+
+/// A violation of a naming policy declared in Rules
+#[derive(Debug, Fail, PartialEq, Eq, Clone)]
+pub enum Violation {
+    #[fail(display = "{:?} '{}' is reserved", level, value)]
+    Reserved { level: LevelName, value: String },
+
+    #[fail(display = "{:?} '{}' contains banned substring '{}'", level, value, substring)]
+    BannedSubstring { level: LevelName, value: String, substring: String },
+
+    #[fail(display = "{:?} '{}' is not a number", level, value)]
+    NotNumeric { level: LevelName, value: String },
+
+    #[fail(display = "{:?} '{}' is outside the range {}..={}", level, value, min, max)]
+    OutOfRange { level: LevelName, value: String, min: u64, max: u64 },
+
+    #[fail(display = "{:?} '{}' does not match '{}'", level, value, pattern)]
+    RegexMismatch { level: LevelName, value: String, pattern: String },
+}
+
+/// Naming policy for LevelSpecs, declared as a list of constraints per level.
+/// Unlike a LevelSchema, which describes the shape of valid names, Rules 
+/// capture site policy such as reserved names and permitted shot ranges.
+/// Constraints apply to terms; wildcard and relative levels are not checked.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, LevelName, Rules};
+/// 
+/// let rules = Rules::new()
+///     .reserve(LevelName::Sequence, "TMP")
+///     .ban_substring(LevelName::Sequence, "TEST")
+///     .numeric_range(LevelName::Shot, 1, 9990)
+///     .require_regex(LevelName::Show, "^[A-Z]{3}[0-9]{2}$").unwrap();
+/// 
+/// assert!(rules.check(&LevelSpec::from_shot("DEV01", "RD", "0010")).is_empty());
+/// assert_eq!(rules.check(&LevelSpec::from_shot("DEVELOP", "TMP", "9999")).len(), 3);
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct Rules {
+    constraints: Vec<(LevelName, Constraint)>,
+}
+
+impl Rules {
+    /// New up an empty set of Rules
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve a name for the supplied level and return self. Reserved names
+    /// are compared case insensitively, as with `LevelRule::with_reserved`.
+    pub fn reserve<I>(mut self, level: LevelName, name: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.constraints.push((level, Constraint::Reserved(name.into())));
+        self
+    }
+
+    /// Ban a substring from the supplied level and return self. Substrings
+    /// are compared case insensitively.
+    pub fn ban_substring<I>(mut self, level: LevelName, substring: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.constraints.push((level, Constraint::BannedSubstring(substring.into())));
+        self
+    }
+
+    /// Require the supplied level to be a number within `min..=max` and return self
+    pub fn numeric_range(mut self, level: LevelName, min: u64, max: u64) -> Self {
+        self.constraints.push((level, Constraint::NumericRange { min, max }));
+        self
+    }
+
+    /// Require the supplied level to match a regular expression and return self.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `level` - The level to constrain
+    /// * `pattern` - The regular expression. Anchor it with `^` and `$` to 
+    ///   match the whole level.
+    /// 
+    /// # Returns
+    /// Self, or a RuleError if the regular expression is invalid
+    pub fn require_regex(mut self, level: LevelName, pattern: &str) -> Result<Self, LSE> {
+        let regex = Regex::new(pattern)
+            .map_err(|e| LSE::RuleError(format!("Invalid regex '{}': {}", pattern, e)))?;
+        self.constraints.push((level, Constraint::Regex(regex)));
+        Ok(self)
+    }
+
+    /// Check the LevelSpec against every rule
+    /// 
+    /// # Parameters
+    /// 
+    /// * `levelspec` - The LevelSpec to check
+    /// 
+    /// # Returns
+    /// Every violation found, in the order the rules were declared
+    pub fn check(&self, levelspec: &LevelSpec) -> Vec<Violation> {
+        let mut violations = Vec::new();
+        for (level, constraint) in &self.constraints {
+            let value = match level_term(levelspec, *level) {
+                Some(value) => value,
+                None => continue,
+            };
+            let level = *level;
+            let violation = match constraint {
+                Constraint::Reserved(name) if schema::is_reserved([name], value) => {
+                    Some(Violation::Reserved { level, value: value.to_string() })
+                },
+                Constraint::BannedSubstring(substring) 
+                    if value.to_ascii_uppercase().contains(&substring.to_ascii_uppercase()) => {
+                    Some(Violation::BannedSubstring { level, value: value.to_string(), substring: substring.clone() })
+                },
+                Constraint::NumericRange { min, max } => match value.parse::<u64>() {
+                    Err(_) => Some(Violation::NotNumeric { level, value: value.to_string() }),
+                    Ok(number) if number < *min || number > *max => {
+                        Some(Violation::OutOfRange { level, value: value.to_string(), min: *min, max: *max })
+                    },
+                    Ok(_) => None,
+                },
+                Constraint::Regex(regex) if !regex.is_match(value) => {
+                    Some(Violation::RegexMismatch { level, value: value.to_string(), pattern: regex.as_str().to_string() })
+                },
+                _ => None,
+            };
+            violations.extend(violation);
+        }
+        violations
+    }
+}
+
+// retrieve the term at the supplied level, if present
+fn level_term(levelspec: &LevelSpec, level: LevelName) -> Option<&str> {
+    let level_type = match level {
+        LevelName::Show => Some(levelspec.show()),
+        LevelName::Sequence => levelspec.sequence(),
+        LevelName::Shot => levelspec.shot(),
+    };
+    match level_type {
+        Some(LevelType::Term(val)) => Some(val),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reports_all_violations_in_order() {
+        let rules = Rules::new()
+            .numeric_range(LevelName::Shot, 1, 100)
+            .reserve(LevelName::Sequence, "tmp")
+            .ban_substring(LevelName::Show, "x");
+        let violations = rules.check(&LevelSpec::from_shot("XDEV", "TMP", "0101"));
+        assert_eq!(violations, vec![
+            Violation::OutOfRange { level: LevelName::Shot, value: "0101".to_string(), min: 1, max: 100 },
+            Violation::Reserved { level: LevelName::Sequence, value: "TMP".to_string() },
+            Violation::BannedSubstring { level: LevelName::Show, value: "XDEV".to_string(), substring: "x".to_string() },
+        ]);
+        assert_eq!(violations[0].to_string(), "Shot '0101' is outside the range 1..=100");
+    }
+
+    #[test]
+    fn non_numeric_shots_violate_ranges() {
+        let rules = Rules::new().numeric_range(LevelName::Shot, 1, 100);
+        assert_eq!(
+            rules.check(&LevelSpec::from_shot("DEV01", "ASSETDEV", "FOO")),
+            vec![Violation::NotNumeric { level: LevelName::Shot, value: "FOO".to_string() }]
+        );
+    }
+
+    #[test]
+    fn skips_missing_and_non_term_levels() {
+        let rules = Rules::new().numeric_range(LevelName::Shot, 1, 100).reserve(LevelName::Sequence, "TMP");
+        assert!(rules.check(&LevelSpec::from_show("DEV01")).is_empty());
+        assert!(rules.check(&LevelSpec::new("DEV01.%.%").unwrap()).is_empty());
+    }
+
+    #[test]
+    fn rejects_invalid_regex() {
+        assert!(Rules::new().require_regex(LevelName::Show, "(").is_err());
+        let rules = Rules::new().require_regex(LevelName::Show, "^DEV").unwrap();
+        assert_eq!(
+            rules.check(&LevelSpec::from_show("PROD01")),
+            vec![Violation::RegexMismatch { level: LevelName::Show, value: "PROD01".to_string(), pattern: "^DEV".to_string() }]
+        );
+    }
+}
@@ -0,0 +1,237 @@
+use crate::{LevelSpec, LevelName, LevelType, ReservedSequences, SequenceKind};
+use failure::Fail;
+
+/// The set of characters a level may contain
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Charset {
+    /// ascii digits
+    Digits,
+    /// ascii letters and digits
+    Alphanumeric,
+    /// uppercase ascii letters and digits
+    UpperAlphanumeric,
+    /// exactly the characters in the supplied string
+    Custom(String),
+}
+
+impl Charset {
+    /// Returns true if the character is a member of the charset
+    pub fn contains(&self, c: char) -> bool {
+        match self {
+            Charset::Digits => c.is_ascii_digit(),
+            Charset::Alphanumeric => c.is_ascii_alphanumeric(),
+            Charset::UpperAlphanumeric => c.is_ascii_uppercase() || c.is_ascii_digit(),
+            Charset::Custom(chars) => chars.contains(c),
+        }
+    }
+}
+
+/// Rules a single level must satisfy
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LevelRule {
+    pub charset: Charset,
+    pub min_length: usize,
+    pub max_length: Option<usize>,
+    /// Names which may not be used, compared case insensitively
+    pub reserved: Vec<String>,
+}
+
+impl LevelRule {
+    /// New up a LevelRule permitting names of any length made up of `charset`
+    pub fn new(charset: Charset) -> Self {
+        Self {
+            charset,
+            min_length: 1,
+            max_length: None,
+            reserved: Vec::new(),
+        }
+    }
+
+    /// Set the minimum and maximum length and return self
+    pub fn with_length(mut self, min_length: usize, max_length: Option<usize>) -> Self {
+        self.min_length = min_length;
+        self.max_length = max_length;
+        self
+    }
+
+    /// Add a reserved name and return self
+    pub fn with_reserved<I>(mut self, name: I) -> Self
+    where
+        I: Into<String>
+    {
+        self.reserved.push(name.into());
+        self
+    }
+
+    /// Check the supplied value against the rule, appending any errors to `errors`
+    pub fn check(&self, level: LevelName, value: &str, errors: &mut Vec<ValidationError>) {
+        if let Some(character) = value.chars().find(|c| !self.charset.contains(*c)) {
+            errors.push(ValidationError::InvalidCharacter { level, value: value.to_string(), character });
+        }
+        let length = value.chars().count();
+        if length < self.min_length {
+            errors.push(ValidationError::TooShort { level, value: value.to_string(), min: self.min_length });
+        }
+        if let Some(max) = self.max_length {
+            if length > max {
+                errors.push(ValidationError::TooLong { level, value: value.to_string(), max });
+            }
+        }
+        if is_reserved(&self.reserved, value) {
+            errors.push(ValidationError::Reserved { level, value: value.to_string() });
+        }
+    }
+}
+
+/// Returns true if the value is one of the reserved names, ignoring ASCII
+/// case. Shared by LevelRule and `Rules::reserve`.
+pub(crate) fn is_reserved<I>(names: I, value: &str) -> bool
+where
+    I: IntoIterator,
+    I::Item: AsRef<str>
+{
+    names.into_iter().any(|name| name.as_ref().eq_ignore_ascii_case(value))
+}
+
+/// A violation of a LevelSchema
+#[derive(Debug, Fail, PartialEq, Eq, Clone)]
+pub enum ValidationError {
+    #[fail(display = "{:?} '{}' contains invalid character '{}'", level, value, character)]
+    InvalidCharacter { level: LevelName, value: String, character: char },
+
+    #[fail(display = "{:?} '{}' is shorter than {} characters", level, value, min)]
+    TooShort { level: LevelName, value: String, min: usize },
+
+    #[fail(display = "{:?} '{}' is longer than {} characters", level, value, max)]
+    TooLong { level: LevelName, value: String, max: usize },
+
+    #[fail(display = "{:?} '{}' is reserved", level, value)]
+    Reserved { level: LevelName, value: String },
+}
+
+/// Site naming rules for each level of a LevelSpec. Unlike the parser, the
+/// schema may be applied to LevelSpecs built directly from their parts, such
+/// as via `from_shot`. Wildcard and relative levels are not checked.
+///
+/// The default schema mirrors the parser's character rules: uppercase
+/// alphanumeric shows and sequences (any case in case-insensitive builds),
+/// numeric shots, and alphanumeric shots within ASSETDEV.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, LevelSchema, LevelRule, Charset};
+///
+/// let mut schema = LevelSchema::default();
+/// schema.shot = LevelRule::new(Charset::Digits).with_length(4, Some(4));
+/// assert!(LevelSpec::from_shot("DEV01", "RD", "0001").validate(&schema).is_ok());
+/// assert!(LevelSpec::from_shot("DEV01", "RD", "1").validate(&schema).is_err());
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LevelSchema {
+    pub show: LevelRule,
+    pub sequence: LevelRule,
+    pub shot: LevelRule,
+    /// rule applied to shots within the ASSETDEV sequence and reserved sequences
+    pub assetdev_shot: LevelRule,
+    /// sequences whose shots are named rather than numbered
+    pub reserved_sequences: ReservedSequences,
+}
+
+impl LevelSchema {
+    /// Set the sequences whose shots are checked against `assetdev_shot`
+    /// and return self. See `ReservedSequences`.
+    pub fn with_reserved_sequences(mut self, reserved_sequences: ReservedSequences) -> Self {
+        self.reserved_sequences = reserved_sequences;
+        self
+    }
+
+    /// Check each term of the LevelSpec against the schema
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to validate
+    ///
+    /// # Returns
+    /// Ok if the LevelSpec satisfies the schema. Otherwise, every violation found.
+    pub fn validate(&self, levelspec: &LevelSpec) -> Result<(), Vec<ValidationError>> {
+        let mut errors = Vec::new();
+        if let LevelType::Term(ref show) = levelspec.show {
+            self.show.check(LevelName::Show, show, &mut errors);
+        }
+        if let Some(LevelType::Term(ref sequence)) = levelspec.sequence {
+            self.sequence.check(LevelName::Sequence, sequence, &mut errors);
+        }
+        if let Some(LevelType::Term(ref shot)) = levelspec.shot {
+            let named = matches!(levelspec.sequence_kind_with(&self.reserved_sequences), Some(SequenceKind::AssetDev) | Some(SequenceKind::Reserved));
+            let rule = if named {&self.assetdev_shot} else {&self.shot};
+            rule.check(LevelName::Shot, shot, &mut errors);
+        }
+        if errors.is_empty() {Ok(())} else {Err(errors)}
+    }
+}
+
+impl Default for LevelSchema {
+    fn default() -> Self {
+        let name_charset = if cfg!(feature = "case-insensitive") {
+            Charset::Alphanumeric
+        } else {
+            Charset::UpperAlphanumeric
+        };
+        Self {
+            show: LevelRule::new(name_charset.clone()),
+            sequence: LevelRule::new(name_charset.clone()),
+            shot: LevelRule::new(Charset::Digits),
+            assetdev_shot: LevelRule::new(name_charset),
+            reserved_sequences: ReservedSequences::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn default_schema_accepts_parsed_levelspecs() {
+        for spec in &["DEV01", "DEV01.RD", "DEV01.RD.0001", "DEV01.ASSETDEV.FOO", "%.%.%", ".RD."] {
+            let ls = LevelSpec::new(spec).unwrap();
+            assert_eq!(ls.validate(&LevelSchema::default()), Ok(()));
+        }
+    }
+
+    #[test]
+    fn reports_all_violations() {
+        let ls = LevelSpec::from_shot("DEV 01", "R_D", "00-1");
+        let errors = ls.validate(&LevelSchema::default()).unwrap_err();
+        assert_eq!(errors, vec![
+            ValidationError::InvalidCharacter { level: LevelName::Show, value: "DEV 01".to_string(), character: ' ' },
+            ValidationError::InvalidCharacter { level: LevelName::Sequence, value: "R_D".to_string(), character: '_' },
+            ValidationError::InvalidCharacter { level: LevelName::Shot, value: "00-1".to_string(), character: '-' },
+        ]);
+    }
+
+    #[test]
+    fn rejects_reserved_names() {
+        let mut schema = LevelSchema::default();
+        schema.sequence = schema.sequence.with_reserved("TMP");
+        let errors = LevelSpec::from_sequence("DEV01", "TMP").validate(&schema).unwrap_err();
+        assert_eq!(errors, vec![ValidationError::Reserved { level: LevelName::Sequence, value: "TMP".to_string() }]);
+        assert_eq!(errors[0].to_string(), "Sequence 'TMP' is reserved");
+    }
+
+    #[test]
+    fn enforces_length() {
+        let mut schema = LevelSchema::default();
+        schema.show = schema.show.with_length(3, Some(5));
+        assert!(LevelSpec::from_show("DEV01").validate(&schema).is_ok());
+        assert_eq!(
+            LevelSpec::from_show("DEVELOP").validate(&schema),
+            Err(vec![ValidationError::TooLong { level: LevelName::Show, value: "DEVELOP".to_string(), max: 5 }])
+        );
+        assert_eq!(
+            LevelSpec::from_show("DV").validate(&schema),
+            Err(vec![ValidationError::TooShort { level: LevelName::Show, value: "DV".to_string(), min: 3 }])
+        );
+    }
+}
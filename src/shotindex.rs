@@ -0,0 +1,242 @@
+use crate::{LevelSpec, LevelTree, PathTemplate, LevelSpecterError as LSE};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+// Number of consecutive directories which may fail to match a template, such
+// as literal directories between levels, before a scan stops descending
+const MAX_UNMATCHED_DEPTH: usize = 2;
+
+/// An in-memory index of concrete LevelSpecs, such as the shots on disk,
+/// supporting the queries needed by pickers and autocompletion. Parents of
+/// indexed LevelSpecs are implied, so indexing `DEV01.RD.0001` makes 
+/// `DEV01.RD` and `DEV01` exist as well.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, ShotIndex};
+/// 
+/// let index = ShotIndex::new(vec![
+///     LevelSpec::from_shot("DEV01", "RD", "0001"),
+///     LevelSpec::from_shot("DEV01", "RD", "0002"),
+///     LevelSpec::from_shot("DEV01", "RA", "0001"),
+/// ]);
+/// assert!(index.exists(&LevelSpec::from_sequence("DEV01", "RD")));
+/// assert_eq!(index.expand(&LevelSpec::new("DEV01.%.0001").unwrap()).len(), 2);
+/// assert_eq!(index.complete("DEV01.R"), vec![
+///     LevelSpec::from_sequence("DEV01", "RA"),
+///     LevelSpec::from_sequence("DEV01", "RD"),
+/// ]);
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ShotIndex {
+    tree: LevelTree,
+}
+
+impl ShotIndex {
+    /// New up a ShotIndex from the supplied LevelSpecs. LevelSpecs which
+    /// are not concrete, or which have relative levels, are skipped.
+    pub fn new<I>(levelspecs: I) -> Self
+    where
+        I: IntoIterator<Item = LevelSpec>
+    {
+        let mut index = Self::default();
+        for levelspec in levelspecs {
+            index.insert(levelspec);
+        }
+        index
+    }
+
+    /// New up a ShotIndex from the directories beneath `root` which match the
+    /// supplied template. Scanning descends through matching directories until
+    /// it reaches shots, and through at most two consecutive directories which
+    /// do not match, to allow for literal directories between levels.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `root` - The directory to scan
+    /// * `template` - The PathTemplate describing the layout beneath `root`
+    /// 
+    /// # Returns
+    /// A ShotIndex, or an IoError if `root` cannot be read. Unreadable 
+    /// directories beneath `root` are skipped.
+    pub fn scan<P>(root: P, template: &PathTemplate) -> Result<Self, LSE>
+    where
+        P: AsRef<Path>
+    {
+        let root = root.as_ref();
+        let entries = fs::read_dir(root)
+            .map_err(|e| LSE::IoError(format!("Unable to read {}: {}", root.display(), e)))?;
+        let mut index = Self::default();
+        index.scan_dirs(entries.flatten().map(|entry| entry.path()).collect(), template);
+        Ok(index)
+    }
+
+    // Index the directories matching the template among the supplied paths
+    // and beneath them, as `scan` does, returning the LevelSpecs added
+    pub(crate) fn scan_dirs(&mut self, dirs: Vec<PathBuf>, template: &PathTemplate) -> Vec<LevelSpec> {
+        let mut added = Vec::new();
+        let mut pending = dirs.into_iter().map(|dir| (dir, 0)).collect::<Vec<_>>();
+        while let Some((dir, unmatched)) = pending.pop() {
+            if !dir.is_dir() {
+                continue;
+            }
+            let unmatched = match template.match_path(&dir) {
+                Ok((levelspec, _)) => {
+                    let is_shot = levelspec.is_shot();
+                    if self.insert(levelspec.clone()) {
+                        added.push(levelspec);
+                    }
+                    if is_shot {
+                        continue;
+                    }
+                    0
+                },
+                Err(_) if unmatched < MAX_UNMATCHED_DEPTH => unmatched + 1,
+                Err(_) => continue,
+            };
+            if let Ok(entries) = fs::read_dir(&dir) {
+                pending.extend(entries.flatten().map(|entry| (entry.path(), unmatched)));
+            }
+        }
+        added
+    }
+
+    /// Add a LevelSpec to the index
+    /// 
+    /// # Returns
+    /// true if the LevelSpec was added. Non-concrete LevelSpecs, and those
+    /// with relative levels, are not added.
+    pub fn insert(&mut self, levelspec: LevelSpec) -> bool {
+        if !levelspec.is_concrete() || levelspec.has_relative() {
+            return false;
+        }
+        self.tree.insert(levelspec)
+    }
+
+    /// Remove a LevelSpec and everything beneath it from the index
+    /// 
+    /// # Returns
+    /// The indexed LevelSpecs which were removed
+    pub fn remove(&mut self, levelspec: &LevelSpec) -> Vec<LevelSpec> {
+        self.tree.remove_subtree(levelspec)
+    }
+
+    /// Returns true if the LevelSpec, or any LevelSpec beneath it, is indexed
+    pub fn exists(&self, levelspec: &LevelSpec) -> bool {
+        self.tree.has_node(levelspec)
+    }
+
+    /// Retrieve the indexed shows, in order
+    pub fn shows(&self) -> Vec<LevelSpec> {
+        self.tree.shows()
+    }
+
+    /// Retrieve the children of the supplied LevelSpec, in order
+    pub fn children(&self, parent: &LevelSpec) -> Vec<LevelSpec> {
+        self.tree.children(parent)
+    }
+
+    /// Retrieve the existing LevelSpecs matching the supplied pattern, in order
+    pub fn expand(&self, pattern: &LevelSpec) -> Vec<LevelSpec> {
+        self.tree.expand(pattern)
+    }
+
+    /// Complete a partially typed levelspec. The text after the last `.` is
+    /// treated as the prefix of a child of the LevelSpec before it. Prefixes
+    /// are compared case insensitively.
+    /// 
+    /// # Parameters
+    /// 
+    /// * `partial` - The partial input, eg `DEV01.R` or `DEV01.RD.`
+    /// 
+    /// # Returns
+    /// The matching LevelSpecs, in order. The Vec is empty if the text before
+    /// the last `.` is not an existing LevelSpec.
+    pub fn complete(&self, partial: &str) -> Vec<LevelSpec> {
+        let (candidates, prefix) = match partial.rfind('.') {
+            Some(idx) => match LevelSpec::new(&partial[..idx]) {
+                Ok(parent) if parent.levels() < 3 => (self.tree.children(&parent), &partial[idx + 1..]),
+                _ => return Vec::new(),
+            },
+            None => (self.tree.shows(), partial),
+        };
+        let prefix = prefix.to_ascii_uppercase();
+        candidates.into_iter()
+            .filter(|candidate| {
+                let levels = candidate.to_vec_str();
+                levels[levels.len() - 1].to_str().to_ascii_uppercase().starts_with(&prefix)
+            })
+            .collect()
+    }
+
+    /// Retrieve the number of LevelSpecs indexed
+    pub fn len(&self) -> usize {
+        self.tree.len()
+    }
+
+    /// Returns true if nothing is indexed
+    pub fn is_empty(&self) -> bool {
+        self.tree.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn index() -> ShotIndex {
+        ShotIndex::new(vec![
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0010"),
+            LevelSpec::from_shot("DEV02", "AA", "0001"),
+            LevelSpec::new("DEV01.%.0001").unwrap(),
+        ])
+    }
+
+    #[test]
+    fn skips_non_concrete_specs() {
+        assert_eq!(index().len(), 3);
+        assert!(!index().exists(&LevelSpec::new("DEV01.%").unwrap()));
+    }
+
+    #[test]
+    fn completes_each_level() {
+        let index = index();
+        assert_eq!(index.complete("DEV"), vec![LevelSpec::from_show("DEV01"), LevelSpec::from_show("DEV02")]);
+        assert_eq!(index.complete("DEV01.RD.001"), vec![LevelSpec::from_shot("DEV01", "RD", "0010")]);
+        assert_eq!(index.complete("DEV01.RD.").len(), 2);
+        assert!(index.complete("DEV03.").is_empty());
+        assert!(index.complete("DEV01.RD.0001.").is_empty());
+    }
+
+    #[test]
+    fn scans_matching_directories() {
+        let root = tempfile::tempdir().unwrap();
+        for dir in &["DEV01/shots/RD/0001", "DEV01/shots/RD/0002/work", "DEV01/shots/AA", "DEV01/docs/notes", "bad_show/shots/RD"] {
+            fs::create_dir_all(root.path().join(dir)).unwrap();
+        }
+        fs::write(root.path().join("DEV01/shots/RD/0003"), "not a directory").unwrap();
+        let template = PathTemplate::new("{root}/{show}/shots/{sequence}/{shot}").unwrap().with_root(root.path());
+        let index = ShotIndex::scan(root.path(), &template).unwrap();
+        assert_eq!(index.expand(&LevelSpec::new("%.%.%").unwrap()), vec![
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ]);
+        assert_eq!(index.children(&LevelSpec::from_show("DEV01")).len(), 2);
+        assert_eq!(index.len(), 5);
+        assert!(ShotIndex::scan(root.path().join("missing"), &template).is_err());
+    }
+
+    #[test]
+    fn removes_subtrees() {
+        let mut index = index();
+        assert_eq!(index.remove(&LevelSpec::from_show("DEV02")), vec![LevelSpec::from_shot("DEV02", "AA", "0001")]);
+        assert!(!index.exists(&LevelSpec::from_show("DEV02")));
+    }
+
+    #[test]
+    fn lists_children() {
+        assert_eq!(index().children(&LevelSpec::from_show("DEV02")), vec![LevelSpec::from_sequence("DEV02", "AA")]);
+    }
+}
@@ -0,0 +1,110 @@
+use crate::{LevelSpec, LevelType, LevelSpecterError as LSE};
+use std::borrow::Cow;
+
+/// An iterator over a range of shot level LevelSpecs within a sequence.
+/// The range includes both `start` and `end`, and shots are zero padded 
+/// to `padding` digits.
+/// 
+/// # Example
+/// 
+/// ```
+/// use levelspecter::{LevelSpec, ShotRange};
+/// 
+/// let sequence = LevelSpec::from_sequence("DEV01", "RD");
+/// let shots = ShotRange::new(&sequence, 10, 100, 10, 4).unwrap().collect::<Vec<_>>();
+/// assert_eq!(shots.len(), 10);
+/// assert_eq!(shots[0], LevelSpec::from_shot("DEV01", "RD", "0010"));
+/// assert_eq!(shots[9], LevelSpec::from_shot("DEV01", "RD", "0100"));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct ShotRange {
+    sequence: LevelSpec,
+    next: Option<u32>,
+    end: u32,
+    step: u32,
+    padding: usize,
+}
+
+impl ShotRange {
+    /// New up a ShotRange
+    /// 
+    /// # Parameters
+    /// 
+    /// * `sequence` - A sequence level LevelSpec, whose shots we generate
+    /// * `start` - The first shot number
+    /// * `end` - The last shot number, included if reached by `step`
+    /// * `step` - The difference between consecutive shot numbers
+    /// * `padding` - The minimum number of digits in each shot
+    /// 
+    /// # Returns
+    /// A ShotRange if `sequence` is sequence level and `step` is non zero. 
+    /// Otherwise, a ShotError.
+    pub fn new(sequence: &LevelSpec, start: u32, end: u32, step: u32, padding: usize) -> Result<Self, LSE> {
+        if !sequence.is_sequence() {
+            return Err(LSE::ShotError(format!("'{}' is not a sequence", sequence)));
+        }
+        if step == 0 {
+            return Err(LSE::ShotError("ShotRange step must be greater than 0".to_string()));
+        }
+        Ok(Self {
+            sequence: sequence.clone(),
+            next: if start <= end {Some(start)} else {None},
+            end,
+            step,
+            padding,
+        })
+    }
+}
+
+impl Iterator for ShotRange {
+    type Item = LevelSpec;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let current = self.next?;
+        self.next = current.checked_add(self.step).filter(|next| *next <= self.end);
+        let shot = format!("{:0>width$}", current, width = self.padding);
+        Some(LevelSpec {
+            shot: Some(LevelType::Term(Cow::Owned(shot))),
+            ..self.sequence.clone()
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.next.map_or(0, |next| ((self.end - next) / self.step) as usize + 1);
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for ShotRange {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn includes_end_when_reached() {
+        let sequence = LevelSpec::from_sequence("DEV01", "RD");
+        let shots = ShotRange::new(&sequence, 1, 5, 2, 4).unwrap().map(|ls| ls.to_string()).collect::<Vec<_>>();
+        assert_eq!(shots, vec!["DEV01.RD.0001", "DEV01.RD.0003", "DEV01.RD.0005"]);
+        assert_eq!(ShotRange::new(&sequence, 1, 6, 2, 4).unwrap().len(), 3);
+    }
+
+    #[test]
+    fn empty_when_start_after_end() {
+        let sequence = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(ShotRange::new(&sequence, 5, 1, 1, 4).unwrap().count(), 0);
+    }
+
+    #[test]
+    fn does_not_overflow() {
+        let sequence = LevelSpec::from_sequence("DEV01", "RD");
+        assert_eq!(ShotRange::new(&sequence, u32::MAX - 1, u32::MAX, 5, 0).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn rejects_bad_input() {
+        assert!(ShotRange::new(&LevelSpec::from_show("DEV01"), 1, 5, 1, 4).is_err());
+        assert!(ShotRange::new(&LevelSpec::from_shot("DEV01", "RD", "0001"), 1, 5, 1, 4).is_err());
+        assert!(ShotRange::new(&LevelSpec::from_sequence("DEV01", "RD"), 1, 5, 0, 4).is_err());
+    }
+}
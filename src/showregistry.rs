@@ -0,0 +1,141 @@
+use crate::{LevelSpec, LevelSpecterError as LSE, LevelType, aliases};
+use std::collections::HashMap;
+use std::fmt;
+
+/// Maps show codes, eg `DEV01`, onto their long names, eg `Development`,
+/// for reports which need human readable show titles. The registry also
+/// lists the shows which exist, so that parsed LevelSpecs may be checked
+/// against it. A code which differs from a registered one only in case, eg
+/// `dev01`, names the same show, so registering a code again in another case
+/// replaces the earlier registration.
+///
+/// # Example
+///
+/// ```
+/// use levelspecter::{LevelSpec, ShowRegistry, LevelSpecterError};
+///
+/// let registry = ShowRegistry::default().with_show("DEV01", "Development");
+/// let ls = registry.parse("DEV01.RD.0001").unwrap();
+/// assert_eq!(ls.display_long(&registry).to_string(), "Development RD.0001");
+/// assert_eq!(registry.parse("PRD01.RD"), Err(LevelSpecterError::UnknownShow("PRD01".to_string())));
+/// ```
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct ShowRegistry {
+    /// Map of show code to long name
+    pub shows: HashMap<String, String>,
+}
+
+impl ShowRegistry {
+    /// Register a show's code and long name and return self
+    pub fn with_show<I>(mut self, code: I, name: I) -> Self
+    where
+        I: Into<String>
+    {
+        aliases::insert(&mut self.shows, code.into(), name.into());
+        self
+    }
+
+    /// Returns true if no show is registered
+    pub fn is_empty(&self) -> bool {
+        self.shows.is_empty()
+    }
+
+    /// Returns true if the show code is registered
+    pub fn contains(&self, code: &str) -> bool {
+        self.name(code).is_some()
+    }
+
+    /// Retrieve the long name of the show code, if it is registered
+    pub fn name(&self, code: &str) -> Option<&str> {
+        aliases::lookup(&self.shows, code).map(String::as_str)
+    }
+
+    /// Check that the show of the supplied LevelSpec is registered. Wildcard
+    /// and relative shows are accepted, as they name no particular show.
+    ///
+    /// # Parameters
+    ///
+    /// * `levelspec` - The LevelSpec to check
+    ///
+    /// # Returns
+    /// Ok, or an UnknownShow error naming the unregistered show
+    pub fn validate(&self, levelspec: &LevelSpec) -> Result<(), LSE> {
+        match &levelspec.show {
+            LevelType::Term(show) if !self.contains(show) => Err(LSE::UnknownShow(show.to_string())),
+            _ => Ok(()),
+        }
+    }
+
+    /// Parse the input into a LevelSpec whose show is registered
+    ///
+    /// # Parameters
+    ///
+    /// * `input` - The str to parse
+    ///
+    /// # Returns
+    /// A LevelSpec, a ParseError if the input is not a legal levelspec, or an
+    /// UnknownShow error if its show is not registered
+    pub fn parse(&self, input: &str) -> Result<LevelSpec, LSE> {
+        let levelspec = LevelSpec::new(input)?;
+        self.validate(&levelspec)?;
+        Ok(levelspec)
+    }
+}
+
+/// Display adapter returned by `LevelSpec::display_long`
+#[derive(Debug, Clone, Copy)]
+pub struct LongDisplay<'a> {
+    pub(crate) levelspec: &'a LevelSpec,
+    pub(crate) registry: &'a ShowRegistry,
+}
+
+impl<'a> fmt::Display for LongDisplay<'a> {
+    /// Renders the show's long name, followed by the remaining levels in
+    /// dotted form. Unregistered, wildcard, and relative shows are rendered
+    /// as they are.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let LevelSpec { show, sequence, shot } = self.levelspec;
+        match show {
+            LevelType::Term(code) => write!(f, "{}", self.registry.name(code).unwrap_or(code))?,
+            _ => write!(f, "{}", show)?,
+        }
+        match (sequence, shot) {
+            (Some(sequence), Some(shot)) => write!(f, " {}.{}", sequence, shot),
+            (None, Some(shot)) => write!(f, " .{}", shot),
+            (Some(sequence), None) => write!(f, " {}", sequence),
+            (None, None) => Ok(()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::str::FromStr;
+
+    #[test]
+    fn validates_shows() {
+        let registry = ShowRegistry::default().with_show("DEV01", "Development");
+        assert_eq!(registry.name("dev01"), Some("Development"));
+        let registry = registry.with_show("dev01", "Dev");
+        assert_eq!(registry.shows.len(), 1);
+        assert_eq!(registry.name("DEV01"), Some("Dev"));
+        assert!(registry.validate(&LevelSpec::from_str("%.RD").unwrap()).is_ok());
+        assert!(registry.validate(&LevelSpec::from_str(".RD").unwrap()).is_ok());
+        assert_eq!(
+            registry.validate(&LevelSpec::from_show("PRD01")),
+            Err(LSE::UnknownShow("PRD01".to_string()))
+        );
+        assert!(registry.parse("bad_line").unwrap_err().to_string().starts_with("Parse Error"));
+    }
+
+    #[test]
+    fn displays_long_names() {
+        let registry = ShowRegistry::default().with_show("DEV01", "Development");
+        let display = |input: &str| LevelSpec::from_str(input).unwrap().display_long(&registry).to_string();
+        assert_eq!(display("DEV01"), "Development");
+        assert_eq!(display("DEV01.RD"), "Development RD");
+        assert_eq!(display("PRD01.RD.0001"), "PRD01 RD.0001");
+        assert_eq!(display("%.RD"), "% RD");
+    }
+}
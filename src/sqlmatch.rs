@@ -0,0 +1,59 @@
+//! diesel helpers for querying Text columns which hold levelspecs. Available
+//! with the `diesel` feature.
+use crate::{LevelSpec, LevelSpecterError as LSE};
+use diesel::dsl;
+use diesel::expression::Expression;
+use diesel::sql_types::Text;
+use diesel::{BoolExpressionMethods, EscapeExpressionMethods, TextExpressionMethods};
+
+/// Build a filter matching the rows whose levelspec column is matched by
+/// `pattern`, as `LevelSpec::matches` would. This expands to
+/// `column LIKE <pattern> AND column NOT LIKE <deeper>`, where the LIKE
+/// pattern comes from `LevelSpec::to_sql_like`, and the NOT LIKE excludes
+/// rows with more levels than the pattern, which a SQL `%` would otherwise
+/// match across. Both declare `\` as their escape character, so that the
+/// `%` and `_` escaped within terms match literally whatever the database's
+/// default.
+///
+/// # Parameters
+///
+/// * `column` - The Text column, or expression, holding levelspecs
+/// * `pattern` - The LevelSpec to match rows against
+///
+/// # Returns
+/// The filter expression, or a RelToAbsError if `pattern` has relative levels
+///
+/// # Example
+///
+/// ```ignore
+/// use levelspecter::{LevelSpec, sqlmatch::matches_spec};
+///
+/// let pattern = LevelSpec::new("DEV01.%.0001")?;
+/// let shots = shots::table
+///     .filter(matches_spec(shots::levelspec, &pattern)?)
+///     .load::<Shot>(&mut conn)?;
+/// ```
+pub fn matches_spec<C>(column: C, pattern: &LevelSpec) -> Result<dsl::And<dsl::Escape<dsl::Like<C, String>>, dsl::Escape<dsl::NotLike<C, String>>>, LSE>
+where
+    C: Expression<SqlType = Text> + TextExpressionMethods + Clone
+{
+    let like = pattern.to_sql_like()?;
+    let deeper = format!("%{}", ".%".repeat(pattern.levels()));
+    Ok(column.clone().like(like).escape('\\').and(column.not_like(deeper).escape('\\')))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use diesel::dsl::sql;
+    use diesel::pg::Pg;
+
+    #[test]
+    fn escapes_underscores_within_terms() {
+        let pattern = LevelSpec::from_shot("DEV01", "R_D", "%");
+        let filter = matches_spec(sql::<Text>("levelspec"), &pattern).unwrap();
+        let query = diesel::debug_query::<Pg, _>(&filter).to_string();
+        assert_eq!(query.matches(" ESCAPE ").count(), 2, "{}", query);
+        assert!(query.contains(r#""DEV01.R\\_D._%""#), "{}", query);
+    }
+}
@@ -0,0 +1,110 @@
+//! The `levelspec://` URI representation, eg `levelspec://DEV01/RD/0001?version=3`,
+//! for embedding LevelSpecs in hyperlinks. Each level is a path segment, with
+//! the show as the authority, and `%` and `?` are percent encoded.
+use crate::{LevelSpec, LevelSpecterError as LSE, ParseFailure};
+use std::collections::HashMap;
+use std::str::FromStr;
+
+/// Scheme of LevelSpec URIs
+pub const URI_SCHEME: &str = "levelspec";
+
+/// Render a LevelSpec as a URI with the supplied query parameters, which
+/// are written in order.
+pub(crate) fn to_uri(levelspec: &LevelSpec, query: &[(&str, &str)]) -> String {
+    let path = levelspec.to_vec_str().iter()
+        .map(|level| encode(level.to_str()))
+        .collect::<Vec<_>>()
+        .join("/");
+    let mut uri = format!("{}://{}", URI_SCHEME, path);
+    for (idx, (key, value)) in query.iter().enumerate() {
+        uri.push(if idx == 0 { '?' } else { '&' });
+        uri.push_str(&encode(key));
+        uri.push('=');
+        uri.push_str(&encode(value));
+    }
+    uri
+}
+
+/// Parse a URI into a LevelSpec and its query parameters
+pub(crate) fn from_uri(uri: &str) -> Result<(LevelSpec, HashMap<String, String>), LSE> {
+    let prefix = format!("{}://", URI_SCHEME);
+    let rest = uri.get(..prefix.len())
+        .filter(|scheme| scheme.eq_ignore_ascii_case(&prefix))
+        .map(|_| &uri[prefix.len()..])
+        .ok_or_else(|| LSE::ParseError(ParseFailure::Message(format!("'{}' is not a {} URI", uri, URI_SCHEME))))?;
+    let (path, query) = match rest.find('?') {
+        Some(idx) => (&rest[..idx], Some(&rest[idx + 1..])),
+        None => (rest, None),
+    };
+    let path = path.strip_suffix('/').unwrap_or(path);
+    let levels = path.split('/').map(decode).collect::<Result<Vec<_>, _>>()?;
+    let levelspec = LevelSpec::from_str(&levels.join("."))?;
+    let mut params = HashMap::new();
+    for pair in query.into_iter().flat_map(|query| query.split('&')).filter(|pair| !pair.is_empty()) {
+        let (key, value) = match pair.find('=') {
+            Some(idx) => (&pair[..idx], &pair[idx + 1..]),
+            None => (pair, ""),
+        };
+        params.insert(decode(key)?, decode(value)?);
+    }
+    Ok((levelspec, params))
+}
+
+// Percent encode everything outside of the unreserved characters
+fn encode(input: &str) -> String {
+    input.bytes().fold(String::new(), |mut out, byte| {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            out.push(byte as char);
+        } else {
+            out.push_str(&format!("%{:02X}", byte));
+        }
+        out
+    })
+}
+
+fn decode(input: &str) -> Result<String, LSE> {
+    let invalid = || LSE::ParseError(ParseFailure::Message(format!("Invalid percent encoding in '{}'", input)));
+    let bytes = input.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut idx = 0;
+    while idx < bytes.len() {
+        if bytes[idx] == b'%' {
+            let hex = input.get(idx + 1..idx + 3).ok_or_else(invalid)?;
+            out.push(u8::from_str_radix(hex, 16).map_err(|_| invalid())?);
+            idx += 3;
+        } else {
+            out.push(bytes[idx]);
+            idx += 1;
+        }
+    }
+    String::from_utf8(out).map_err(|_| invalid())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn can_round_trip_wildcards() {
+        let ls = LevelSpec::from_str("DEV01.%.%").unwrap();
+        let uri = to_uri(&ls, &[]);
+        assert_eq!(uri, "levelspec://DEV01/%25/%25");
+        assert_eq!(from_uri(&uri).unwrap().0, ls);
+    }
+
+    #[test]
+    fn can_parse_query_parameters() {
+        let (ls, params) = from_uri("levelspec://DEV01/RD/0001/?version=3&note=a%20b&flag").unwrap();
+        assert_eq!(ls, LevelSpec::from_shot("DEV01", "RD", "0001"));
+        assert_eq!(params.get("version").map(String::as_str), Some("3"));
+        assert_eq!(params.get("note").map(String::as_str), Some("a b"));
+        assert_eq!(params.get("flag").map(String::as_str), Some(""));
+    }
+
+    #[test]
+    fn rejects_bad_uris() {
+        assert!(from_uri("http://DEV01/RD").is_err());
+        assert!(from_uri("levelspec://DEV01/R%2").is_err());
+        assert!(from_uri("levelspec://bad_line").is_err());
+    }
+}
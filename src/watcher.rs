@@ -0,0 +1,148 @@
+use crate::{LevelSpec, LevelSpecterError as LSE, PathTemplate, ShotIndex};
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use notify::event::{ModifyKind, RenameMode};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// A change to a LiveShotIndex
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum IndexEvent {
+    /// A directory matching the template was created
+    Added(LevelSpec),
+    /// A LevelSpec was removed from the index, along with its descendants
+    Removed(LevelSpec),
+}
+
+/// A ShotIndex built from disk and kept current by a filesystem watcher. 
+/// As directories matching the template are created and removed, the index
+/// is updated and an IndexEvent is sent for each affected LevelSpec. 
+/// Available with the `watch` feature.
+/// 
+/// The watcher stops when the LiveShotIndex is dropped.
+pub struct LiveShotIndex {
+    index: Arc<Mutex<ShotIndex>>,
+    events: Receiver<IndexEvent>,
+    _watcher: RecommendedWatcher,
+}
+
+impl LiveShotIndex {
+    /// Scan `root` as per `ShotIndex::scan` and watch it for changes
+    /// 
+    /// # Parameters
+    /// 
+    /// * `root` - The directory to scan and watch
+    /// * `template` - The PathTemplate describing the layout beneath `root`
+    /// 
+    /// # Returns
+    /// A LiveShotIndex, an IoError if `root` cannot be scanned, or a 
+    /// WatchError if it cannot be watched
+    pub fn watch<P>(root: P, template: PathTemplate) -> Result<Self, LSE>
+    where
+        P: AsRef<Path>
+    {
+        let root = root.as_ref();
+        let index = Arc::new(Mutex::new(ShotIndex::scan(root, &template)?));
+        let (sender, events) = channel();
+        let handler_index = index.clone();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            if let Ok(event) = event {
+                handle_event(event, &template, &handler_index, &sender);
+            }
+        }).map_err(|e| LSE::WatchError(format!("Unable to create watcher: {}", e)))?;
+        watcher.watch(root, RecursiveMode::Recursive)
+            .map_err(|e| LSE::WatchError(format!("Unable to watch {}: {}", root.display(), e)))?;
+        Ok(Self { index, events, _watcher: watcher })
+    }
+
+    /// Lock and retrieve the current index
+    pub fn index(&self) -> MutexGuard<'_, ShotIndex> {
+        self.index.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    /// Retrieve the receiver of IndexEvents
+    pub fn events(&self) -> &Receiver<IndexEvent> {
+        &self.events
+    }
+}
+
+// apply a filesystem event to the index, reporting the changes
+fn handle_event(event: Event, template: &PathTemplate, index: &Mutex<ShotIndex>, sender: &Sender<IndexEvent>) {
+    let (added, removed): (Vec<&PathBuf>, Vec<&PathBuf>) = match event.kind {
+        EventKind::Create(_) => (event.paths.iter().collect(), Vec::new()),
+        EventKind::Remove(_) => (Vec::new(), event.paths.iter().collect()),
+        EventKind::Modify(ModifyKind::Name(RenameMode::From)) => (Vec::new(), event.paths.iter().collect()),
+        EventKind::Modify(ModifyKind::Name(RenameMode::To)) => (event.paths.iter().collect(), Vec::new()),
+        EventKind::Modify(ModifyKind::Name(RenameMode::Both)) if event.paths.len() == 2 => {
+            (vec![&event.paths[1]], vec![&event.paths[0]])
+        },
+        _ => return,
+    };
+    let mut index = index.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+    for path in removed {
+        if let Ok((levelspec, _)) = template.match_path(path) {
+            for levelspec in index.remove(&levelspec) {
+                let _ = sender.send(IndexEvent::Removed(levelspec));
+            }
+        }
+    }
+    // directories renamed into place arrive populated, and those created
+    // beneath new directories may predate their watches, so new directories
+    // are scanned as the initial index was
+    let added = added.into_iter().cloned().collect();
+    for levelspec in index.scan_dirs(added, template) {
+        let _ = sender.send(IndexEvent::Added(levelspec));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::time::Duration;
+
+    #[test]
+    fn reports_created_and_removed_directories() {
+        let root = tempfile::tempdir().unwrap();
+        fs::create_dir_all(root.path().join("DEV01/RD/0001")).unwrap();
+        let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap().with_root(root.path());
+        let live = LiveShotIndex::watch(root.path(), template).unwrap();
+        assert_eq!(live.index().len(), 3);
+
+        fs::create_dir(root.path().join("DEV01/RD/0002")).unwrap();
+        let event = live.events().recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event, IndexEvent::Added(LevelSpec::from_shot("DEV01", "RD", "0002")));
+        assert!(live.index().exists(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+
+        fs::remove_dir(root.path().join("DEV01/RD/0002")).unwrap();
+        let event = live.events().recv_timeout(Duration::from_secs(5)).unwrap();
+        assert_eq!(event, IndexEvent::Removed(LevelSpec::from_shot("DEV01", "RD", "0002")));
+    }
+
+    #[test]
+    fn indexes_trees_renamed_into_place() {
+        let root = tempfile::tempdir().unwrap();
+        let staging = tempfile::tempdir().unwrap();
+        fs::create_dir(root.path().join("DEV01")).unwrap();
+        fs::create_dir_all(staging.path().join("RD/0001")).unwrap();
+        fs::create_dir_all(staging.path().join("RD/0002")).unwrap();
+        let template = PathTemplate::new("{root}/{show}/{sequence}/{shot}").unwrap().with_root(root.path());
+        let live = LiveShotIndex::watch(root.path(), template).unwrap();
+        assert_eq!(live.index().len(), 1);
+
+        fs::rename(staging.path().join("RD"), root.path().join("DEV01/RD")).unwrap();
+        let mut added = (0..3)
+            .map(|_| match live.events().recv_timeout(Duration::from_secs(5)).unwrap() {
+                IndexEvent::Added(levelspec) => levelspec,
+                event => panic!("unexpected {:?}", event),
+            })
+            .collect::<Vec<_>>();
+        added.sort_by_key(|levelspec| levelspec.to_string());
+        assert_eq!(added, vec![
+            LevelSpec::from_sequence("DEV01", "RD"),
+            LevelSpec::from_shot("DEV01", "RD", "0001"),
+            LevelSpec::from_shot("DEV01", "RD", "0002"),
+        ]);
+        assert!(live.index().exists(&LevelSpec::from_shot("DEV01", "RD", "0002")));
+    }
+}